@@ -0,0 +1,7 @@
+-- Add migration script here
+
+ALTER TABLE transfers
+    ADD COLUMN suspension_reason TEXT,
+    ADD COLUMN suspended_at TIMESTAMPTZ,
+    ADD COLUMN termination_reason TEXT,
+    ADD COLUMN terminated_at TIMESTAMPTZ;
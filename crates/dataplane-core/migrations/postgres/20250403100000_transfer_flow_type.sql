@@ -0,0 +1,5 @@
+-- Add migration script here
+
+ALTER TABLE transfers
+    ADD COLUMN flow_type TEXT NOT NULL DEFAULT 'PULL',
+    ADD COLUMN destination JSONB;
@@ -0,0 +1,3 @@
+-- Consumer transfers only know their source once the provider started the flow
+
+ALTER TABLE transfers ALTER COLUMN source DROP NOT NULL;
@@ -0,0 +1,5 @@
+-- Distinguishes the consumer transfers, created by a prepare message, from the provider ones
+
+ALTER TABLE transfers ADD COLUMN consumer BOOLEAN NOT NULL DEFAULT FALSE;
+
+UPDATE transfers SET consumer = TRUE WHERE status = 'Prepared' OR source IS NULL;
@@ -0,0 +1,3 @@
+-- Transfers stored before the type was recorded are HttpData ones of their flow type
+
+UPDATE transfers SET transfer_type = 'HttpData-' || flow_type WHERE transfer_type IS NULL;
@@ -0,0 +1,6 @@
+-- Add migration script here
+
+ALTER TABLE transfers ADD COLUMN suspension_reason TEXT;
+ALTER TABLE transfers ADD COLUMN suspended_at TIMESTAMP;
+ALTER TABLE transfers ADD COLUMN termination_reason TEXT;
+ALTER TABLE transfers ADD COLUMN terminated_at TIMESTAMP;
@@ -0,0 +1,4 @@
+-- Add migration script here
+
+ALTER TABLE transfers ADD COLUMN flow_type TEXT NOT NULL DEFAULT 'PULL';
+ALTER TABLE transfers ADD COLUMN destination TEXT;
@@ -0,0 +1,19 @@
+-- Add migration script here
+
+ALTER TABLE transfers ADD COLUMN callback_address TEXT;
+
+CREATE TABLE IF NOT EXISTS notifications (
+    id TEXT PRIMARY KEY,
+    transfer_id TEXT NOT NULL,
+    callback_address TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    reason TEXT,
+    status TEXT NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    next_attempt_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS notifications_due ON notifications (status, next_attempt_at);
@@ -0,0 +1,29 @@
+-- Consumer transfers only know their source once the provider started the flow
+
+CREATE TABLE transfers_new (
+    id TEXT PRIMARY KEY,
+    status TEXT NOT NULL,
+    source TEXT,
+    participant_id TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL,
+    suspension_reason TEXT,
+    suspended_at TIMESTAMP,
+    termination_reason TEXT,
+    terminated_at TIMESTAMP,
+    flow_type TEXT NOT NULL DEFAULT 'PULL',
+    destination TEXT,
+    callback_address TEXT
+);
+
+INSERT INTO transfers_new (id, status, source, participant_id, created_at, updated_at,
+    suspension_reason, suspended_at, termination_reason, terminated_at,
+    flow_type, destination, callback_address)
+SELECT id, status, source, participant_id, created_at, updated_at,
+    suspension_reason, suspended_at, termination_reason, terminated_at,
+    flow_type, destination, callback_address
+FROM transfers;
+
+DROP TABLE transfers;
+
+ALTER TABLE transfers_new RENAME TO transfers;
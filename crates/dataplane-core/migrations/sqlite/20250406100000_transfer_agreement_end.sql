@@ -0,0 +1,3 @@
+-- End of the contract agreement, past it the transfer is terminated by the reaper
+
+ALTER TABLE transfers ADD COLUMN agreement_end TIMESTAMP;
@@ -0,0 +1,3 @@
+-- Type of the transfer such as HttpData-PULL, routes the transfer to its manager
+
+ALTER TABLE transfers ADD COLUMN transfer_type TEXT;
@@ -0,0 +1,7 @@
+-- Agreement, dataset and properties of the start message, empty for older transfers
+
+ALTER TABLE transfers ADD COLUMN agreement_id TEXT;
+ALTER TABLE transfers ADD COLUMN dataset_id TEXT;
+ALTER TABLE transfers ADD COLUMN properties TEXT;
+
+CREATE INDEX IF NOT EXISTS transfers_agreement ON transfers (agreement_id);
@@ -1,2 +1,5 @@
+pub mod memory;
+pub mod notification;
+pub mod postgres;
 pub mod sqlite;
 pub mod transfer;
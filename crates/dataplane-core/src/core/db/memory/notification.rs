@@ -0,0 +1,103 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+
+use crate::core::{
+    db::notification::NotificationRepo,
+    model::notification::{Notification, NotificationStatus},
+};
+
+/// Outbox of [`super::transfer::InMemoryTransferRepo`], shared through
+/// [`InMemoryTransferRepo::notifications`](super::transfer::InMemoryTransferRepo::notifications)
+#[derive(Clone, Default)]
+pub struct InMemoryNotificationRepo {
+    notifications: Arc<DashMap<String, Notification>>,
+}
+
+impl InMemoryNotificationRepo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(super) fn insert(&self, notification: Notification) {
+        self.notifications
+            .insert(notification.id.clone(), notification);
+    }
+
+    pub(super) fn remove_by_transfer(&self, transfer_id: &str) {
+        self.notifications
+            .retain(|_, notification| notification.transfer_id != transfer_id);
+    }
+
+    fn filter(&self, predicate: impl Fn(&Notification) -> bool) -> Vec<Notification> {
+        self.notifications
+            .iter()
+            .filter(|notification| predicate(notification))
+            .map(|notification| notification.clone())
+            .collect()
+    }
+}
+
+#[async_trait::async_trait]
+impl NotificationRepo for InMemoryNotificationRepo {
+    async fn save(&self, notification: Notification) -> anyhow::Result<()> {
+        match self.notifications.get_mut(&notification.id) {
+            // Same columns as the upsert of the SQL stores
+            Some(mut existing) => {
+                existing.status = notification.status;
+                existing.attempts = notification.attempts;
+                existing.last_error = notification.last_error;
+                existing.next_attempt_at = notification.next_attempt_at;
+                existing.updated_at = notification.updated_at;
+            }
+            None => {
+                self.notifications
+                    .insert(notification.id.clone(), notification);
+            }
+        }
+        Ok(())
+    }
+
+    async fn fetch_by_id(&self, id: &str) -> anyhow::Result<Option<Notification>> {
+        Ok(self
+            .notifications
+            .get(id)
+            .map(|notification| notification.clone()))
+    }
+
+    async fn fetch_by_transfer(&self, transfer_id: &str) -> anyhow::Result<Vec<Notification>> {
+        let mut notifications = self.filter(|n| n.transfer_id == transfer_id);
+        notifications.sort_by_key(|n| n.created_at);
+        Ok(notifications)
+    }
+
+    async fn claim_due(
+        &self,
+        now: DateTime<Utc>,
+        until: DateTime<Utc>,
+        limit: i32,
+    ) -> anyhow::Result<Vec<Notification>> {
+        let is_due =
+            |n: &Notification| n.status == NotificationStatus::Pending && n.next_attempt_at <= now;
+
+        let mut due = self.filter(is_due);
+        due.sort_by_key(|n| n.next_attempt_at);
+
+        let mut notifications = due
+            .into_iter()
+            .filter_map(|n| {
+                let mut notification = self.notifications.get_mut(&n.id)?;
+                // Skips the notifications claimed since they were filtered
+                is_due(&notification).then(|| {
+                    notification.next_attempt_at = until;
+                    notification.clone()
+                })
+            })
+            .take(limit.max(0) as usize)
+            .collect::<Vec<_>>();
+
+        notifications.sort_by_key(|n| n.created_at);
+        Ok(notifications)
+    }
+}
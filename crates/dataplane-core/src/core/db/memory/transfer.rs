@@ -0,0 +1,120 @@
+use std::sync::Arc;
+
+use dashmap::{mapref::entry::Entry, DashMap};
+
+use crate::core::{
+    db::transfer::{illegal_transition, TransferQuery, TransferRepo},
+    model::{
+        notification::Notification,
+        transfer::{Transfer, TransferStatus},
+    },
+};
+
+use super::notification::InMemoryNotificationRepo;
+
+/// Keeps the transfers in memory, they are lost on restart
+#[derive(Clone, Default)]
+pub struct InMemoryTransferRepo {
+    transfers: Arc<DashMap<String, Transfer>>,
+    notifications: InMemoryNotificationRepo,
+}
+
+impl InMemoryTransferRepo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Outbox the notifications of [`TransferRepo::transition`] are written to
+    pub fn notifications(&self) -> InMemoryNotificationRepo {
+        self.notifications.clone()
+    }
+
+    fn update(existing: &mut Transfer, transfer: Transfer) {
+        // Same columns as the update of the SQL stores
+        existing.updated_at = transfer.updated_at;
+        existing.status = transfer.status;
+        existing.suspension_reason = transfer.suspension_reason;
+        existing.suspended_at = transfer.suspended_at;
+        existing.termination_reason = transfer.termination_reason;
+        existing.terminated_at = transfer.terminated_at;
+        existing.source = transfer.source;
+        existing.destination = transfer.destination;
+    }
+}
+
+#[async_trait::async_trait]
+impl TransferRepo for InMemoryTransferRepo {
+    async fn save(&self, transfer: Transfer) -> anyhow::Result<()> {
+        match self.transfers.get_mut(&transfer.id) {
+            Some(mut existing) => Self::update(&mut existing, transfer),
+            None => {
+                self.transfers.insert(transfer.id.clone(), transfer);
+            }
+        }
+        Ok(())
+    }
+
+    async fn create(&self, transfer: Transfer) -> anyhow::Result<bool> {
+        match self.transfers.entry(transfer.id.clone()) {
+            Entry::Occupied(_) => Ok(false),
+            Entry::Vacant(entry) => {
+                entry.insert(transfer);
+                Ok(true)
+            }
+        }
+    }
+
+    async fn fetch_by_id(&self, transfer_id: &str) -> anyhow::Result<Option<Transfer>> {
+        Ok(self
+            .transfers
+            .get(transfer_id)
+            .map(|transfer| transfer.clone()))
+    }
+
+    async fn delete(&self, transfer_id: &str) -> anyhow::Result<()> {
+        // The entry stays locked until the notifications are removed
+        if let Entry::Occupied(entry) = self.transfers.entry(transfer_id.to_string()) {
+            self.notifications.remove_by_transfer(transfer_id);
+            entry.remove();
+        }
+        Ok(())
+    }
+
+    async fn query(&self, query: TransferQuery) -> anyhow::Result<Vec<Transfer>> {
+        Ok(query.apply(self.transfers.iter().map(|transfer| transfer.clone())))
+    }
+
+    async fn change_status(&self, id: String, status: TransferStatus) -> anyhow::Result<()> {
+        if let Some(mut transfer) = self.transfers.get_mut(&id) {
+            if !transfer.status.can_transition_to(&status) {
+                return Err(illegal_transition(&transfer, &status));
+            }
+            transfer.status = status;
+        }
+        Ok(())
+    }
+
+    async fn transition(
+        &self,
+        transfer: Transfer,
+        from: TransferStatus,
+        notification: Option<Notification>,
+    ) -> anyhow::Result<bool> {
+        // The entry stays locked until the notification is written
+        let Some(mut existing) = self.transfers.get_mut(&transfer.id) else {
+            return Ok(false);
+        };
+
+        if existing.status != from {
+            return Ok(false);
+        }
+
+        Self::update(&mut existing, transfer);
+
+        if let Some(notification) = notification {
+            self.notifications.insert(notification);
+        }
+
+        Ok(true)
+    }
+}
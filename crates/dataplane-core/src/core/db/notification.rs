@@ -0,0 +1,25 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use miwa::derive::interface;
+
+use crate::core::model::notification::Notification;
+
+#[cfg(test)]
+use mockall::{automock, predicate::*};
+
+#[async_trait]
+#[interface]
+#[cfg_attr(test, automock)]
+pub trait NotificationRepo {
+    async fn save(&self, notification: Notification) -> anyhow::Result<()>;
+    async fn fetch_by_id(&self, id: &str) -> anyhow::Result<Option<Notification>>;
+    async fn fetch_by_transfer(&self, transfer_id: &str) -> anyhow::Result<Vec<Notification>>;
+    /// Claims the pending notifications whose next attempt is due, oldest first. Their next
+    /// attempt is postponed to `until` so that the other replicas skip them in the meantime
+    async fn claim_due(
+        &self,
+        now: DateTime<Utc>,
+        until: DateTime<Utc>,
+        limit: i32,
+    ) -> anyhow::Result<Vec<Notification>>;
+}
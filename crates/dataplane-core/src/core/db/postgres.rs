@@ -0,0 +1,47 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+use sqlx::{postgres::PgPoolOptions, PgPool};
+
+pub mod notification;
+pub mod transfer;
+
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct PgPoolConfig {
+    pub max_connections: Option<u32>,
+    pub min_connections: Option<u32>,
+    /// Timeout in seconds when acquiring a connection from the pool
+    pub acquire_timeout: Option<u64>,
+    /// Idle time in seconds before a connection is closed
+    pub idle_timeout: Option<u64>,
+    /// Maximum lifetime in seconds of a connection
+    pub max_lifetime: Option<u64>,
+}
+
+impl PgPoolConfig {
+    pub async fn connect(&self, url: &str) -> anyhow::Result<PgPool> {
+        let mut options = PgPoolOptions::new();
+
+        if let Some(max) = self.max_connections {
+            options = options.max_connections(max);
+        }
+
+        if let Some(min) = self.min_connections {
+            options = options.min_connections(min);
+        }
+
+        if let Some(timeout) = self.acquire_timeout {
+            options = options.acquire_timeout(Duration::from_secs(timeout));
+        }
+
+        if let Some(timeout) = self.idle_timeout {
+            options = options.idle_timeout(Duration::from_secs(timeout));
+        }
+
+        if let Some(lifetime) = self.max_lifetime {
+            options = options.max_lifetime(Duration::from_secs(lifetime));
+        }
+
+        Ok(options.connect(url).await?)
+    }
+}
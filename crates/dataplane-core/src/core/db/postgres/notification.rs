@@ -0,0 +1,112 @@
+use chrono::{DateTime, Utc};
+use sqlx::{PgExecutor, PgPool};
+
+use crate::core::{
+    db::notification::NotificationRepo,
+    model::notification::{Notification, NotificationStatus},
+};
+
+/// Shares the pool and the migrations of [`super::transfer::PgTransferRepo`]
+#[derive(Clone)]
+pub struct PgNotificationRepo {
+    pool: PgPool,
+}
+
+impl PgNotificationRepo {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl NotificationRepo for PgNotificationRepo {
+    async fn save(&self, notification: Notification) -> anyhow::Result<()> {
+        save(&self.pool, notification).await
+    }
+
+    async fn fetch_by_id(&self, id: &str) -> anyhow::Result<Option<Notification>> {
+        sqlx::query_as::<_, Notification>(
+            r#"
+            SELECT * FROM notifications where id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map(Ok)?
+    }
+
+    async fn fetch_by_transfer(&self, transfer_id: &str) -> anyhow::Result<Vec<Notification>> {
+        sqlx::query_as::<_, Notification>(
+            r#"
+            SELECT * FROM notifications where transfer_id = $1 ORDER BY created_at
+            "#,
+        )
+        .bind(transfer_id)
+        .fetch_all(&self.pool)
+        .await
+        .map(Ok)?
+    }
+
+    async fn claim_due(
+        &self,
+        now: DateTime<Utc>,
+        until: DateTime<Utc>,
+        limit: i32,
+    ) -> anyhow::Result<Vec<Notification>> {
+        let mut notifications = sqlx::query_as::<_, Notification>(
+            r#"
+            UPDATE notifications SET next_attempt_at = $1
+            WHERE id IN (
+                SELECT id FROM notifications WHERE status = $2 AND next_attempt_at <= $3
+                ORDER BY next_attempt_at LIMIT $4 FOR UPDATE SKIP LOCKED
+            )
+            RETURNING *
+            "#,
+        )
+        .bind(until)
+        .bind(NotificationStatus::Pending)
+        .bind(now)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        notifications.sort_by_key(|n| n.created_at);
+        Ok(notifications)
+    }
+}
+
+/// Upserts the notification, within the transaction of a transfer update or on the pool
+pub(super) async fn save<'c>(
+    executor: impl PgExecutor<'c>,
+    notification: Notification,
+) -> anyhow::Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO notifications (id, transfer_id, callback_address, kind, reason, status,
+            attempts, last_error, next_attempt_at, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        ON CONFLICT (id) DO UPDATE SET
+            status = EXCLUDED.status,
+            attempts = EXCLUDED.attempts,
+            last_error = EXCLUDED.last_error,
+            next_attempt_at = EXCLUDED.next_attempt_at,
+            updated_at = EXCLUDED.updated_at
+        "#,
+    )
+    .bind(notification.id)
+    .bind(notification.transfer_id)
+    .bind(notification.callback_address)
+    .bind(notification.kind)
+    .bind(notification.reason)
+    .bind(notification.status)
+    .bind(notification.attempts)
+    .bind(notification.last_error)
+    .bind(notification.next_attempt_at)
+    .bind(notification.created_at)
+    .bind(notification.updated_at)
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,228 @@
+use sqlx::{PgPool, QueryBuilder};
+
+use crate::core::{
+    db::transfer::{illegal_transition, push_transition_to, TransferQuery, TransferRepo},
+    model::{
+        notification::Notification,
+        transfer::{Transfer, TransferStatus},
+    },
+};
+
+use super::{notification, PgPoolConfig};
+
+#[derive(Clone)]
+pub struct PgTransferRepo {
+    pool: PgPool,
+}
+
+impl PgTransferRepo {
+    pub async fn connect(url: &str) -> anyhow::Result<Self> {
+        Self::connect_with(url, &PgPoolConfig::default()).await
+    }
+
+    pub async fn connect_with(url: &str, cfg: &PgPoolConfig) -> anyhow::Result<Self> {
+        let pool = cfg.connect(url).await?;
+        Ok(Self { pool })
+    }
+}
+
+#[async_trait::async_trait]
+impl TransferRepo for PgTransferRepo {
+    async fn save(&self, transfer: Transfer) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO transfers (id, status, source, participant_id, created_at, updated_at,
+                suspension_reason, suspended_at, termination_reason, terminated_at,
+                flow_type, destination, callback_address, agreement_end, transfer_type,
+                agreement_id, dataset_id, properties, consumer)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18,
+                $19)
+            ON CONFLICT (id) DO UPDATE SET
+                updated_at = EXCLUDED.updated_at,
+                status = EXCLUDED.status,
+                suspension_reason = EXCLUDED.suspension_reason,
+                suspended_at = EXCLUDED.suspended_at,
+                termination_reason = EXCLUDED.termination_reason,
+                terminated_at = EXCLUDED.terminated_at,
+                source = EXCLUDED.source,
+                destination = EXCLUDED.destination
+            "#,
+        )
+        .bind(transfer.id)
+        .bind(transfer.status)
+        .bind(transfer.source)
+        .bind(transfer.participant_id)
+        .bind(transfer.created_at)
+        .bind(transfer.updated_at)
+        .bind(transfer.suspension_reason)
+        .bind(transfer.suspended_at)
+        .bind(transfer.termination_reason)
+        .bind(transfer.terminated_at)
+        .bind(transfer.flow_type)
+        .bind(transfer.destination)
+        .bind(transfer.callback_address)
+        .bind(transfer.agreement_end)
+        .bind(transfer.transfer_type)
+        .bind(transfer.agreement_id)
+        .bind(transfer.dataset_id)
+        .bind(transfer.properties)
+        .bind(transfer.consumer)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn create(&self, transfer: Transfer) -> anyhow::Result<bool> {
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO transfers (id, status, source, participant_id, created_at, updated_at,
+                suspension_reason, suspended_at, termination_reason, terminated_at,
+                flow_type, destination, callback_address, agreement_end, transfer_type,
+                agreement_id, dataset_id, properties, consumer)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18,
+                $19)
+            ON CONFLICT (id) DO NOTHING
+            "#,
+        )
+        .bind(transfer.id)
+        .bind(transfer.status)
+        .bind(transfer.source)
+        .bind(transfer.participant_id)
+        .bind(transfer.created_at)
+        .bind(transfer.updated_at)
+        .bind(transfer.suspension_reason)
+        .bind(transfer.suspended_at)
+        .bind(transfer.termination_reason)
+        .bind(transfer.terminated_at)
+        .bind(transfer.flow_type)
+        .bind(transfer.destination)
+        .bind(transfer.callback_address)
+        .bind(transfer.agreement_end)
+        .bind(transfer.transfer_type)
+        .bind(transfer.agreement_id)
+        .bind(transfer.dataset_id)
+        .bind(transfer.properties)
+        .bind(transfer.consumer)
+        .execute(&self.pool)
+        .await?
+        .rows_affected();
+
+        Ok(inserted == 1)
+    }
+
+    async fn fetch_by_id(&self, transfer_id: &str) -> anyhow::Result<Option<Transfer>> {
+        sqlx::query_as::<_, Transfer>(
+            r#"
+            SELECT * FROM transfers where id = $1
+            "#,
+        )
+        .bind(transfer_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map(Ok)?
+    }
+
+    async fn query(&self, query: TransferQuery) -> anyhow::Result<Vec<Transfer>> {
+        let mut q = QueryBuilder::new("SELECT * FROM transfers");
+
+        query.push_to(&mut q);
+
+        q.build_query_as().fetch_all(&self.pool).await.map(Ok)?
+    }
+
+    async fn delete(&self, transfer_id: &str) -> anyhow::Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM notifications where transfer_id = $1
+            "#,
+        )
+        .bind(transfer_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM transfers where id = $1
+            "#,
+        )
+        .bind(transfer_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn change_status(&self, id: String, status: TransferStatus) -> anyhow::Result<()> {
+        let mut q = QueryBuilder::new("UPDATE transfers SET status = ");
+        q.push_bind(status.clone())
+            .push(" WHERE id = ")
+            .push_bind(id.clone())
+            .push(" AND ");
+        push_transition_to(&mut q, &status);
+
+        if q.build().execute(&self.pool).await?.rows_affected() == 0 {
+            if let Some(transfer) = self.fetch_by_id(&id).await? {
+                return Err(illegal_transition(&transfer, &status));
+            }
+        }
+
+        Ok(())
+    }
+    async fn transition(
+        &self,
+        transfer: Transfer,
+        from: TransferStatus,
+        notification: Option<Notification>,
+    ) -> anyhow::Result<bool> {
+        let mut tx = self.pool.begin().await?;
+
+        let updated = sqlx::query(
+            r#"
+            UPDATE transfers SET updated_at=$1, status=$2, suspension_reason=$3, suspended_at=$4,
+                termination_reason=$5, terminated_at=$6, source=$7, destination=$8
+            WHERE id = $9 AND status = $10
+            "#,
+        )
+        .bind(transfer.updated_at)
+        .bind(transfer.status)
+        .bind(transfer.suspension_reason)
+        .bind(transfer.suspended_at)
+        .bind(transfer.termination_reason)
+        .bind(transfer.terminated_at)
+        .bind(transfer.source)
+        .bind(transfer.destination)
+        .bind(transfer.id)
+        .bind(from)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        if updated == 0 {
+            return Ok(false);
+        }
+
+        if let Some(notification) = notification {
+            notification::save(&mut *tx, notification).await?;
+        }
+
+        tx.commit().await?;
+        Ok(true)
+    }
+}
+
+impl PgTransferRepo {
+    pub fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+
+    pub async fn migrate(&self) -> anyhow::Result<()> {
+        let mut migrator = sqlx::migrate!("./migrations/postgres");
+        // The proxy tokens store may share the same database and migrations table
+        migrator.set_ignore_missing(true);
+        migrator.run(&self.pool).await?;
+        Ok(())
+    }
+}
@@ -1,10 +1,15 @@
 use sqlx::{QueryBuilder, SqlitePool};
 
 use crate::core::{
-    db::transfer::{TransferQuery, TransferRepo},
-    model::transfer::{Transfer, TransferStatus},
+    db::transfer::{illegal_transition, push_transition_to, TransferQuery, TransferRepo},
+    model::{
+        notification::Notification,
+        transfer::{Transfer, TransferStatus},
+    },
 };
 
+use super::notification;
+
 #[derive(Clone)]
 pub struct SqliteTransferRepo {
     pool: SqlitePool,
@@ -20,13 +25,49 @@ impl SqliteTransferRepo {
 #[async_trait::async_trait]
 impl TransferRepo for SqliteTransferRepo {
     async fn save(&self, transfer: Transfer) -> anyhow::Result<()> {
-        if self.fetch_by_id(&transfer.id).await?.is_none() {
-            self.internal_save(transfer).await?;
-        } else {
+        if !self.create(transfer.clone()).await? {
             self.internal_update(transfer).await?;
         }
         Ok(())
     }
+    async fn create(&self, transfer: Transfer) -> anyhow::Result<bool> {
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO transfers (id, status, source, participant_id, created_at, updated_at,
+                suspension_reason, suspended_at, termination_reason, terminated_at,
+                flow_type, destination, callback_address, agreement_end, transfer_type,
+                agreement_id, dataset_id, properties, consumer)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18,
+                $19)
+            ON CONFLICT (id) DO NOTHING
+            "#,
+        )
+        .bind(transfer.id)
+        .bind(transfer.status)
+        .bind(transfer.source)
+        .bind(transfer.participant_id)
+        .bind(transfer.created_at)
+        .bind(transfer.updated_at)
+        .bind(transfer.suspension_reason)
+        .bind(transfer.suspended_at)
+        .bind(transfer.termination_reason)
+        .bind(transfer.terminated_at)
+        .bind(transfer.flow_type)
+        .bind(transfer.destination)
+        .bind(transfer.callback_address)
+        .bind(transfer.agreement_end)
+        .bind(transfer.transfer_type)
+        .bind(transfer.agreement_id)
+        .bind(transfer.dataset_id)
+        .bind(transfer.properties)
+        .bind(transfer.consumer)
+        .execute(&self.pool)
+        .await?
+        .rows_affected();
+
+        Ok(inserted == 1)
+    }
+
     async fn fetch_by_id(&self, transfer_id: &str) -> anyhow::Result<Option<Transfer>> {
         sqlx::query_as::<_, Transfer>(
             r#"
@@ -42,79 +83,111 @@ impl TransferRepo for SqliteTransferRepo {
     async fn query(&self, query: TransferQuery) -> anyhow::Result<Vec<Transfer>> {
         let mut q = QueryBuilder::new("SELECT * FROM transfers");
 
-        if query.id.is_some() {
-            q.push(" WHERE ");
-        }
-
-        if let Some(id) = query.id {
-            q.push(" id = ").push_bind(id);
-        }
-
-        q.push(" LIMIT ")
-            .push_bind(query.limit)
-            .push(" OFFSET ")
-            .push_bind(query.offset);
+        query.push_to(&mut q);
 
         q.build_query_as().fetch_all(&self.pool).await.map(Ok)?
     }
 
     async fn delete(&self, transfer_id: &str) -> anyhow::Result<()> {
+        let mut tx = self.pool.begin().await?;
+
         sqlx::query(
             r#"
-            DELETE FROM transfers where id = $1
+            DELETE FROM notifications where transfer_id = $1
             "#,
         )
         .bind(transfer_id)
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await?;
 
-        Ok(())
-    }
-
-    async fn change_status(&self, id: String, status: TransferStatus) -> anyhow::Result<()> {
         sqlx::query(
             r#"
-            UPDATE transfers SET status=$1
-            WHERE id = $2
+            DELETE FROM transfers where id = $1
             "#,
         )
-        .bind(status)
-        .bind(id)
-        .execute(&self.pool)
+        .bind(transfer_id)
+        .execute(&mut *tx)
         .await?;
 
+        tx.commit().await?;
         Ok(())
     }
-}
 
-impl SqliteTransferRepo {
-    async fn internal_save(&self, transfer: Transfer) -> anyhow::Result<()> {
-        sqlx::query(
+    async fn change_status(&self, id: String, status: TransferStatus) -> anyhow::Result<()> {
+        let mut q = QueryBuilder::new("UPDATE transfers SET status = ");
+        q.push_bind(status.clone())
+            .push(" WHERE id = ")
+            .push_bind(id.clone())
+            .push(" AND ");
+        push_transition_to(&mut q, &status);
+
+        if q.build().execute(&self.pool).await?.rows_affected() == 0 {
+            if let Some(transfer) = self.fetch_by_id(&id).await? {
+                return Err(illegal_transition(&transfer, &status));
+            }
+        }
+
+        Ok(())
+    }
+    async fn transition(
+        &self,
+        transfer: Transfer,
+        from: TransferStatus,
+        notification: Option<Notification>,
+    ) -> anyhow::Result<bool> {
+        let mut tx = self.pool.begin().await?;
+
+        let updated = sqlx::query(
             r#"
-            INSERT INTO transfers (id, status, source, participant_id, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6)
+            UPDATE transfers SET updated_at=$1, status=$2, suspension_reason=$3, suspended_at=$4,
+                termination_reason=$5, terminated_at=$6, source=$7, destination=$8
+            WHERE id = $9 AND status = $10
             "#,
         )
-        .bind(transfer.id)
+        .bind(transfer.updated_at)
         .bind(transfer.status)
+        .bind(transfer.suspension_reason)
+        .bind(transfer.suspended_at)
+        .bind(transfer.termination_reason)
+        .bind(transfer.terminated_at)
         .bind(transfer.source)
-        .bind(transfer.participant_id)
-        .bind(transfer.created_at)
-        .bind(transfer.updated_at)
-        .execute(&self.pool)
-        .await?;
-        Ok(())
+        .bind(transfer.destination)
+        .bind(transfer.id)
+        .bind(from)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        if updated == 0 {
+            return Ok(false);
+        }
+
+        if let Some(notification) = notification {
+            notification::save(&mut *tx, notification).await?;
+        }
+
+        tx.commit().await?;
+        Ok(true)
     }
+}
 
+impl SqliteTransferRepo {
     async fn internal_update(&self, transfer: Transfer) -> anyhow::Result<()> {
         sqlx::query(
             r#"
-            UPDATE transfers SET updated_at=$1, status=$2
-            WHERE id = $3
+            UPDATE transfers SET updated_at=$1, status=$2, suspension_reason=$3, suspended_at=$4,
+                termination_reason=$5, terminated_at=$6, source=$7, destination=$8
+            WHERE id = $9
             "#,
         )
         .bind(transfer.updated_at)
         .bind(transfer.status)
+        .bind(transfer.suspension_reason)
+        .bind(transfer.suspended_at)
+        .bind(transfer.termination_reason)
+        .bind(transfer.terminated_at)
+        .bind(transfer.source)
+        .bind(transfer.destination)
         .bind(transfer.id)
         .execute(&self.pool)
         .await?;
@@ -122,6 +195,10 @@ impl SqliteTransferRepo {
         Ok(())
     }
 
+    pub fn pool(&self) -> &SqlitePool {
+        &self.pool
+    }
+
     pub async fn migrate(&self) -> anyhow::Result<()> {
         sqlx::migrate!("./migrations/sqlite")
             .run(&self.pool)
@@ -1,8 +1,14 @@
 use async_trait::async_trait;
-use bon::{builder, Builder};
+use bon::Builder;
+use chrono::{DateTime, Utc};
 use miwa::derive::interface;
+use serde::Deserialize;
+use sqlx::{Database, Encode, QueryBuilder, Type};
 
-use crate::core::model::transfer::{Transfer, TransferStatus};
+use crate::core::model::{
+    notification::Notification,
+    transfer::{Transfer, TransferStatus},
+};
 
 #[cfg(test)]
 use mockall::{automock, predicate::*};
@@ -12,7 +18,11 @@ use mockall::{automock, predicate::*};
 #[cfg_attr(test, automock)]
 pub trait TransferRepo {
     async fn save(&self, transfer: Transfer) -> anyhow::Result<()>;
+    /// Inserts the transfer unless one with the same id is stored. Returns whether the
+    /// transfer was inserted
+    async fn create(&self, transfer: Transfer) -> anyhow::Result<bool>;
     async fn fetch_by_id(&self, transfer_id: &str) -> anyhow::Result<Option<Transfer>>;
+    /// Deletes the transfer along with its notifications, in a single transaction
     async fn delete(&self, transfer_id: &str) -> anyhow::Result<()>;
     async fn query(&self, query: TransferQuery) -> anyhow::Result<Vec<Transfer>>;
     async fn change_status(
@@ -20,9 +30,17 @@ pub trait TransferRepo {
         transfer_id: String,
         status: TransferStatus,
     ) -> anyhow::Result<()>;
+    /// Saves the transfer if its stored status is still `from`, the notification of the new
+    /// status being written in the same transaction. Returns whether the transfer was saved
+    async fn transition(
+        &self,
+        transfer: Transfer,
+        from: TransferStatus,
+        notification: Option<Notification>,
+    ) -> anyhow::Result<bool>;
 }
 
-#[derive(Builder)]
+#[derive(Builder, Debug, Clone)]
 pub struct TransferQuery {
     #[builder(default = 50)]
     pub limit: i32,
@@ -30,4 +48,246 @@ pub struct TransferQuery {
     pub offset: i32,
     #[builder(into)]
     pub id: Option<String>,
+    /// Restricts the query to these transfers, none match when empty
+    pub ids: Option<Vec<String>>,
+    #[builder(into)]
+    pub participant_id: Option<String>,
+    pub status: Option<TransferStatus>,
+    #[builder(into)]
+    pub agreement_id: Option<String>,
+    #[builder(into)]
+    pub dataset_id: Option<String>,
+    #[builder(into)]
+    pub transfer_type: Option<String>,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+    pub updated_after: Option<DateTime<Utc>>,
+    pub updated_before: Option<DateTime<Utc>>,
+    pub agreement_ended_before: Option<DateTime<Utc>>,
+    #[builder(default)]
+    pub sort_by: TransferSortField,
+    #[builder(default)]
+    pub sort_order: SortOrder,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum TransferSortField {
+    #[default]
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+impl TransferQuery {
+    /// Appends the filters, sorting and pagination of the query to a `SELECT` statement
+    pub(crate) fn push_to<'a, DB>(self, q: &mut QueryBuilder<'a, DB>)
+    where
+        DB: Database,
+        i32: Encode<'a, DB> + Type<DB>,
+        String: Encode<'a, DB> + Type<DB>,
+        TransferStatus: Encode<'a, DB> + Type<DB>,
+        DateTime<Utc>: Encode<'a, DB> + Type<DB>,
+    {
+        let mut conditions = 0;
+        let mut condition = |q: &mut QueryBuilder<'a, DB>, clause: &str| {
+            q.push(if conditions == 0 { " WHERE " } else { " AND " })
+                .push(clause);
+            conditions += 1;
+        };
+
+        if let Some(id) = self.id {
+            condition(q, "id = ");
+            q.push_bind(id);
+        }
+
+        if let Some(ids) = self.ids {
+            if ids.is_empty() {
+                condition(q, "1 = 0");
+            } else {
+                condition(q, "id IN (");
+                let mut separated = q.separated(", ");
+                for id in ids {
+                    separated.push_bind(id);
+                }
+                separated.push_unseparated(")");
+            }
+        }
+
+        if let Some(participant_id) = self.participant_id {
+            condition(q, "participant_id = ");
+            q.push_bind(participant_id);
+        }
+
+        if let Some(status) = self.status {
+            condition(q, "status = ");
+            q.push_bind(status);
+        }
+
+        if let Some(agreement_id) = self.agreement_id {
+            condition(q, "agreement_id = ");
+            q.push_bind(agreement_id);
+        }
+
+        if let Some(dataset_id) = self.dataset_id {
+            condition(q, "dataset_id = ");
+            q.push_bind(dataset_id);
+        }
+
+        if let Some(transfer_type) = self.transfer_type {
+            condition(q, "transfer_type = ");
+            q.push_bind(transfer_type);
+        }
+
+        if let Some(created_after) = self.created_after {
+            condition(q, "created_at >= ");
+            q.push_bind(created_after);
+        }
+
+        if let Some(created_before) = self.created_before {
+            condition(q, "created_at < ");
+            q.push_bind(created_before);
+        }
+
+        if let Some(updated_after) = self.updated_after {
+            condition(q, "updated_at >= ");
+            q.push_bind(updated_after);
+        }
+
+        if let Some(updated_before) = self.updated_before {
+            condition(q, "updated_at < ");
+            q.push_bind(updated_before);
+        }
+
+        if let Some(agreement_ended_before) = self.agreement_ended_before {
+            condition(q, "agreement_end < ");
+            q.push_bind(agreement_ended_before);
+        }
+
+        let column = match self.sort_by {
+            TransferSortField::CreatedAt => "created_at",
+            TransferSortField::UpdatedAt => "updated_at",
+        };
+
+        let order = match self.sort_order {
+            SortOrder::Asc => "ASC",
+            SortOrder::Desc => "DESC",
+        };
+
+        // The id makes the order stable across pages
+        q.push(format!(" ORDER BY {column} {order}, id {order} LIMIT "))
+            .push_bind(self.limit)
+            .push(" OFFSET ")
+            .push_bind(self.offset);
+    }
+
+    /// Applies the filters, sorting and pagination of the query to stored transfers, the
+    /// same way as [`TransferQuery::push_to`]
+    pub(crate) fn apply(self, transfers: impl Iterator<Item = Transfer>) -> Vec<Transfer> {
+        let mut transfers = transfers
+            .filter(|transfer| self.matches(transfer))
+            .collect::<Vec<_>>();
+
+        transfers.sort_by(|a, b| {
+            let ordering = match self.sort_by {
+                TransferSortField::CreatedAt => a.created_at.cmp(&b.created_at),
+                TransferSortField::UpdatedAt => a.updated_at.cmp(&b.updated_at),
+            }
+            .then_with(|| a.id.cmp(&b.id));
+
+            match self.sort_order {
+                SortOrder::Asc => ordering,
+                SortOrder::Desc => ordering.reverse(),
+            }
+        });
+
+        transfers
+            .into_iter()
+            .skip(self.offset.max(0) as usize)
+            .take(self.limit.max(0) as usize)
+            .collect()
+    }
+
+    fn matches(&self, transfer: &Transfer) -> bool {
+        self.id.as_ref().is_none_or(|id| &transfer.id == id)
+            && self
+                .ids
+                .as_ref()
+                .is_none_or(|ids| ids.contains(&transfer.id))
+            && self
+                .participant_id
+                .as_ref()
+                .is_none_or(|participant_id| &transfer.participant_id == participant_id)
+            && self
+                .status
+                .as_ref()
+                .is_none_or(|status| &transfer.status == status)
+            && self
+                .agreement_id
+                .as_ref()
+                .is_none_or(|agreement_id| transfer.agreement_id.as_ref() == Some(agreement_id))
+            && self
+                .dataset_id
+                .as_ref()
+                .is_none_or(|dataset_id| transfer.dataset_id.as_ref() == Some(dataset_id))
+            && self
+                .transfer_type
+                .as_ref()
+                .is_none_or(|transfer_type| transfer.transfer_type.as_ref() == Some(transfer_type))
+            && self
+                .created_after
+                .is_none_or(|after| transfer.created_at >= after)
+            && self
+                .created_before
+                .is_none_or(|before| transfer.created_at < before)
+            && self
+                .updated_after
+                .is_none_or(|after| transfer.updated_at >= after)
+            && self
+                .updated_before
+                .is_none_or(|before| transfer.updated_at < before)
+            && self.agreement_ended_before.is_none_or(|before| {
+                transfer
+                    .agreement_end
+                    .is_some_and(|agreement_end| agreement_end < before)
+            })
+    }
+}
+
+/// Appends the condition restricting an update to the transfers allowed to transition to
+/// `status`, see [`TransferStatus::can_transition_to`]
+pub(crate) fn push_transition_to<'a, DB>(q: &mut QueryBuilder<'a, DB>, status: &TransferStatus)
+where
+    DB: Database,
+    TransferStatus: Encode<'a, DB> + Type<DB>,
+{
+    let predecessors = status.predecessors();
+
+    if predecessors.is_empty() {
+        q.push("1 = 0");
+        return;
+    }
+
+    q.push("status IN (");
+    let mut separated = q.separated(", ");
+    for predecessor in predecessors {
+        separated.push_bind(predecessor);
+    }
+    separated.push_unseparated(")");
+}
+
+pub(crate) fn illegal_transition(transfer: &Transfer, status: &TransferStatus) -> anyhow::Error {
+    anyhow::anyhow!(
+        "Transfer {} cannot transition from {:?} to {:?}",
+        transfer.id,
+        transfer.status,
+        status
+    )
 }
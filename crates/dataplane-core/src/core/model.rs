@@ -1,2 +1,3 @@
 pub mod namespace;
+pub mod notification;
 pub mod transfer;
@@ -1,3 +1,5 @@
+use std::borrow::Cow;
+
 pub struct Namespace<'a>(&'a str);
 
 impl<'a> Namespace<'a> {
@@ -15,3 +17,29 @@ pub static EDC_NAMESPACE: Namespace<'static> = Namespace("https://w3id.org/edc/v
 pub static DSPACE_NAMESPACE: Namespace<'static> = Namespace("https://w3id.org/dspace/v0.8/");
 
 pub static IDSA_NAMESPACE: Namespace<'static> = Namespace("https://w3id.org/idsa/v4.1/");
+
+/// Prefixes accepted in compact IRIs
+pub static PREFIXES: [(&str, &Namespace<'static>); 3] = [
+    ("edc", &EDC_NAMESPACE),
+    ("dspace", &DSPACE_NAMESPACE),
+    ("idsa", &IDSA_NAMESPACE),
+];
+
+/// Expands a compact IRI such as `edc:baseUrl` or a bare term, which belongs to the
+/// EDC vocabulary, to its full IRI
+pub fn expand_iri(value: &str) -> Cow<'_, str> {
+    match value.split_once(':') {
+        Some((prefix, suffix)) => PREFIXES
+            .iter()
+            .find(|(name, _)| *name == prefix)
+            .map(|(_, ns)| Cow::Owned(ns.to_iri(suffix)))
+            .unwrap_or(Cow::Borrowed(value)),
+        None => Cow::Owned(EDC_NAMESPACE.to_iri(value)),
+    }
+}
+
+/// Strips the EDC vocabulary from an IRI, `https://w3id.org/edc/v0.0.1/ns/HttpData`
+/// becoming `HttpData`
+pub fn compact_iri(value: &str) -> &str {
+    value.strip_prefix(EDC_NAMESPACE.ns()).unwrap_or(value)
+}
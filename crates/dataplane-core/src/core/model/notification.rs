@@ -0,0 +1,47 @@
+use bon::Builder;
+use chrono::{DateTime, Utc};
+use sqlx::prelude::FromRow;
+use uuid::Uuid;
+
+/// Transfer status change waiting to be delivered to the control plane
+#[derive(Builder, Clone, Debug, FromRow, PartialEq)]
+pub struct Notification {
+    #[builder(default = Uuid::new_v4().to_string())]
+    pub id: String,
+    #[builder(into)]
+    pub transfer_id: String,
+    #[builder(into)]
+    pub callback_address: String,
+    pub kind: NotificationKind,
+    #[builder(into)]
+    pub reason: Option<String>,
+    #[builder(default = NotificationStatus::Pending)]
+    pub status: NotificationStatus,
+    #[builder(default)]
+    pub attempts: i32,
+    #[builder(into)]
+    pub last_error: Option<String>,
+    #[builder(default = Utc::now())]
+    pub next_attempt_at: DateTime<Utc>,
+    #[builder(default = Utc::now())]
+    pub created_at: DateTime<Utc>,
+    #[builder(default = Utc::now())]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug, sqlx::Type, PartialEq)]
+#[sqlx(type_name = "text", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum NotificationKind {
+    Completed,
+    Failed,
+    Terminated,
+}
+
+#[derive(Clone, Debug, sqlx::Type, PartialEq)]
+#[sqlx(type_name = "text", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum NotificationStatus {
+    Pending,
+    Delivered,
+    /// Gave up after the maximum number of attempts or a permanent rejection
+    Failed,
+}
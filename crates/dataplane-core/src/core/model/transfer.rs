@@ -1,47 +1,192 @@
+use std::collections::HashMap;
+
 use bon::Builder;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use sqlx::{prelude::FromRow, types::Json};
 
-use crate::signaling::DataAddress;
+use crate::signaling::{DataAddress, FlowType};
 
 #[derive(Builder, Clone, Debug, FromRow, PartialEq)]
 pub struct Transfer {
     pub id: String,
     pub participant_id: String,
     pub status: TransferStatus,
-    #[builder(into)]
-    pub source: Json<DataAddress>,
     #[builder(default)]
+    pub flow_type: FlowType,
+    /// Only known by consumer transfers once the provider started the flow
+    #[builder(into)]
+    pub source: Option<Json<DataAddress>>,
+    #[builder(into)]
+    pub destination: Option<Json<DataAddress>>,
+    /// Control plane endpoint notified when the transfer reaches a final state
+    #[builder(into)]
+    pub callback_address: Option<String>,
+    #[builder(default = chrono::Utc::now())]
     pub created_at: chrono::DateTime<chrono::Utc>,
-    #[builder(default)]
+    #[builder(default = chrono::Utc::now())]
     pub updated_at: chrono::DateTime<chrono::Utc>,
+    #[builder(into)]
+    pub suspension_reason: Option<String>,
+    pub suspended_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Reason of the termination or of the failure of the transfer
+    #[builder(into)]
+    pub termination_reason: Option<String>,
+    /// Set when the transfer reaches a final state
+    pub terminated_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// End of the contract agreement, past it the transfer is terminated by the reaper
+    pub agreement_end: Option<chrono::DateTime<chrono::Utc>>,
+    /// Such as `HttpData-PULL`, selects the manager handling the transfer
+    #[builder(into)]
+    pub transfer_type: Option<String>,
+    /// Contract agreement of the transfer, unknown for transfers stored before it was kept
+    #[builder(into)]
+    pub agreement_id: Option<String>,
+    #[builder(into)]
+    pub dataset_id: Option<String>,
+    /// Properties of the message that created the transfer, the values of secret ones redacted
+    #[builder(into)]
+    pub properties: Option<Json<HashMap<String, Value>>>,
+    /// Created by a prepare message, the provider then starts and completes it
+    #[builder(default)]
+    pub consumer: bool,
+}
+
+impl Transfer {
+    pub fn source_address(&self) -> Result<&DataAddress, types::DataAddressError> {
+        self.source
+            .as_ref()
+            .map(|source| &source.0)
+            .ok_or_else(|| types::DataAddressError::MissingProperty("source".to_string()))
+    }
+}
+
+/// EDR issued or received for a transfer, without its tokens
+#[derive(Builder, Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferEdr {
+    #[builder(into)]
+    pub endpoint: String,
+    /// Id of the access token, only known for the issued EDRs
+    #[builder(into)]
+    pub token_id: Option<String>,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Whether the access token can be refreshed
+    pub refreshable: bool,
 }
 
-#[derive(Clone, Debug, sqlx::Type, PartialEq)]
+#[derive(Clone, Debug, sqlx::Type, PartialEq, Serialize, Deserialize)]
+#[sqlx(type_name = "text")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum TransferStatus {
+    /// Consumer transfer with a provisioned destination, waiting for the provider
+    Prepared,
+    Received,
     Started,
     Suspended,
+    Terminated,
+    Completed,
+    Failed,
+}
+
+impl TransferStatus {
+    pub const ALL: [TransferStatus; 7] = [
+        TransferStatus::Prepared,
+        TransferStatus::Received,
+        TransferStatus::Started,
+        TransferStatus::Suspended,
+        TransferStatus::Terminated,
+        TransferStatus::Completed,
+        TransferStatus::Failed,
+    ];
+
+    pub fn is_final(&self) -> bool {
+        matches!(
+            self,
+            TransferStatus::Terminated | TransferStatus::Completed | TransferStatus::Failed
+        )
+    }
+
+    pub fn can_transition_to(&self, next: &TransferStatus) -> bool {
+        use TransferStatus::*;
+
+        matches!(
+            (self, next),
+            (Prepared | Received, Started | Terminated | Failed)
+                | (Started, Suspended | Terminated | Completed | Failed)
+                | (Suspended, Started | Terminated | Failed)
+        )
+    }
+
+    /// Statuses from which a transfer can transition to this one
+    pub fn predecessors(&self) -> Vec<TransferStatus> {
+        TransferStatus::ALL
+            .into_iter()
+            .filter(|status| status.can_transition_to(self))
+            .collect()
+    }
 }
 
 pub mod types {
+    use std::path::PathBuf;
+
     use axum::http::Uri;
+    use thiserror::Error;
 
     use crate::{core::model::namespace::EDC_NAMESPACE, signaling::DataAddress};
 
-    pub enum TransferKind {
-        HttpData(HttpData),
+    #[derive(Debug, Error)]
+    pub enum DataAddressError {
+        #[error("Unsupported endpoint type {0}")]
+        UnsupportedType(String),
+        #[error("Missing property {0}")]
+        MissingProperty(String),
+        #[error("Invalid property {name}: {reason}")]
+        InvalidProperty { name: String, reason: String },
     }
 
-    impl TryFrom<&DataAddress> for TransferKind {
-        type Error = anyhow::Error;
+    /// Endpoint type of data addresses, crates adding their own types implement it and
+    /// register it in [`EndpointTypes`](crate::core::service::registry::EndpointTypes)
+    pub trait EndpointType: Send + Sync {
+        /// Such as `HttpData`, matched against the endpoint type of the addresses without
+        /// the EDC vocabulary
+        fn name(&self) -> &str;
 
-        fn try_from(value: &DataAddress) -> Result<Self, Self::Error> {
-            match value.endpoint_type.as_str() {
-                "HttpData" => Ok(TransferKind::HttpData(HttpData::try_from(value)?)),
-                kind if kind == EDC_NAMESPACE.to_iri("HttpData") => {
-                    Ok(TransferKind::HttpData(HttpData::try_from(value)?))
-                }
-                _ => Err(anyhow::anyhow!("Unsupported endpoint type")),
-            }
+        /// Collects every violation of the address instead of stopping at the first one
+        fn validate(&self, address: &DataAddress) -> Vec<DataAddressError>;
+
+        /// URL the pull proxy forwards the requests of the consumer to, `None` for types
+        /// that cannot be pulled through the proxy
+        fn pull_url(&self, _address: &DataAddress) -> Result<Option<Uri>, DataAddressError> {
+            Ok(None)
+        }
+    }
+
+    pub struct HttpDataType;
+
+    impl EndpointType for HttpDataType {
+        fn name(&self) -> &str {
+            "HttpData"
+        }
+
+        fn validate(&self, address: &DataAddress) -> Vec<DataAddressError> {
+            HttpData::validate(address)
+        }
+
+        fn pull_url(&self, address: &DataAddress) -> Result<Option<Uri>, DataAddressError> {
+            HttpData::try_from(address).map(|data| Some(data.base_url))
+        }
+    }
+
+    pub struct FileType;
+
+    impl EndpointType for FileType {
+        fn name(&self) -> &str {
+            "File"
+        }
+
+        fn validate(&self, address: &DataAddress) -> Vec<DataAddressError> {
+            FileData::validate(address)
         }
     }
 
@@ -53,26 +198,86 @@ pub mod types {
     }
 
     impl TryFrom<&DataAddress> for HttpData {
-        type Error = anyhow::Error;
+        type Error = DataAddressError;
+
+        fn try_from(value: &DataAddress) -> Result<Self, Self::Error> {
+            Ok(Self {
+                base_url: get_uri_property(value, "baseUrl")?,
+                proxy_path: get_bool_property(value, "proxyPath")?,
+                proxy_method: get_bool_property(value, "proxyMethod")?,
+                proxy_query_params: get_bool_property(value, "proxyQueryParams")?,
+            })
+        }
+    }
+
+    impl HttpData {
+        pub fn validate(value: &DataAddress) -> Vec<DataAddressError> {
+            [
+                get_uri_property(value, "baseUrl").err(),
+                get_bool_property(value, "proxyPath").err(),
+                get_bool_property(value, "proxyMethod").err(),
+                get_bool_property(value, "proxyQueryParams").err(),
+            ]
+            .into_iter()
+            .flatten()
+            .collect()
+        }
+    }
+
+    pub struct FileData {
+        pub path: PathBuf,
+    }
+
+    impl TryFrom<&DataAddress> for FileData {
+        type Error = DataAddressError;
 
         fn try_from(value: &DataAddress) -> Result<Self, Self::Error> {
             Ok(Self {
-                base_url: value
-                    .get_property(&EDC_NAMESPACE.to_iri("baseUrl"))
-                    .map(|url| url.parse::<Uri>())
-                    .ok_or_else(|| anyhow::anyhow!("Missing base url"))??,
-                proxy_path: get_bool_property(value, "proxyPath"),
-                proxy_method: get_bool_property(value, "proxyMethod"),
-                proxy_query_params: get_bool_property(value, "proxyQueryParams"),
+                path: value
+                    .get_property(&EDC_NAMESPACE.to_iri("path"))
+                    .map(PathBuf::from)
+                    .ok_or_else(|| DataAddressError::MissingProperty("path".to_string()))?,
             })
         }
     }
 
-    fn get_bool_property(value: &DataAddress, property: &str) -> bool {
+    impl FileData {
+        pub fn validate(value: &DataAddress) -> Vec<DataAddressError> {
+            FileData::try_from(value).err().into_iter().collect()
+        }
+    }
+
+    fn get_uri_property(value: &DataAddress, property: &str) -> Result<Uri, DataAddressError> {
+        let uri = value
+            .get_property(&EDC_NAMESPACE.to_iri(property))
+            .ok_or_else(|| DataAddressError::MissingProperty(property.to_string()))?
+            .parse::<Uri>()
+            .map_err(|err| DataAddressError::InvalidProperty {
+                name: property.to_string(),
+                reason: err.to_string(),
+            })?;
+
+        if uri.scheme().is_none() || uri.authority().is_none() {
+            return Err(DataAddressError::InvalidProperty {
+                name: property.to_string(),
+                reason: "must be an absolute URL".to_string(),
+            });
+        }
+
+        Ok(uri)
+    }
+
+    /// Flags are optional and default to `false`
+    fn get_bool_property(value: &DataAddress, property: &str) -> Result<bool, DataAddressError> {
         value
             .get_property(&EDC_NAMESPACE.to_iri(property))
-            .map(|v| v.parse::<bool>())
-            .unwrap_or_else(|| Ok(false))
-            .unwrap_or(false)
+            .map(|v| {
+                v.parse::<bool>()
+                    .map_err(|_| DataAddressError::InvalidProperty {
+                        name: property.to_string(),
+                        reason: format!("expected true or false, got {}", v),
+                    })
+            })
+            .unwrap_or(Ok(false))
     }
 }
@@ -0,0 +1,160 @@
+use miwa::derive::Injectable;
+use serde::Serialize;
+use tokio::sync::broadcast;
+use tracing::trace;
+
+/// Lifecycle changes of the transfers, published on [`TransferEvents`]
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(
+    tag = "type",
+    rename_all = "snake_case",
+    rename_all_fields = "camelCase"
+)]
+pub enum TransferEvent {
+    /// The endpoint is the one of the EDR, when the transfer has one
+    Started {
+        transfer_id: String,
+        endpoint: Option<String>,
+    },
+    Resumed {
+        transfer_id: String,
+        endpoint: Option<String>,
+    },
+    Suspended {
+        transfer_id: String,
+        reason: Option<String>,
+    },
+    Terminated {
+        transfer_id: String,
+        reason: Option<String>,
+    },
+    /// The provider completed a consumer transfer
+    Completed {
+        transfer_id: String,
+    },
+    TokenRefreshed {
+        transfer_id: String,
+    },
+    /// The transfer is unknown when the token could not be validated
+    ProxyAccessDenied {
+        transfer_id: Option<String>,
+        reason: String,
+    },
+}
+
+impl TransferEvent {
+    /// Names of all the events, see [`TransferEvent::name`]
+    pub const NAMES: [&'static str; 7] = [
+        "started",
+        "resumed",
+        "suspended",
+        "terminated",
+        "completed",
+        "token_refreshed",
+        "proxy_access_denied",
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TransferEvent::Started { .. } => "started",
+            TransferEvent::Resumed { .. } => "resumed",
+            TransferEvent::Suspended { .. } => "suspended",
+            TransferEvent::Terminated { .. } => "terminated",
+            TransferEvent::Completed { .. } => "completed",
+            TransferEvent::TokenRefreshed { .. } => "token_refreshed",
+            TransferEvent::ProxyAccessDenied { .. } => "proxy_access_denied",
+        }
+    }
+
+    pub fn transfer_id(&self) -> Option<&str> {
+        match self {
+            TransferEvent::Started { transfer_id, .. }
+            | TransferEvent::Resumed { transfer_id, .. }
+            | TransferEvent::Suspended { transfer_id, .. }
+            | TransferEvent::Terminated { transfer_id, .. }
+            | TransferEvent::Completed { transfer_id }
+            | TransferEvent::TokenRefreshed { transfer_id } => Some(transfer_id),
+            TransferEvent::ProxyAccessDenied { transfer_id, .. } => transfer_id.as_deref(),
+        }
+    }
+}
+
+/// In-process bus of the [`TransferEvent`]s, subscribers lagging more than the
+/// capacity behind miss the oldest events
+#[derive(Clone, Injectable)]
+pub struct TransferEvents {
+    sender: broadcast::Sender<TransferEvent>,
+}
+
+impl TransferEvents {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender }
+    }
+
+    pub fn publish(&self, event: TransferEvent) {
+        trace!("Publishing transfer event {:?}", event);
+        // Fails only when there are no subscribers
+        let _ = self.sender.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<TransferEvent> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for TransferEvents {
+    fn default() -> Self {
+        Self::new(1024)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{TransferEvent, TransferEvents};
+
+    #[tokio::test]
+    async fn publishes_to_all_subscribers() {
+        let events = TransferEvents::default();
+
+        let mut first = events.subscribe();
+        let mut second = events.subscribe();
+
+        events.publish(TransferEvent::Started {
+            transfer_id: "process_id".to_string(),
+            endpoint: None,
+        });
+
+        for receiver in [&mut first, &mut second] {
+            let event = receiver.recv().await.unwrap();
+            assert_eq!(event.name(), "started");
+            assert_eq!(event.transfer_id(), Some("process_id"));
+        }
+    }
+
+    #[test]
+    fn publish_without_subscribers() {
+        TransferEvents::default().publish(TransferEvent::TokenRefreshed {
+            transfer_id: "process_id".to_string(),
+        });
+    }
+
+    #[test]
+    fn serializes_with_type() {
+        let event = TransferEvent::ProxyAccessDenied {
+            transfer_id: None,
+            reason: "Missing token".to_string(),
+        };
+
+        assert_eq!(
+            serde_json::to_value(event).unwrap(),
+            json!({
+                "type": "proxy_access_denied",
+                "transferId": null,
+                "reason": "Missing token"
+            })
+        );
+    }
+}
@@ -0,0 +1,381 @@
+use bon::Builder;
+use chrono::{Duration, Utc};
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use tracing::{debug, warn};
+
+use crate::core::{
+    db::notification::NotificationRepoRef,
+    model::{
+        notification::{Notification, NotificationKind, NotificationStatus},
+        transfer::{Transfer, TransferStatus},
+    },
+};
+
+/// Decides the status changes the control plane is notified of, the notifications being
+/// written to the outbox together with the status change, see
+/// [`TransferRepo::transition`](crate::core::db::transfer::TransferRepo::transition)
+#[derive(Clone, Default)]
+pub struct NotificationService;
+
+impl NotificationService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Notification of a transfer that reached a final state and has a callback address
+    pub fn notification(&self, transfer: &Transfer) -> Option<Notification> {
+        let kind = match transfer.status {
+            TransferStatus::Completed => NotificationKind::Completed,
+            TransferStatus::Failed => NotificationKind::Failed,
+            TransferStatus::Terminated => NotificationKind::Terminated,
+            _ => return None,
+        };
+
+        let callback_address = transfer.callback_address.as_ref()?;
+
+        debug!(
+            "Enqueuing {:?} notification for transfer {}",
+            kind, transfer.id
+        );
+
+        Some(
+            Notification::builder()
+                .transfer_id(&transfer.id)
+                .callback_address(callback_address)
+                .kind(kind)
+                .maybe_reason(transfer.termination_reason.clone())
+                .build(),
+        )
+    }
+}
+
+/// Delivers the outbox to the control plane, retrying with an exponential backoff
+#[derive(Builder, Clone)]
+pub struct NotificationDispatcher {
+    repo: NotificationRepoRef,
+    #[builder(default)]
+    client: reqwest::Client,
+    max_attempts: i32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    batch_size: i32,
+    /// How long the claimed notifications are hidden from the other dispatchers
+    lease: Duration,
+}
+
+enum DeliveryError {
+    Retryable(String),
+    /// The control plane rejected the notification, retrying would not help
+    Permanent(String),
+}
+
+impl NotificationDispatcher {
+    /// Sends the due notifications and returns how many were delivered
+    pub async fn dispatch(&self) -> anyhow::Result<usize> {
+        let now = Utc::now();
+        let mut delivered = 0;
+
+        for mut notification in self
+            .repo
+            .claim_due(now, now + self.lease, self.batch_size)
+            .await?
+        {
+            match self.deliver(&notification).await {
+                Ok(()) => {
+                    notification.status = NotificationStatus::Delivered;
+                    delivered += 1;
+                }
+                Err(err) => {
+                    let (permanent, error) = match err {
+                        DeliveryError::Retryable(error) => (false, error),
+                        DeliveryError::Permanent(error) => (true, error),
+                    };
+
+                    notification.attempts += 1;
+
+                    if permanent || notification.attempts >= self.max_attempts {
+                        warn!(
+                            "Giving up on {:?} notification for transfer {} after {} attempts: {}",
+                            notification.kind,
+                            notification.transfer_id,
+                            notification.attempts,
+                            error
+                        );
+                        notification.status = NotificationStatus::Failed;
+                    } else {
+                        debug!(
+                            "Failed to deliver notification for transfer {}: {}",
+                            notification.transfer_id, error
+                        );
+                        notification.next_attempt_at = now + self.backoff(notification.attempts);
+                    }
+
+                    notification.last_error = Some(error);
+                }
+            }
+
+            notification.updated_at = now;
+            self.repo.save(notification).await?;
+        }
+
+        Ok(delivered)
+    }
+
+    fn backoff(&self, attempts: i32) -> Duration {
+        let factor = 2_i32.saturating_pow(attempts.saturating_sub(1) as u32);
+        self.initial_backoff
+            .checked_mul(factor)
+            .unwrap_or(self.max_backoff)
+            .min(self.max_backoff)
+    }
+
+    async fn deliver(&self, notification: &Notification) -> Result<(), DeliveryError> {
+        let (action, body) = request(notification);
+        let url = format!(
+            "{}/transferprocess/{}/{}",
+            notification.callback_address.trim_end_matches('/'),
+            notification.transfer_id,
+            action
+        );
+
+        let response = self
+            .client
+            .post(url)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|err| DeliveryError::Retryable(err.to_string()))?;
+
+        let status = response.status();
+
+        if status.is_success() {
+            return Ok(());
+        }
+
+        let error = format!("{}: {}", status, response.text().await.unwrap_or_default());
+
+        if status.is_client_error()
+            && status != StatusCode::REQUEST_TIMEOUT
+            && status != StatusCode::TOO_MANY_REQUESTS
+        {
+            Err(DeliveryError::Permanent(error))
+        } else {
+            Err(DeliveryError::Retryable(error))
+        }
+    }
+}
+
+fn request(notification: &Notification) -> (&'static str, Value) {
+    match notification.kind {
+        NotificationKind::Completed => ("complete", json!({})),
+        NotificationKind::Failed => (
+            "fail",
+            json!({ "errorMessage": notification.reason.clone().unwrap_or_default() }),
+        ),
+        NotificationKind::Terminated => ("terminate", json!({ "reason": notification.reason })),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+    use futures::FutureExt;
+    use serde_json::json;
+    use wiremock::{
+        matchers::{body_json, method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    use crate::{
+        core::{
+            db::notification::{MockNotificationRepo, NotificationRepoRef},
+            model::{
+                notification::{Notification, NotificationKind, NotificationStatus},
+                transfer::{Transfer, TransferStatus},
+            },
+        },
+        signaling::DataAddress,
+    };
+
+    use super::{NotificationDispatcher, NotificationService};
+
+    fn create_notification(callback_address: &str, kind: NotificationKind) -> Notification {
+        Notification::builder()
+            .transfer_id("process_id")
+            .callback_address(callback_address)
+            .kind(kind)
+            .reason("reason")
+            .build()
+    }
+
+    fn create_dispatcher(repo: MockNotificationRepo) -> NotificationDispatcher {
+        NotificationDispatcher::builder()
+            .repo(NotificationRepoRef::of(repo))
+            .max_attempts(3)
+            .initial_backoff(Duration::seconds(1))
+            .max_backoff(Duration::seconds(60))
+            .batch_size(10)
+            .lease(Duration::seconds(60))
+            .build()
+    }
+
+    async fn callback_server(action: &str, status: u16) -> MockServer {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path(format!("/transferprocess/process_id/{}", action)))
+            .respond_with(ResponseTemplate::new(status))
+            .mount(&server)
+            .await;
+        server
+    }
+
+    fn due(repo: &mut MockNotificationRepo, notification: Notification) {
+        repo.expect_claim_due()
+            .returning(move |_, _, _| futures::future::ok(vec![notification.clone()]).boxed());
+    }
+
+    #[test]
+    fn notify_final_transfers_only() {
+        let service = NotificationService::new();
+
+        let transfer = |status: TransferStatus, callback_address: Option<&str>| {
+            Transfer::builder()
+                .id("process_id".to_string())
+                .participant_id("participant_id".to_string())
+                .source(
+                    DataAddress::builder()
+                        .endpoint_type("HttpData".to_string())
+                        .endpoint_properties(vec![])
+                        .build(),
+                )
+                .status(status)
+                .maybe_callback_address(callback_address)
+                .termination_reason("reason")
+                .build()
+        };
+
+        assert!(service
+            .notification(&transfer(TransferStatus::Started, Some("http://cp")))
+            .is_none());
+        assert!(service
+            .notification(&transfer(TransferStatus::Failed, None))
+            .is_none());
+
+        let notification = service
+            .notification(&transfer(TransferStatus::Failed, Some("http://cp")))
+            .unwrap();
+        assert_eq!(notification.kind, NotificationKind::Failed);
+        assert_eq!(notification.reason.as_deref(), Some("reason"));
+        assert_eq!(notification.status, NotificationStatus::Pending);
+        assert_eq!(notification.callback_address, "http://cp");
+    }
+
+    #[tokio::test]
+    async fn dispatch_delivers() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/transferprocess/process_id/terminate"))
+            .and(body_json(json!({ "reason": "reason" })))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut repo = MockNotificationRepo::new();
+        due(
+            &mut repo,
+            create_notification(&server.uri(), NotificationKind::Terminated),
+        );
+
+        repo.expect_save()
+            .withf(|notification| notification.status == NotificationStatus::Delivered)
+            .times(1)
+            .returning(|_| futures::future::ok(()).boxed());
+
+        let delivered = create_dispatcher(repo).dispatch().await.unwrap();
+
+        assert_eq!(delivered, 1);
+        server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn dispatch_retries_with_backoff() {
+        let server = callback_server("complete", 503).await;
+
+        let mut notification = create_notification(&server.uri(), NotificationKind::Completed);
+        notification.attempts = 1;
+
+        let mut repo = MockNotificationRepo::new();
+        due(&mut repo, notification);
+
+        let now = Utc::now();
+        repo.expect_save()
+            .withf(move |notification| {
+                notification.status == NotificationStatus::Pending
+                    && notification.attempts == 2
+                    && notification.next_attempt_at >= now + Duration::seconds(2)
+                    && notification.next_attempt_at < now + Duration::seconds(4)
+                    && notification
+                        .last_error
+                        .as_deref()
+                        .is_some_and(|error| error.starts_with("503"))
+            })
+            .times(1)
+            .returning(|_| futures::future::ok(()).boxed());
+
+        let delivered = create_dispatcher(repo).dispatch().await.unwrap();
+
+        assert_eq!(delivered, 0);
+    }
+
+    #[tokio::test]
+    async fn dispatch_gives_up_after_max_attempts() {
+        let server = callback_server("fail", 503).await;
+
+        let mut notification = create_notification(&server.uri(), NotificationKind::Failed);
+        notification.attempts = 2;
+
+        let mut repo = MockNotificationRepo::new();
+        due(&mut repo, notification);
+
+        repo.expect_save()
+            .withf(|notification| {
+                notification.status == NotificationStatus::Failed && notification.attempts == 3
+            })
+            .times(1)
+            .returning(|_| futures::future::ok(()).boxed());
+
+        create_dispatcher(repo).dispatch().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn dispatch_gives_up_on_rejection() {
+        let server = callback_server("fail", 400).await;
+
+        let mut repo = MockNotificationRepo::new();
+        due(
+            &mut repo,
+            create_notification(&server.uri(), NotificationKind::Failed),
+        );
+
+        repo.expect_save()
+            .withf(|notification| {
+                notification.status == NotificationStatus::Failed && notification.attempts == 1
+            })
+            .times(1)
+            .returning(|_| futures::future::ok(()).boxed());
+
+        create_dispatcher(repo).dispatch().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn backoff_is_capped() {
+        let dispatcher = create_dispatcher(MockNotificationRepo::new());
+
+        assert_eq!(dispatcher.backoff(1), Duration::seconds(1));
+        assert_eq!(dispatcher.backoff(4), Duration::seconds(8));
+        assert_eq!(dispatcher.backoff(10), Duration::seconds(60));
+        assert_eq!(dispatcher.backoff(40), Duration::seconds(60));
+    }
+}
@@ -0,0 +1,118 @@
+use bon::Builder;
+use chrono::{DateTime, Duration, Utc};
+use tracing::{debug, info};
+
+use crate::core::{
+    db::transfer::TransferQuery,
+    model::transfer::TransferStatus,
+    service::transfer::{TransferResult, TransferService},
+};
+
+const FINAL: [TransferStatus; 3] = [
+    TransferStatus::Terminated,
+    TransferStatus::Completed,
+    TransferStatus::Failed,
+];
+
+/// Terminates the expired transfers, deletes the final ones past the retention and
+/// purges the state left behind for them. Meant to run on a single replica, the replicas
+/// running it concurrently would race on the same transfers.
+#[derive(Builder, Clone)]
+pub struct TransferReaper {
+    transfers: TransferService,
+    /// Transfers not in a final state are terminated past this age
+    max_age: Option<Duration>,
+    /// Transfers in a final state are deleted once unchanged for this long, kept when unset
+    retention: Option<Duration>,
+    #[builder(default = 50)]
+    batch_size: i32,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct ReaperReport {
+    pub terminated: usize,
+    pub deleted: usize,
+    pub purged: usize,
+}
+
+impl TransferReaper {
+    pub async fn run(&self) -> TransferResult<ReaperReport> {
+        let now = Utc::now();
+        let mut report = ReaperReport::default();
+
+        if let Some(max_age) = self.max_age {
+            report.terminated += self
+                .terminate(
+                    TransferQuery::builder()
+                        .limit(self.batch_size)
+                        .created_before(now - max_age)
+                        .build(),
+                    "Maximum transfer age exceeded",
+                )
+                .await?;
+        }
+
+        report.terminated += self
+            .terminate(
+                TransferQuery::builder()
+                    .limit(self.batch_size)
+                    .agreement_ended_before(now)
+                    .build(),
+                "Contract agreement ended",
+            )
+            .await?;
+
+        if let Some(retention) = self.retention {
+            report.deleted = self.delete(now - retention).await?;
+        }
+        report.purged = self.transfers.purge(self.batch_size).await?;
+
+        if report == ReaperReport::default() {
+            debug!("Reaper found nothing to clean up");
+        } else {
+            info!(
+                "Reaper terminated {} transfers, deleted {} transfers and purged {} entries",
+                report.terminated, report.deleted, report.purged
+            );
+        }
+
+        Ok(report)
+    }
+
+    async fn terminate(&self, query: TransferQuery, reason: &str) -> TransferResult<usize> {
+        self.transfers
+            .terminate_matching(query, Some(reason.to_string()))
+            .await
+    }
+
+    async fn delete(&self, updated_before: DateTime<Utc>) -> TransferResult<usize> {
+        let mut deleted = 0;
+
+        for status in FINAL {
+            loop {
+                let page = self
+                    .transfers
+                    .query(
+                        TransferQuery::builder()
+                            .limit(self.batch_size)
+                            .status(status.clone())
+                            .updated_before(updated_before)
+                            .build(),
+                    )
+                    .await?;
+
+                if page.is_empty() {
+                    break;
+                }
+
+                for transfer in page {
+                    self.transfers.delete(&transfer.id).await?;
+                    debug!("Deleted {:?} transfer {}", transfer.status, transfer.id);
+                    deleted += 1;
+                }
+            }
+        }
+
+        Ok(deleted)
+    }
+}
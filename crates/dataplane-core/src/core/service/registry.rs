@@ -0,0 +1,370 @@
+use std::sync::{Arc, RwLock};
+
+use axum::http::Uri;
+use miwa::derive::Injectable;
+
+use crate::{
+    core::{
+        model::{
+            namespace::compact_iri,
+            transfer::types::{DataAddressError, EndpointType, FileType, HttpDataType},
+        },
+        service::transfer::{TransferError, TransferManagerRef, TransferResult},
+    },
+    signaling::DataAddress,
+};
+
+/// Managers of the transfers by transfer type, such as `HttpData-PULL` or `HttpData-PUSH`.
+///
+/// Extensions register their manager at initialization, the registry being shared the
+/// services resolving managers see every registration.
+#[derive(Clone, Default, Injectable)]
+pub struct TransferManagers {
+    managers: Arc<RwLock<Vec<Registration>>>,
+}
+
+struct Registration {
+    types: Vec<String>,
+    manager: TransferManagerRef,
+}
+
+impl TransferManagers {
+    /// Registers a manager for the given transfer types, a type handled by another manager
+    /// is rejected
+    pub fn register<T: Into<String>>(
+        &self,
+        types: impl IntoIterator<Item = T>,
+        manager: TransferManagerRef,
+    ) -> anyhow::Result<()> {
+        let types = types
+            .into_iter()
+            .map(|ty| compact_iri(&ty.into()).to_string())
+            .collect::<Vec<_>>();
+
+        let mut managers = self.managers.write().unwrap();
+
+        if let Some(ty) = types
+            .iter()
+            .find(|ty| managers.iter().any(|r| r.types.contains(ty)))
+        {
+            anyhow::bail!("Transfer type {} is already handled by another manager", ty);
+        }
+
+        managers.push(Registration { types, manager });
+        Ok(())
+    }
+
+    /// Union of the types handled by the registered managers
+    pub fn types(&self) -> Vec<String> {
+        let mut types = self
+            .managers
+            .read()
+            .unwrap()
+            .iter()
+            .flat_map(|r| r.types.iter().cloned())
+            .collect::<Vec<_>>();
+
+        types.sort();
+        types.dedup();
+        types
+    }
+
+    /// Manager of the transfer type, falling back to a manager registered without types
+    /// which handles the transfers of any type
+    pub fn resolve(&self, transfer_type: &str) -> TransferResult<TransferManagerRef> {
+        let managers = self.managers.read().unwrap();
+        let transfer_type = compact_iri(transfer_type);
+
+        managers
+            .iter()
+            .find(|r| r.types.iter().any(|ty| ty == transfer_type))
+            .or_else(|| managers.iter().find(|r| r.types.is_empty()))
+            .map(|r| r.manager.clone())
+            .ok_or(TransferError::Unsupported)
+    }
+
+    pub fn all(&self) -> Vec<TransferManagerRef> {
+        self.managers
+            .read()
+            .unwrap()
+            .iter()
+            .map(|r| r.manager.clone())
+            .collect()
+    }
+}
+
+impl From<TransferManagerRef> for TransferManagers {
+    fn from(manager: TransferManagerRef) -> Self {
+        let managers = TransferManagers::default();
+        managers.managers.write().unwrap().push(Registration {
+            types: vec![],
+            manager,
+        });
+        managers
+    }
+}
+
+/// Endpoint types the dataplane understands, `HttpData` and `File` being registered by
+/// default
+#[derive(Clone, Injectable)]
+pub struct EndpointTypes {
+    types: Arc<RwLock<Vec<Arc<dyn EndpointType>>>>,
+}
+
+impl EndpointTypes {
+    /// Without any type, not even the default ones
+    pub fn empty() -> Self {
+        Self {
+            types: Arc::default(),
+        }
+    }
+
+    pub fn register(&self, endpoint_type: impl EndpointType + 'static) -> anyhow::Result<()> {
+        let mut types = self.types.write().unwrap();
+
+        if types.iter().any(|ty| ty.name() == endpoint_type.name()) {
+            anyhow::bail!(
+                "Endpoint type {} is already registered",
+                endpoint_type.name()
+            );
+        }
+
+        types.push(Arc::new(endpoint_type));
+        Ok(())
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        let mut names = self
+            .types
+            .read()
+            .unwrap()
+            .iter()
+            .map(|ty| ty.name().to_string())
+            .collect::<Vec<_>>();
+
+        names.sort();
+        names
+    }
+
+    /// Type of the address, failing with [`DataAddressError::UnsupportedType`] when it is
+    /// not registered
+    pub fn get(&self, address: &DataAddress) -> Result<Arc<dyn EndpointType>, DataAddressError> {
+        self.types
+            .read()
+            .unwrap()
+            .iter()
+            .find(|ty| address.is_type(ty.name()))
+            .cloned()
+            .ok_or_else(|| DataAddressError::UnsupportedType(address.endpoint_type.clone()))
+    }
+
+    pub fn validate(&self, address: &DataAddress) -> Vec<DataAddressError> {
+        match self.get(address) {
+            Ok(endpoint_type) => endpoint_type.validate(address),
+            Err(err) => vec![err],
+        }
+    }
+
+    /// URL the pull proxy forwards the requests to, `None` when the type cannot be proxied
+    pub fn pull_url(&self, address: &DataAddress) -> Result<Option<Uri>, DataAddressError> {
+        self.get(address)?.pull_url(address)
+    }
+}
+
+impl Default for EndpointTypes {
+    fn default() -> Self {
+        Self {
+            types: Arc::new(RwLock::new(vec![
+                Arc::new(HttpDataType),
+                Arc::new(FileType),
+            ])),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::FutureExt;
+
+    use crate::{
+        core::{
+            model::{
+                namespace::EDC_NAMESPACE,
+                transfer::{
+                    types::{DataAddressError, EndpointType},
+                    Transfer, TransferStatus,
+                },
+            },
+            service::transfer::{MockTransferManager, TransferError, TransferManagerRef},
+        },
+        signaling::{DataAddress, EndpointProperty},
+    };
+
+    use super::{EndpointTypes, TransferManagers};
+
+    /// Tells the managers apart by the answer of `can_handle`
+    fn manager(handles: bool) -> TransferManagerRef {
+        let mut manager = MockTransferManager::new();
+        manager
+            .expect_can_handle()
+            .returning(move |_| futures::future::ok(handles).boxed());
+        TransferManagerRef::of(manager)
+    }
+
+    async fn resolved(managers: &TransferManagers, transfer_type: &str) -> bool {
+        let transfer = Transfer::builder()
+            .id("process_id".to_string())
+            .participant_id("participant_id".to_string())
+            .status(TransferStatus::Received)
+            .build();
+
+        managers
+            .resolve(transfer_type)
+            .unwrap()
+            .can_handle(&transfer)
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn advertises_union_of_types() {
+        let managers = TransferManagers::default();
+
+        managers.register(["HttpData-PULL"], manager(true)).unwrap();
+        managers
+            .register(
+                [
+                    EDC_NAMESPACE.to_iri("HttpData-PUSH"),
+                    "File-PUSH".to_string(),
+                ],
+                manager(true),
+            )
+            .unwrap();
+
+        assert_eq!(
+            managers.types(),
+            vec!["File-PUSH", "HttpData-PULL", "HttpData-PUSH"]
+        );
+    }
+
+    #[test]
+    fn rejects_type_handled_twice() {
+        let managers = TransferManagers::default();
+
+        managers.register(["HttpData-PULL"], manager(true)).unwrap();
+        assert!(managers
+            .register(["HttpData-PUSH", "HttpData-PULL"], manager(true))
+            .is_err());
+
+        assert_eq!(managers.types(), vec!["HttpData-PULL"]);
+    }
+
+    #[tokio::test]
+    async fn resolves_by_type() {
+        let managers = TransferManagers::default();
+
+        managers.register(["HttpData-PULL"], manager(true)).unwrap();
+        assert!(resolved(&managers, "HttpData-PULL").await);
+        assert!(matches!(
+            managers.resolve("HttpData-PUSH"),
+            Err(TransferError::Unsupported)
+        ));
+
+        managers
+            .register(["HttpData-PUSH"], manager(false))
+            .unwrap();
+        assert!(!resolved(&managers, "HttpData-PUSH").await);
+        assert!(resolved(&managers, &EDC_NAMESPACE.to_iri("HttpData-PULL")).await);
+        assert!(matches!(
+            managers.resolve("File-PUSH"),
+            Err(TransferError::Unsupported)
+        ));
+    }
+
+    #[tokio::test]
+    async fn resolves_any_type_to_catch_all() {
+        let managers = TransferManagers::from(manager(true));
+
+        managers
+            .register(["HttpData-PUSH"], manager(false))
+            .unwrap();
+        assert!(!resolved(&managers, "HttpData-PUSH").await);
+        assert!(resolved(&managers, "HttpData-PULL").await);
+        assert!(resolved(&managers, "File-PUSH").await);
+    }
+
+    struct KafkaType;
+
+    impl EndpointType for KafkaType {
+        fn name(&self) -> &str {
+            "Kafka"
+        }
+
+        fn validate(&self, address: &DataAddress) -> Vec<DataAddressError> {
+            match address.get_property("topic") {
+                Some(_) => vec![],
+                None => vec![DataAddressError::MissingProperty("topic".to_string())],
+            }
+        }
+    }
+
+    fn address(endpoint_type: &str, properties: &[(&str, &str)]) -> DataAddress {
+        DataAddress::builder()
+            .endpoint_type(endpoint_type.to_string())
+            .endpoint_properties(
+                properties
+                    .iter()
+                    .map(|(name, value)| {
+                        EndpointProperty::builder()
+                            .name(*name)
+                            .value(*value)
+                            .build()
+                    })
+                    .collect(),
+            )
+            .build()
+    }
+
+    #[test]
+    fn validates_with_registered_endpoint_types() {
+        let endpoint_types = EndpointTypes::default();
+        let kafka = address(&EDC_NAMESPACE.to_iri("Kafka"), &[]);
+
+        assert!(matches!(
+            endpoint_types.validate(&kafka).as_slice(),
+            [DataAddressError::UnsupportedType(_)]
+        ));
+
+        endpoint_types.register(KafkaType).unwrap();
+        assert!(endpoint_types.register(KafkaType).is_err());
+        assert_eq!(endpoint_types.names(), vec!["File", "HttpData", "Kafka"]);
+
+        assert!(matches!(
+            endpoint_types.validate(&kafka).as_slice(),
+            [DataAddressError::MissingProperty(name)] if name == "topic"
+        ));
+        assert!(endpoint_types
+            .validate(&address("Kafka", &[("topic", "events")]))
+            .is_empty());
+        assert!(endpoint_types
+            .pull_url(&address("Kafka", &[("topic", "events")]))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn http_data_is_pulled_from_its_base_url() {
+        let endpoint_types = EndpointTypes::default();
+
+        let http = address("HttpData", &[("baseUrl", "https://example.com/data")]);
+        assert_eq!(
+            endpoint_types.pull_url(&http).unwrap().unwrap(),
+            "https://example.com/data"
+        );
+        assert!(endpoint_types
+            .pull_url(&address("File", &[("path", "/tmp/data")]))
+            .unwrap()
+            .is_none());
+        assert!(EndpointTypes::empty().pull_url(&http).is_err());
+    }
+}
@@ -1,66 +1,652 @@
+use std::collections::HashMap;
+
 use async_trait::async_trait;
+use chrono::Utc;
 use miwa::derive::interface;
 
 use miwa::derive::Injectable;
 #[cfg(test)]
 use mockall::{automock, predicate::*};
-use tracing::debug;
+use serde_json::Value;
+use sqlx::types::Json;
+use thiserror::Error;
+use tracing::{debug, error, warn};
 
 use crate::{
     core::{
-        db::transfer::TransferRepoRef,
-        model::transfer::{Transfer, TransferStatus},
+        db::transfer::{TransferQuery, TransferRepoRef},
+        model::{
+            notification::Notification,
+            transfer::{types::DataAddressError, Transfer, TransferEdr, TransferStatus},
+        },
+        service::{
+            events::{TransferEvent, TransferEvents},
+            notification::NotificationService,
+            registry::TransferManagers,
+        },
+    },
+    signaling::{
+        DataAddress, DataFlowPrepareMessage, DataFlowResponseMessage, DataFlowStartMessage,
     },
-    signaling::{DataAddress, DataFlowResponseMessage, DataFlowStartMessage},
 };
 
+pub type TransferResult<T> = Result<T, TransferError>;
+
+const LIVE: [TransferStatus; 4] = [
+    TransferStatus::Prepared,
+    TransferStatus::Received,
+    TransferStatus::Started,
+    TransferStatus::Suspended,
+];
+
+/// Fragments of the property names whose values are not stored, such as `authorization`
+/// or `https://w3id.org/edc/v0.0.1/ns/apiKey`
+const SECRET_PROPERTIES: [&str; 6] = [
+    "secret",
+    "password",
+    "token",
+    "authorization",
+    "apikey",
+    "credential",
+];
+
+const REDACTED: &str = "***";
+
 #[derive(Clone, Injectable)]
 pub struct TransferService {
-    manager: TransferManagerRef,
+    managers: TransferManagers,
     db: TransferRepoRef,
+    notifications: Option<NotificationService>,
+    events: Option<TransferEvents>,
 }
 
 impl TransferService {
-    pub fn new(manager: TransferManagerRef, db: TransferRepoRef) -> Self {
-        Self { manager, db }
+    /// Transfers are routed to the manager of their type, see [`TransferManagers`]
+    pub fn new(managers: impl Into<TransferManagers>, db: TransferRepoRef) -> Self {
+        Self {
+            managers: managers.into(),
+            db,
+            notifications: None,
+            events: None,
+        }
+    }
+
+    /// Notifies the control plane of the transfers terminated by the dataplane
+    pub fn with_notifications(mut self, notifications: NotificationService) -> Self {
+        self.notifications = Some(notifications);
+        self
+    }
+
+    /// Publishes the lifecycle changes of the transfers
+    pub fn with_events(mut self, events: TransferEvents) -> Self {
+        self.events = Some(events);
+        self
     }
 
     pub async fn start(
         &self,
         req: DataFlowStartMessage,
-    ) -> anyhow::Result<DataFlowResponseMessage> {
-        let transfer = Transfer::builder()
+    ) -> TransferResult<DataFlowResponseMessage> {
+        let received = Transfer::builder()
+            .maybe_agreement_end(req.agreement_end())
+            .maybe_transfer_type(req.transfer_type())
+            .agreement_id(req.agreement_id())
+            .dataset_id(req.dataset_id())
+            .properties(Json(redacted(req.properties())))
             .id(req.process_id.clone())
             .participant_id(req.participant_id.clone())
-            .source(req.source_data_address)
-            .status(TransferStatus::Started)
+            .flow_type(req.flow_type.clone())
+            .source(req.source_data_address.clone())
+            .maybe_destination(req.destination_data_address.clone().map(Json))
+            .maybe_callback_address(req.callback_address.clone())
+            .status(TransferStatus::Received)
             .build();
 
-        if self.manager.can_handle(&transfer).await? {
-            let address = self.manager.handle_start(&transfer).await?;
-            self.db.save(transfer).await?;
-            Ok(DataFlowResponseMessage::new(address))
+        // Storing the received transfer claims the process id, a concurrent start with the
+        // same id finds it and takes the path of a retry
+        let claimed = self
+            .db
+            .create(received.clone())
+            .await
+            .map_err(TransferError::Storage)?;
+
+        let transfer = if claimed {
+            received
+        } else {
+            let existing = self
+                .db
+                .fetch_by_id(&req.process_id)
+                .await
+                .map_err(TransferError::Storage)?
+                .ok_or_else(|| TransferError::Modified(req.process_id.clone()))?;
+
+            if !is_same_start(&existing, &req) {
+                return Err(TransferError::Conflict(existing.id));
+            }
+
+            match existing.status {
+                TransferStatus::Started => {
+                    debug!("Transfer with id {} is already started", existing.id);
+                    let address = self.manager(&existing)?.reissue(&existing).await?;
+                    return Ok(DataFlowResponseMessage::new(address));
+                }
+                TransferStatus::Received => {
+                    debug!("Transfer with id {} is being started", existing.id);
+                    return Err(TransferError::Modified(existing.id));
+                }
+                _ => {}
+            }
+
+            debug!("Resuming transfer with id {}", existing.id);
+            check_transition(&existing, &TransferStatus::Started)?;
+            existing
+        };
+
+        let address = match self.start_transfer(&transfer).await {
+            Ok(address) => address,
+            Err(err) => {
+                // Release the claim, unless the transfer moved on in the meantime
+                if claimed && !matches!(err, TransferError::Modified(_)) {
+                    if let Err(delete_err) = self.db.delete(&transfer.id).await {
+                        error!(
+                            "Failed to release the claim on transfer {}: {:#}",
+                            transfer.id, delete_err
+                        );
+                    }
+                }
+                return Err(err);
+            }
+        };
+
+        let transfer_id = transfer.id;
+        let endpoint = endpoint_of(address.as_ref());
+        self.publish(if transfer.status == TransferStatus::Suspended {
+            TransferEvent::Resumed {
+                transfer_id,
+                endpoint,
+            }
         } else {
-            Err(anyhow::anyhow!("Transfer not supported"))
+            TransferEvent::Started {
+                transfer_id,
+                endpoint,
+            }
+        });
+
+        Ok(DataFlowResponseMessage::new(address))
+    }
+
+    /// Consumer side, provisions the destination of the transfer
+    pub async fn prepare(
+        &self,
+        req: DataFlowPrepareMessage,
+    ) -> TransferResult<DataFlowResponseMessage> {
+        if let Some(existing) = self
+            .db
+            .fetch_by_id(&req.process_id)
+            .await
+            .map_err(TransferError::Storage)?
+        {
+            if existing.status != TransferStatus::Prepared {
+                return Err(TransferError::IllegalTransition {
+                    id: existing.id,
+                    from: existing.status,
+                    to: TransferStatus::Prepared,
+                });
+            }
+
+            debug!("Transfer with id {} is already prepared", existing.id);
+            return Ok(DataFlowResponseMessage::prepared(
+                existing.destination.map(|destination| destination.0),
+            ));
+        }
+
+        let mut transfer = Transfer::builder()
+            .maybe_transfer_type(req.transfer_type())
+            .agreement_id(req.agreement_id())
+            .dataset_id(req.dataset_id())
+            .properties(Json(redacted(req.properties())))
+            .id(req.process_id)
+            .participant_id(req.participant_id)
+            .flow_type(req.flow_type)
+            .maybe_destination(req.destination_data_address.map(Json))
+            .maybe_callback_address(req.callback_address)
+            .status(TransferStatus::Prepared)
+            .consumer(true)
+            .build();
+
+        let destination = self.manager(&transfer)?.handle_prepare(&transfer).await?;
+        transfer.destination = destination.clone().map(Json);
+
+        self.db
+            .save(transfer)
+            .await
+            .map_err(TransferError::Storage)?;
+
+        Ok(DataFlowResponseMessage::prepared(destination))
+    }
+
+    /// Consumer side, the provider started the transfer
+    pub async fn started(&self, id: String, source: Option<DataAddress>) -> TransferResult<()> {
+        debug!("Transfer with id {} started by the provider", id);
+
+        let mut transfer = self.fetch(&id).await?;
+        check_consumer(&transfer)?;
+        check_transition(&transfer, &TransferStatus::Started)?;
+
+        let from = transfer.status.clone();
+        if let Some(source) = source {
+            transfer.source = Some(Json(source));
+        }
+        transfer.status = TransferStatus::Started;
+        transfer.updated_at = Utc::now();
+
+        let manager = self.manager(&transfer)?;
+        manager.handle_started(&transfer).await?;
+
+        if let Err(err) = self.transition(&transfer, from).await {
+            // A concurrent completion or termination already released the transfer, the
+            // state saved for it since is released too
+            if self
+                .fetch(&id)
+                .await
+                .is_ok_and(|current| current.status.is_final())
+            {
+                if let Err(err) = manager.handle_terminate(&id).await {
+                    error!("Failed to release transfer {}: {}", id, err);
+                }
+            }
+            return Err(err);
         }
+
+        let endpoint = endpoint_of(transfer.source.as_ref().map(|source| &source.0));
+
+        self.publish(TransferEvent::Started {
+            transfer_id: id,
+            endpoint,
+        });
+        Ok(())
+    }
+
+    /// Consumer side, the provider completed the transfer
+    pub async fn complete(&self, id: String) -> TransferResult<()> {
+        debug!("Transfer with id {} completed by the provider", id);
+
+        let mut transfer = self.fetch(&id).await?;
+        check_consumer(&transfer)?;
+        check_transition(&transfer, &TransferStatus::Completed)?;
+
+        self.manager(&transfer)?.handle_terminate(&id).await?;
+
+        let from = transfer.status.clone();
+        let now = Utc::now();
+        transfer.status = TransferStatus::Completed;
+        transfer.terminated_at = Some(now);
+        transfer.updated_at = now;
+
+        self.transition(&transfer, from).await?;
+
+        self.publish(TransferEvent::Completed { transfer_id: id });
+        Ok(())
+    }
+
+    pub async fn get(&self, id: &str) -> TransferResult<Option<Transfer>> {
+        self.db
+            .fetch_by_id(id)
+            .await
+            .map_err(TransferError::Storage)
     }
 
-    pub async fn get(&self, id: &str) -> anyhow::Result<Option<Transfer>> {
-        self.db.fetch_by_id(id).await
+    pub async fn query(&self, query: TransferQuery) -> TransferResult<Vec<Transfer>> {
+        self.db.query(query).await.map_err(TransferError::Storage)
     }
 
-    pub async fn suspend(&self, id: String) -> anyhow::Result<()> {
-        debug!("Suspending transfer with id {}", id);
+    pub async fn suspend(&self, id: String, reason: Option<String>) -> TransferResult<()> {
+        debug!(
+            "Suspending transfer with id {} with reason: {:?}",
+            id, reason
+        );
+
+        let mut transfer = self.fetch(&id).await?;
+        check_transition(&transfer, &TransferStatus::Suspended)?;
+
+        self.manager(&transfer)?.handle_suspend(&id).await?;
+
+        let from = transfer.status.clone();
+        let now = Utc::now();
+        transfer.status = TransferStatus::Suspended;
+        transfer.suspension_reason = reason.clone();
+        transfer.suspended_at = Some(now);
+        transfer.updated_at = now;
+
+        self.transition(&transfer, from).await?;
 
-        self.db.change_status(id, TransferStatus::Suspended).await
+        self.publish(TransferEvent::Suspended {
+            transfer_id: id,
+            reason,
+        });
+        Ok(())
     }
 
-    pub async fn terminate(&self, id: String, reason: Option<String>) -> anyhow::Result<()> {
+    /// Terminates the transfer on request of the control plane, which is not notified back
+    pub async fn terminate(&self, id: String, reason: Option<String>) -> TransferResult<()> {
+        self.terminate_transfer(id, reason, false).await
+    }
+
+    /// Terminates the transfer, `notify` telling whether the control plane is notified as
+    /// for the terminations decided by the dataplane
+    async fn terminate_transfer(
+        &self,
+        id: String,
+        reason: Option<String>,
+        notify: bool,
+    ) -> TransferResult<()> {
         debug!(
             "Terminating transfer with id {} with reason: {:?}",
             id, reason
         );
-        self.db.delete(&id).await
+
+        let mut transfer = self.fetch(&id).await?;
+        check_transition(&transfer, &TransferStatus::Terminated)?;
+
+        self.manager(&transfer)?.handle_terminate(&id).await?;
+
+        let from = transfer.status.clone();
+        let now = Utc::now();
+        transfer.status = TransferStatus::Terminated;
+        transfer.termination_reason = reason;
+        transfer.terminated_at = Some(now);
+        transfer.updated_at = now;
+
+        if notify {
+            self.transition(&transfer, from).await?;
+        } else {
+            self.save_transition(&transfer, from, None).await?;
+        }
+
+        self.publish(TransferEvent::Terminated {
+            transfer_id: id,
+            reason: transfer.termination_reason,
+        });
+        Ok(())
+    }
+
+    /// Terminates every live transfer of the contract agreement, e.g. once it is revoked.
+    /// Returns the number of terminated transfers
+    pub async fn terminate_agreement(
+        &self,
+        agreement_id: &str,
+        reason: Option<String>,
+    ) -> TransferResult<usize> {
+        self.terminate_matching(
+            TransferQuery::builder().agreement_id(agreement_id).build(),
+            reason,
+        )
+        .await
+    }
+
+    /// Terminates the live transfers matching the query, a page of its limit at a time, the
+    /// control plane being notified of each termination.
+    /// The transfers failing to terminate are logged and skipped, returns the number of
+    /// terminated transfers
+    pub async fn terminate_matching(
+        &self,
+        query: TransferQuery,
+        reason: Option<String>,
+    ) -> TransferResult<usize> {
+        let mut terminated = 0;
+
+        for status in LIVE {
+            // Terminated transfers leave the results, the failed ones are skipped
+            let mut offset = 0;
+            loop {
+                let page = self
+                    .query(TransferQuery {
+                        status: Some(status.clone()),
+                        offset,
+                        ..query.clone()
+                    })
+                    .await?;
+
+                if page.is_empty() {
+                    break;
+                }
+
+                for transfer in page {
+                    match self
+                        .terminate_transfer(transfer.id.clone(), reason.clone(), true)
+                        .await
+                    {
+                        Ok(()) => terminated += 1,
+                        Err(err) => {
+                            warn!("Failed to terminate transfer {}: {}", transfer.id, err);
+                            offset += 1;
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(terminated)
+    }
+
+    /// Starts the transfer with its manager and saves it as started, rolling back the start
+    /// when the transfer cannot be saved
+    async fn start_transfer(&self, transfer: &Transfer) -> TransferResult<Option<DataAddress>> {
+        let manager = self.manager(transfer)?;
+
+        if !manager.can_handle(transfer).await? {
+            return Err(TransferError::Unsupported);
+        }
+
+        let address = manager.handle_start(transfer).await?;
+
+        let mut started = transfer.clone();
+        started.status = TransferStatus::Started;
+        started.suspension_reason = None;
+        started.suspended_at = None;
+        started.updated_at = Utc::now();
+
+        if let Err(err) = self.transition(&started, transfer.status.clone()).await {
+            // Undo what the manager did for the start, so that no access is granted
+            // for a transfer that was not persisted
+            if let Err(rollback_err) = manager.rollback_start(transfer).await {
+                error!(
+                    "Failed to roll back the start of transfer {}: {:#}",
+                    transfer.id, rollback_err
+                );
+            }
+            return Err(err);
+        }
+
+        manager.commit_start(&started).await;
+
+        Ok(address)
+    }
+
+    /// Saves the status change of the transfer with the notification of the control plane,
+    /// failing when the transfer changed since it was read
+    async fn transition(&self, transfer: &Transfer, from: TransferStatus) -> TransferResult<()> {
+        let notification = self
+            .notifications
+            .as_ref()
+            .and_then(|notifications| notifications.notification(transfer));
+
+        self.save_transition(transfer, from, notification).await
+    }
+
+    async fn save_transition(
+        &self,
+        transfer: &Transfer,
+        from: TransferStatus,
+        notification: Option<Notification>,
+    ) -> TransferResult<()> {
+        let saved = self
+            .db
+            .transition(transfer.clone(), from, notification)
+            .await
+            .map_err(TransferError::Storage)?;
+
+        if saved {
+            Ok(())
+        } else {
+            Err(TransferError::Modified(transfer.id.clone()))
+        }
+    }
+
+    fn publish(&self, event: TransferEvent) {
+        if let Some(events) = &self.events {
+            events.publish(event);
+        }
+    }
+
+    /// Removes a transfer without notifying the manager, only meant for transfers in a
+    /// final state
+    pub async fn delete(&self, id: &str) -> TransferResult<()> {
+        self.db.delete(id).await.map_err(TransferError::Storage)
+    }
+
+    /// EDR of the transfer as known by its manager
+    pub async fn edr(&self, transfer: &Transfer) -> TransferResult<Option<TransferEdr>> {
+        self.manager(transfer)?.edr(transfer).await
+    }
+
+    /// Removes the state kept by the managers for transfers that no longer live, the
+    /// transfers being looked up `batch_size` at a time
+    pub async fn purge(&self, batch_size: i32) -> TransferResult<usize> {
+        let mut purged = 0;
+        for manager in self.managers.all() {
+            purged += manager.purge(&self.db, batch_size).await?;
+        }
+        Ok(purged)
+    }
+
+    /// Manager of the transfer, a transfer without type being an `HttpData` one as before
+    /// the types were stored
+    fn manager(&self, transfer: &Transfer) -> TransferResult<TransferManagerRef> {
+        match &transfer.transfer_type {
+            Some(transfer_type) => self.managers.resolve(transfer_type),
+            None => self
+                .managers
+                .resolve(&format!("HttpData-{}", transfer.flow_type)),
+        }
+    }
+
+    async fn fetch(&self, id: &str) -> TransferResult<Transfer> {
+        self.db
+            .fetch_by_id(id)
+            .await
+            .map_err(TransferError::Storage)?
+            .ok_or_else(|| TransferError::NotFound(id.to_string()))
+    }
+}
+
+/// Properties of a message as stored, the values of the secret ones being redacted. Only
+/// the top level properties are inspected
+fn redacted(properties: &HashMap<String, Value>) -> HashMap<String, Value> {
+    properties
+        .iter()
+        .map(|(name, value)| {
+            let normalized = name.to_lowercase().replace(['-', '_'], "");
+            if SECRET_PROPERTIES
+                .iter()
+                .any(|secret| normalized.contains(secret))
+            {
+                (name.clone(), Value::String(REDACTED.to_string()))
+            } else {
+                (name.clone(), value.clone())
+            }
+        })
+        .collect()
+}
+
+/// Whether a repeated start message carries the same payload as the one the transfer was created with,
+/// the fields unknown for transfers stored before they were kept are not compared
+fn is_same_start(transfer: &Transfer, req: &DataFlowStartMessage) -> bool {
+    transfer.participant_id == req.participant_id
+        && transfer
+            .agreement_id
+            .as_deref()
+            .is_none_or(|agreement_id| agreement_id == req.agreement_id())
+        && transfer
+            .dataset_id
+            .as_deref()
+            .is_none_or(|dataset_id| dataset_id == req.dataset_id())
+        && transfer
+            .properties
+            .as_ref()
+            .is_none_or(|properties| properties.0 == redacted(req.properties()))
+        && (transfer.transfer_type.is_none() || transfer.transfer_type == req.transfer_type())
+        && transfer.flow_type == req.flow_type
+        && transfer.source.as_ref().map(|source| &source.0) == Some(&req.source_data_address)
+        && transfer
+            .destination
+            .as_ref()
+            .map(|destination| &destination.0)
+            == req.destination_data_address.as_ref()
+        && transfer.callback_address == req.callback_address
+}
+
+fn endpoint_of(address: Option<&DataAddress>) -> Option<String> {
+    address
+        .and_then(|address| address.get_property("endpoint"))
+        .map(String::from)
+}
+
+/// Only the consumer transfers are started and completed by the provider
+fn check_consumer(transfer: &Transfer) -> TransferResult<()> {
+    if transfer.consumer {
+        Ok(())
+    } else {
+        Err(TransferError::NotConsumer(transfer.id.clone()))
+    }
+}
+
+fn check_transition(transfer: &Transfer, next: &TransferStatus) -> TransferResult<()> {
+    if transfer.status.can_transition_to(next) {
+        Ok(())
+    } else {
+        Err(TransferError::IllegalTransition {
+            id: transfer.id.clone(),
+            from: transfer.status.clone(),
+            to: next.clone(),
+        })
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum TransferError {
+    #[error("Transfer {0} not found")]
+    NotFound(String),
+    #[error("Transfer {id} cannot transition from {from:?} to {to:?}")]
+    IllegalTransition {
+        id: String,
+        from: TransferStatus,
+        to: TransferStatus,
+    },
+    #[error("Transfer {0} already exists with a different payload")]
+    Conflict(String),
+    #[error("Transfer {0} was modified concurrently")]
+    Modified(String),
+    #[error("Transfer {0} is not a consumer transfer")]
+    NotConsumer(String),
+    #[error("Transfer not supported")]
+    Unsupported,
+    #[error("Unsupported endpoint type {0}")]
+    UnsupportedEndpointType(String),
+    #[error("Validation failed: {0}")]
+    Validation(String),
+    #[error("Storage failure")]
+    Storage(#[source] anyhow::Error),
+    #[error(transparent)]
+    Generic(#[from] anyhow::Error),
+}
+
+impl From<DataAddressError> for TransferError {
+    fn from(value: DataAddressError) -> Self {
+        match value {
+            DataAddressError::UnsupportedType(kind) => TransferError::UnsupportedEndpointType(kind),
+            err => TransferError::Validation(err.to_string()),
+        }
     }
 }
 
@@ -68,27 +654,83 @@ impl TransferService {
 #[interface]
 #[cfg_attr(test, automock)]
 pub trait TransferManager {
-    async fn can_handle(&self, transfer: &Transfer) -> anyhow::Result<bool>;
-    async fn handle_start(&self, transfer: &Transfer) -> anyhow::Result<Option<DataAddress>>;
-    async fn handle_suspend(&self, id: &str) -> anyhow::Result<()>;
-    async fn handle_terminate(&self, id: &str) -> anyhow::Result<()>;
+    async fn can_handle(&self, transfer: &Transfer) -> TransferResult<bool>;
+    /// Called for new transfers and when resuming a suspended one, in which case
+    /// any access granted before the suspension must be invalidated.
+    async fn handle_start(&self, transfer: &Transfer) -> TransferResult<Option<DataAddress>>;
+    /// Called when the start of an already started transfer is repeated, returns the data
+    /// address again without invalidating the one issued before
+    async fn reissue(&self, _transfer: &Transfer) -> TransferResult<Option<DataAddress>> {
+        Ok(None)
+    }
+    /// Called when the transfer could not be persisted after [`TransferManager::handle_start`],
+    /// undoes its side effects. The transfer is passed in the state it had before the start.
+    async fn rollback_start(&self, _transfer: &Transfer) -> TransferResult<()> {
+        Ok(())
+    }
+    /// Called once the transfer started by [`TransferManager::handle_start`] was persisted,
+    /// the work that must not begin before, e.g. pushing data, is started here
+    async fn commit_start(&self, _transfer: &Transfer) {}
+    /// Provisions the destination of a consumer transfer, keeps the requested one by default
+    async fn handle_prepare(&self, transfer: &Transfer) -> TransferResult<Option<DataAddress>> {
+        Ok(transfer
+            .destination
+            .as_ref()
+            .map(|destination| destination.0.clone()))
+    }
+    /// Called when the provider started a consumer transfer, with the source it sent
+    async fn handle_started(&self, _transfer: &Transfer) -> TransferResult<()> {
+        Ok(())
+    }
+    async fn handle_suspend(&self, id: &str) -> TransferResult<()>;
+    async fn handle_terminate(&self, id: &str) -> TransferResult<()>;
+    /// EDR of the transfer shared with the webhooks, for the managers handling EDRs
+    async fn edr(&self, _transfer: &Transfer) -> TransferResult<Option<TransferEdr>> {
+        Ok(None)
+    }
+    /// Removes the state kept for transfers missing from `transfers` or in a final state,
+    /// looking them up `batch_size` at a time. Returns how many entries were removed
+    async fn purge(&self, _transfers: &TransferRepoRef, _batch_size: i32) -> TransferResult<usize> {
+        Ok(0)
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use chrono::{Duration, Utc};
     use futures::FutureExt;
-    use std::collections::HashMap;
+    use std::{
+        collections::HashMap,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+    };
     use uuid::Uuid;
 
     use crate::{
         core::{
-            db::transfer::{MockTransferRepo, TransferRepoRef},
-            model::namespace::{EDC_NAMESPACE, IDSA_NAMESPACE},
+            db::{
+                memory::transfer::InMemoryTransferRepo,
+                transfer::{MockTransferRepo, TransferRepo, TransferRepoRef},
+            },
+            model::{
+                namespace::{EDC_NAMESPACE, IDSA_NAMESPACE},
+                notification::NotificationKind,
+                transfer::{Transfer, TransferStatus},
+            },
+            service::{
+                events::{TransferEvent, TransferEvents},
+                notification::NotificationService,
+                registry::TransferManagers,
+            },
+        },
+        signaling::{
+            DataAddress, DataFlowPrepareMessage, DataFlowStartMessage, EndpointProperty, FlowType,
         },
-        signaling::{DataAddress, DataFlowStartMessage, EndpointProperty, FlowType},
     };
 
-    use super::{MockTransferManager, TransferManagerRef, TransferService};
+    use super::{MockTransferManager, TransferError, TransferManagerRef, TransferService};
 
     #[tokio::test]
     async fn start_transfer() {
@@ -105,9 +747,25 @@ mod tests {
             .expect_handle_start()
             .returning(|_| futures::future::ok(Some(create_data_address())).boxed());
 
+        transfer_manager
+            .expect_commit_start()
+            .withf(|transfer| transfer.status == TransferStatus::Started)
+            .times(1)
+            .returning(|_| futures::future::ready(()).boxed());
+
         store
-            .expect_save()
-            .returning(|_| Box::pin(async { Ok(()) }));
+            .expect_create()
+            .withf(|transfer| transfer.status == TransferStatus::Received)
+            .times(1)
+            .returning(|_| futures::future::ok(true).boxed());
+
+        store
+            .expect_transition()
+            .withf(|transfer, from, _| {
+                transfer.status == TransferStatus::Started && from == &TransferStatus::Received
+            })
+            .times(1)
+            .returning(|_, _, _| futures::future::ok(true).boxed());
 
         let manager = create_transfer_manager(transfer_manager, store);
 
@@ -138,8 +796,28 @@ mod tests {
             .returning(|_| futures::future::ok(Some(create_data_address())).boxed());
 
         store
-            .expect_save()
-            .returning(|_| Box::pin(async { Err(anyhow::anyhow!("Failed to save")) }));
+            .expect_create()
+            .withf(|transfer| transfer.status == TransferStatus::Received)
+            .times(1)
+            .returning(|_| futures::future::ok(true).boxed());
+
+        store
+            .expect_transition()
+            .returning(|_, _, _| futures::future::err(anyhow::anyhow!("Failed to save")).boxed());
+
+        store
+            .expect_delete()
+            .withf(|id| id == "process_id")
+            .times(1)
+            .returning(|_| futures::future::ok(()).boxed());
+
+        transfer_manager
+            .expect_rollback_start()
+            .withf(|transfer| transfer.status == TransferStatus::Received)
+            .times(1)
+            .returning(|_| futures::future::ok(()).boxed());
+
+        transfer_manager.expect_commit_start().never();
 
         let manager = create_transfer_manager(transfer_manager, store);
 
@@ -147,13 +825,17 @@ mod tests {
 
         let result = manager.start(req).await.unwrap_err();
 
-        assert_eq!(result.to_string(), "Failed to save");
+        assert!(matches!(result, TransferError::Storage(_)));
+        assert_eq!(
+            format!("{:#}", anyhow::Error::from(result)),
+            "Storage failure: Failed to save"
+        );
     }
 
     #[tokio::test]
-    async fn start_transfer_fails_when_manager_fails() {
+    async fn start_transfer_reports_store_failure_when_rollback_fails() {
         let mut transfer_manager = MockTransferManager::new();
-        let store = MockTransferRepo::new();
+        let mut store = MockTransferRepo::new();
 
         transfer_manager
             .expect_can_handle()
@@ -161,7 +843,64 @@ mod tests {
 
         transfer_manager
             .expect_handle_start()
-            .returning(|_| futures::future::err(anyhow::anyhow!("Failed to handle start")).boxed());
+            .returning(|_| futures::future::ok(Some(create_data_address())).boxed());
+
+        transfer_manager
+            .expect_rollback_start()
+            .times(1)
+            .returning(|_| {
+                futures::future::err(anyhow::anyhow!("Failed to roll back").into()).boxed()
+            });
+
+        store
+            .expect_create()
+            .withf(|transfer| transfer.status == TransferStatus::Received)
+            .times(1)
+            .returning(|_| futures::future::ok(true).boxed());
+
+        store
+            .expect_transition()
+            .returning(|_, _, _| futures::future::err(anyhow::anyhow!("Failed to save")).boxed());
+
+        store
+            .expect_delete()
+            .returning(|_| futures::future::ok(()).boxed());
+
+        let manager = create_transfer_manager(transfer_manager, store);
+
+        let result = manager.start(create_req()).await.unwrap_err();
+
+        assert!(matches!(result, TransferError::Storage(_)));
+    }
+
+    #[tokio::test]
+    async fn start_transfer_fails_when_manager_fails() {
+        let mut transfer_manager = MockTransferManager::new();
+        let mut store = MockTransferRepo::new();
+
+        transfer_manager
+            .expect_can_handle()
+            .returning(|_| futures::future::ok(true).boxed());
+
+        transfer_manager.expect_handle_start().returning(|_| {
+            futures::future::err(anyhow::anyhow!("Failed to handle start").into()).boxed()
+        });
+
+        transfer_manager.expect_rollback_start().never();
+
+        store
+            .expect_create()
+            .withf(|transfer| transfer.status == TransferStatus::Received)
+            .times(1)
+            .returning(|_| futures::future::ok(true).boxed());
+
+        store.expect_transition().never();
+
+        store
+            .expect_delete()
+            .withf(|id| id == "process_id")
+            .times(1)
+            .returning(|_| futures::future::ok(()).boxed());
 
         let manager = create_transfer_manager(transfer_manager, store);
 
@@ -172,29 +911,992 @@ mod tests {
         assert_eq!(result.to_string(), "Failed to handle start");
     }
 
-    fn create_transfer_manager(
-        mock: MockTransferManager,
-        mock_store: MockTransferRepo,
-    ) -> TransferService {
-        // let edr = EdrManager::builder()
-        //     .tokens(mock)
-        //     .proxy_url("http://localhost:8080/public")
-        //     .issuer("issuer")
-        //     .token_duration(Duration::days(1))
-        //     .token_url("http://localhost:8080/token")
-        //     .jwks_url("http://localhost:8080/.well-known/jwks.json")
-        //     .build();
+    #[tokio::test]
+    async fn start_transfer_resumes_suspended() {
+        let mut transfer_manager = MockTransferManager::new();
+        let mut store = MockTransferRepo::new();
 
-        let manager = TransferManagerRef::of(mock);
-        let store = TransferRepoRef::of(mock_store);
+        let mut suspended = create_transfer(TransferStatus::Suspended);
+        suspended.created_at = Utc::now() - Duration::hours(1);
+        suspended.suspension_reason = Some("reason".to_string());
+        suspended.suspended_at = Some(Utc::now());
 
-        TransferService::new(manager, store)
+        let created_at = suspended.created_at;
+
+        transfer_manager
+            .expect_can_handle()
+            .returning(|_| futures::future::ok(true).boxed());
+
+        transfer_manager
+            .expect_handle_start()
+            .withf(move |transfer| transfer.created_at == created_at)
+            .times(1)
+            .returning(|_| futures::future::ok(Some(create_data_address())).boxed());
+
+        transfer_manager
+            .expect_commit_start()
+            .returning(|_| futures::future::ready(()).boxed());
+
+        store
+            .expect_create()
+            .returning(|_| futures::future::ok(false).boxed());
+
+        store
+            .expect_fetch_by_id()
+            .returning(move |_| futures::future::ok(Some(suspended.clone())).boxed());
+
+        store
+            .expect_transition()
+            .withf(move |transfer, from, _| {
+                from == &TransferStatus::Suspended
+                    && transfer.status == TransferStatus::Started
+                    && transfer.created_at == created_at
+                    && transfer.updated_at > created_at
+                    && transfer.suspension_reason.is_none()
+                    && transfer.suspended_at.is_none()
+            })
+            .times(1)
+            .returning(|_, _, _| futures::future::ok(true).boxed());
+
+        let manager = create_transfer_manager(transfer_manager, store);
+
+        let response = manager.start(create_req()).await.unwrap();
+
+        assert!(response.data_address.is_some());
     }
 
-    fn create_data_address() -> DataAddress {
-        DataAddress::builder()
-            .endpoint_type(IDSA_NAMESPACE.to_iri("HTTP"))
-            .endpoint_properties(vec![])
+    #[tokio::test]
+    async fn start_transfer_rejects_resume_by_other_participant() {
+        let mut transfer_manager = MockTransferManager::new();
+        let mut store = MockTransferRepo::new();
+
+        transfer_manager.expect_handle_start().never();
+
+        store
+            .expect_create()
+            .returning(|_| futures::future::ok(false).boxed());
+
+        store.expect_fetch_by_id().returning(|_| {
+            Box::pin(async { Ok(Some(create_transfer(TransferStatus::Suspended))) })
+        });
+
+        store.expect_transition().never();
+
+        let manager = create_transfer_manager(transfer_manager, store);
+
+        let mut req = create_req();
+        req.participant_id = "other_participant".to_string();
+
+        let result = manager.start(req).await.unwrap_err();
+
+        assert!(matches!(result, TransferError::Conflict(id) if id == "process_id"));
+    }
+
+    #[tokio::test]
+    async fn start_transfer_fails_when_terminated() {
+        let transfer_manager = MockTransferManager::new();
+        let mut store = MockTransferRepo::new();
+
+        store
+            .expect_create()
+            .returning(|_| futures::future::ok(false).boxed());
+
+        store.expect_fetch_by_id().returning(|_| {
+            Box::pin(async { Ok(Some(create_transfer(TransferStatus::Terminated))) })
+        });
+
+        let manager = create_transfer_manager(transfer_manager, store);
+
+        let result = manager.start(create_req()).await.unwrap_err();
+
+        assert!(matches!(
+            result,
+            TransferError::IllegalTransition {
+                from: TransferStatus::Terminated,
+                to: TransferStatus::Started,
+                ..
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn start_transfer_is_idempotent() {
+        let mut transfer_manager = MockTransferManager::new();
+        let mut store = MockTransferRepo::new();
+
+        transfer_manager.expect_handle_start().never();
+
+        transfer_manager
+            .expect_reissue()
+            .times(1)
+            .returning(|_| futures::future::ok(Some(create_data_address())).boxed());
+
+        store
+            .expect_create()
+            .returning(|_| futures::future::ok(false).boxed());
+
+        store
+            .expect_fetch_by_id()
+            .returning(|_| Box::pin(async { Ok(Some(create_transfer(TransferStatus::Started))) }));
+
+        store.expect_transition().never();
+
+        let manager = create_transfer_manager(transfer_manager, store);
+
+        let response = manager.start(create_req()).await.unwrap();
+
+        assert_eq!(response.data_address, Some(create_data_address()));
+    }
+
+    #[tokio::test]
+    async fn start_transfer_rejects_concurrent_start() {
+        let mut transfer_manager = MockTransferManager::new();
+        let mut store = MockTransferRepo::new();
+
+        transfer_manager.expect_handle_start().never();
+        transfer_manager.expect_reissue().never();
+
+        store
+            .expect_create()
+            .returning(|_| futures::future::ok(false).boxed());
+
+        store
+            .expect_fetch_by_id()
+            .returning(|_| Box::pin(async { Ok(Some(create_transfer(TransferStatus::Received))) }));
+
+        store.expect_delete().never();
+
+        let manager = create_transfer_manager(transfer_manager, store);
+
+        let result = manager.start(create_req()).await.unwrap_err();
+
+        assert!(matches!(result, TransferError::Modified(id) if id == "process_id"));
+    }
+
+    #[tokio::test]
+    async fn start_transfer_fails_when_payload_conflicts() {
+        let transfer_manager = MockTransferManager::new();
+        let mut store = MockTransferRepo::new();
+
+        store
+            .expect_create()
+            .returning(|_| futures::future::ok(false).boxed());
+
+        store.expect_fetch_by_id().returning(|_| {
+            let mut transfer = create_transfer(TransferStatus::Started);
+            transfer.participant_id = "other_participant".to_string();
+            Box::pin(async { Ok(Some(transfer)) })
+        });
+
+        let manager = create_transfer_manager(transfer_manager, store);
+
+        let result = manager.start(create_req()).await.unwrap_err();
+
+        assert!(matches!(result, TransferError::Conflict(id) if id == "process_id"));
+    }
+
+    #[tokio::test]
+    async fn start_transfer_fails_when_agreement_conflicts() {
+        let transfer_manager = MockTransferManager::new();
+        let mut store = MockTransferRepo::new();
+
+        store
+            .expect_create()
+            .returning(|_| futures::future::ok(false).boxed());
+
+        store.expect_fetch_by_id().returning(|_| {
+            let mut transfer = create_transfer(TransferStatus::Started);
+            transfer.agreement_id = Some("other_agreement".to_string());
+            Box::pin(async { Ok(Some(transfer)) })
+        });
+
+        let manager = create_transfer_manager(transfer_manager, store);
+
+        let result = manager.start(create_req()).await.unwrap_err();
+
+        assert!(matches!(result, TransferError::Conflict(id) if id == "process_id"));
+    }
+
+    #[tokio::test]
+    async fn start_transfer_keeps_agreement() {
+        let mut transfer_manager = MockTransferManager::new();
+        let mut store = MockTransferRepo::new();
+
+        let req = create_req();
+        let agreement_id = req.agreement_id().to_string();
+        let dataset_id = req.dataset_id().to_string();
+
+        transfer_manager
+            .expect_can_handle()
+            .returning(|_| futures::future::ok(true).boxed());
+
+        transfer_manager
+            .expect_handle_start()
+            .returning(|_| futures::future::ok(None).boxed());
+
+        transfer_manager
+            .expect_commit_start()
+            .returning(|_| futures::future::ready(()).boxed());
+
+        store
+            .expect_create()
+            .returning(|_| futures::future::ok(true).boxed());
+
+        store
+            .expect_transition()
+            .withf(move |transfer, _, _| {
+                transfer.agreement_id.as_ref() == Some(&agreement_id)
+                    && transfer.dataset_id.as_ref() == Some(&dataset_id)
+                    && transfer.properties.as_ref().map(|properties| &properties.0)
+                        == Some(&HashMap::new())
+                    && transfer.transfer_type.is_none()
+            })
+            .times(1)
+            .returning(|_, _, _| futures::future::ok(true).boxed());
+
+        let manager = create_transfer_manager(transfer_manager, store);
+
+        manager.start(req).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn suspend_transfer() {
+        let mut transfer_manager = MockTransferManager::new();
+        let mut store = MockTransferRepo::new();
+
+        transfer_manager
+            .expect_handle_suspend()
+            .withf(|id| id == "process_id")
+            .times(1)
+            .returning(|_| futures::future::ok(()).boxed());
+
+        store
+            .expect_fetch_by_id()
+            .returning(|_| Box::pin(async { Ok(Some(create_transfer(TransferStatus::Started))) }));
+
+        store
+            .expect_transition()
+            .withf(|transfer, from, _| {
+                transfer.status == TransferStatus::Suspended
+                    && from == &TransferStatus::Started
+                    && transfer.suspension_reason.as_deref() == Some("reason")
+                    && transfer.suspended_at.is_some()
+            })
+            .returning(|_, _, _| futures::future::ok(true).boxed());
+
+        let manager = create_transfer_manager(transfer_manager, store);
+
+        manager
+            .suspend("process_id".to_string(), Some("reason".to_string()))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn suspend_transfer_keeps_concurrent_termination() {
+        let mut transfer_manager = MockTransferManager::new();
+        let store = InMemoryTransferRepo::new();
+
+        store
+            .save(create_transfer(TransferStatus::Started))
+            .await
+            .unwrap();
+
+        // The transfer is terminated once the suspension fetched it
+        let terminating = store.clone();
+        transfer_manager
+            .expect_handle_suspend()
+            .returning(move |id| {
+                let store = terminating.clone();
+                let id = id.to_string();
+                async move {
+                    store
+                        .change_status(id, TransferStatus::Terminated)
+                        .await
+                        .unwrap();
+                    Ok(())
+                }
+                .boxed()
+            });
+
+        let manager = TransferService::new(
+            TransferManagerRef::of(transfer_manager),
+            TransferRepoRef::of(store.clone()),
+        );
+
+        let result = manager
+            .suspend("process_id".to_string(), Some("reason".to_string()))
+            .await;
+
+        assert!(matches!(result, Err(TransferError::Modified(_))));
+
+        let transfer = store.fetch_by_id("process_id").await.unwrap().unwrap();
+        assert_eq!(transfer.status, TransferStatus::Terminated);
+        assert!(transfer.suspension_reason.is_none());
+    }
+
+    #[tokio::test]
+    async fn lifecycle_changes_are_published() {
+        let mut transfer_manager = MockTransferManager::new();
+        let mut store = MockTransferRepo::new();
+
+        transfer_manager
+            .expect_can_handle()
+            .returning(|_| futures::future::ok(true).boxed());
+
+        transfer_manager
+            .expect_handle_start()
+            .returning(|_| futures::future::ok(Some(create_data_address())).boxed());
+
+        transfer_manager
+            .expect_commit_start()
+            .returning(|_| futures::future::ready(()).boxed());
+
+        transfer_manager
+            .expect_handle_suspend()
+            .returning(|_| futures::future::ok(()).boxed());
+
+        // The first start claims the transfer, the second one resumes it
+        let mut claims = vec![true, false].into_iter();
+        store
+            .expect_create()
+            .returning(move |_| futures::future::ok(claims.next().unwrap()).boxed());
+
+        let mut statuses = vec![TransferStatus::Started, TransferStatus::Suspended].into_iter();
+        store.expect_fetch_by_id().returning(move |_| {
+            let transfer = statuses.next().map(create_transfer);
+            Box::pin(async { Ok(transfer) })
+        });
+
+        store
+            .expect_save()
+            .returning(|_| Box::pin(async { Ok(()) }));
+
+        store
+            .expect_transition()
+            .returning(|_, _, _| futures::future::ok(true).boxed());
+
+        let events = TransferEvents::default();
+        let mut receiver = events.subscribe();
+
+        let manager = create_transfer_manager(transfer_manager, store).with_events(events);
+
+        manager.start(create_req()).await.unwrap();
+        manager
+            .suspend("process_id".to_string(), Some("reason".to_string()))
+            .await
+            .unwrap();
+        manager.start(create_req()).await.unwrap();
+
+        let transfer_id = "process_id".to_string();
+        assert_eq!(
+            receiver.recv().await.unwrap(),
+            TransferEvent::Started {
+                transfer_id: transfer_id.clone(),
+                endpoint: None
+            }
+        );
+        assert_eq!(
+            receiver.recv().await.unwrap(),
+            TransferEvent::Suspended {
+                transfer_id: transfer_id.clone(),
+                reason: Some("reason".to_string())
+            }
+        );
+        assert_eq!(
+            receiver.recv().await.unwrap(),
+            TransferEvent::Resumed {
+                transfer_id,
+                endpoint: None
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn suspend_transfer_fails_when_terminated() {
+        let transfer_manager = MockTransferManager::new();
+        let mut store = MockTransferRepo::new();
+
+        store.expect_fetch_by_id().returning(|_| {
+            Box::pin(async { Ok(Some(create_transfer(TransferStatus::Terminated))) })
+        });
+
+        let manager = create_transfer_manager(transfer_manager, store);
+
+        let result = manager
+            .suspend("process_id".to_string(), None)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            result,
+            TransferError::IllegalTransition {
+                from: TransferStatus::Terminated,
+                to: TransferStatus::Suspended,
+                ..
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn transfers_are_routed_by_type() {
+        let mut pull = MockTransferManager::new();
+        let mut push = MockTransferManager::new();
+        let mut store = MockTransferRepo::new();
+
+        pull.expect_can_handle()
+            .times(1)
+            .returning(|_| futures::future::ok(true).boxed());
+        pull.expect_handle_start()
+            .times(1)
+            .returning(|_| futures::future::ok(None).boxed());
+        pull.expect_commit_start()
+            .times(1)
+            .returning(|_| futures::future::ready(()).boxed());
+        pull.expect_handle_suspend().never();
+
+        push.expect_handle_start().never();
+        push.expect_handle_suspend()
+            .times(1)
+            .returning(|_| futures::future::ok(()).boxed());
+
+        store
+            .expect_create()
+            .returning(|_| futures::future::ok(true).boxed());
+
+        store.expect_fetch_by_id().returning(move |_| {
+            let mut transfer = create_transfer(TransferStatus::Started);
+            transfer.transfer_type = Some("HttpData-PUSH".to_string());
+            Box::pin(async { Ok(Some(transfer)) })
+        });
+
+        store
+            .expect_transition()
+            .withf(|transfer, _, _| transfer.transfer_type.as_deref() == Some("HttpData-PULL"))
+            .returning(|_, _, _| futures::future::ok(true).boxed());
+
+        store
+            .expect_transition()
+            .withf(|transfer, _, _| transfer.transfer_type.as_deref() == Some("HttpData-PUSH"))
+            .returning(|_, _, _| futures::future::ok(true).boxed());
+
+        let managers = TransferManagers::default();
+        managers
+            .register(["HttpData-PULL"], TransferManagerRef::of(pull))
+            .unwrap();
+        managers
+            .register(["HttpData-PUSH"], TransferManagerRef::of(push))
+            .unwrap();
+
+        let service = TransferService::new(managers, TransferRepoRef::of(store));
+
+        let mut req = create_req();
+        req.transfer_type_destination = Some(EDC_NAMESPACE.to_iri("HttpData"));
+        service.start(req).await.unwrap();
+
+        service
+            .suspend("process_id".to_string(), None)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn terminate_transfer() {
+        let mut transfer_manager = MockTransferManager::new();
+        let mut store = MockTransferRepo::new();
+
+        transfer_manager
+            .expect_handle_terminate()
+            .withf(|id| id == "process_id")
+            .times(1)
+            .returning(|_| futures::future::ok(()).boxed());
+
+        store.expect_fetch_by_id().returning(|_| {
+            Box::pin(async { Ok(Some(create_transfer(TransferStatus::Suspended))) })
+        });
+
+        store
+            .expect_transition()
+            .withf(|transfer, from, notification| {
+                transfer.status == TransferStatus::Terminated
+                    && transfer.termination_reason.as_deref() == Some("reason")
+                    && transfer.terminated_at.is_some()
+                    && *from == TransferStatus::Suspended
+                    && notification.is_none()
+            })
+            .returning(|_, _, _| Box::pin(async { Ok(true) }));
+
+        let manager = create_transfer_manager(transfer_manager, store);
+
+        manager
+            .terminate("process_id".to_string(), Some("reason".to_string()))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn terminate_transfer_fails_when_modified_concurrently() {
+        let mut transfer_manager = MockTransferManager::new();
+        let mut store = MockTransferRepo::new();
+
+        transfer_manager
+            .expect_handle_terminate()
+            .returning(|_| futures::future::ok(()).boxed());
+
+        store
+            .expect_fetch_by_id()
+            .returning(|_| Box::pin(async { Ok(Some(create_transfer(TransferStatus::Started))) }));
+
+        store
+            .expect_transition()
+            .returning(|_, _, _| Box::pin(async { Ok(false) }));
+
+        let manager = create_transfer_manager(transfer_manager, store);
+
+        let result = manager
+            .terminate("process_id".to_string(), None)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(result, TransferError::Modified(id) if id == "process_id"));
+    }
+
+    #[tokio::test]
+    async fn terminate_agreement_notifies_control_plane() {
+        let mut transfer_manager = MockTransferManager::new();
+        let mut store = MockTransferRepo::new();
+
+        transfer_manager
+            .expect_handle_terminate()
+            .times(1)
+            .returning(|_| futures::future::ok(()).boxed());
+
+        let mut transfer = create_transfer(TransferStatus::Started);
+        transfer.callback_address = Some("http://control-plane/callback".to_string());
+
+        // The terminated transfer leaves the results of the query
+        let terminated = Arc::new(AtomicBool::new(false));
+        let queried = transfer.clone();
+        let is_terminated = terminated.clone();
+        store.expect_query().returning(move |query| {
+            let page = if query.agreement_id.as_deref() == Some("agreement_id")
+                && query.status == Some(TransferStatus::Started)
+                && !is_terminated.load(Ordering::SeqCst)
+            {
+                vec![queried.clone()]
+            } else {
+                vec![]
+            };
+            futures::future::ok(page).boxed()
+        });
+
+        store
+            .expect_fetch_by_id()
+            .returning(move |_| futures::future::ok(Some(transfer.clone())).boxed());
+
+        store
+            .expect_transition()
+            .withf(|_, _, notification| {
+                notification.as_ref().is_some_and(|notification| {
+                    notification.transfer_id == "process_id"
+                        && notification.kind == NotificationKind::Terminated
+                        && notification.callback_address == "http://control-plane/callback"
+                        && notification.reason.as_deref() == Some("reason")
+                })
+            })
+            .times(1)
+            .returning(move |_, _, _| {
+                terminated.store(true, Ordering::SeqCst);
+                futures::future::ok(true).boxed()
+            });
+
+        let manager = create_transfer_manager(transfer_manager, store)
+            .with_notifications(NotificationService::new());
+
+        let terminated = manager
+            .terminate_agreement("agreement_id", Some("reason".to_string()))
+            .await
+            .unwrap();
+
+        assert_eq!(terminated, 1);
+    }
+
+    #[tokio::test]
+    async fn terminate_transfer_does_not_notify_control_plane() {
+        let mut transfer_manager = MockTransferManager::new();
+        let mut store = MockTransferRepo::new();
+
+        transfer_manager
+            .expect_handle_terminate()
+            .returning(|_| futures::future::ok(()).boxed());
+
+        store.expect_fetch_by_id().returning(|_| {
+            let mut transfer = create_transfer(TransferStatus::Started);
+            transfer.callback_address = Some("http://control-plane/callback".to_string());
+            Box::pin(async { Ok(Some(transfer)) })
+        });
+
+        store
+            .expect_transition()
+            .withf(|transfer, _, notification| {
+                transfer.status == TransferStatus::Terminated && notification.is_none()
+            })
+            .times(1)
+            .returning(|_, _, _| Box::pin(async { Ok(true) }));
+
+        let manager = create_transfer_manager(transfer_manager, store)
+            .with_notifications(NotificationService::new());
+
+        manager
+            .terminate("process_id".to_string(), Some("reason".to_string()))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn terminate_transfer_fails_when_manager_fails() {
+        let mut transfer_manager = MockTransferManager::new();
+        let mut store = MockTransferRepo::new();
+
+        transfer_manager.expect_handle_terminate().returning(|_| {
+            futures::future::err(anyhow::anyhow!("Failed to handle terminate").into()).boxed()
+        });
+
+        store
+            .expect_fetch_by_id()
+            .returning(|_| Box::pin(async { Ok(Some(create_transfer(TransferStatus::Started))) }));
+
+        store.expect_transition().never();
+
+        let manager = create_transfer_manager(transfer_manager, store);
+
+        let result = manager
+            .terminate("process_id".to_string(), None)
+            .await
+            .unwrap_err();
+
+        assert_eq!(result.to_string(), "Failed to handle terminate");
+    }
+
+    #[tokio::test]
+    async fn terminate_transfer_fails_when_not_found() {
+        let transfer_manager = MockTransferManager::new();
+        let mut store = MockTransferRepo::new();
+
+        store
+            .expect_fetch_by_id()
+            .returning(|_| Box::pin(async { Ok(None) }));
+
+        let manager = create_transfer_manager(transfer_manager, store);
+
+        let result = manager
+            .terminate("process_id".to_string(), None)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(result, TransferError::NotFound(id) if id == "process_id"));
+    }
+
+    #[tokio::test]
+    async fn prepare_transfer() {
+        let mut transfer_manager = MockTransferManager::new();
+        let mut store = MockTransferRepo::new();
+
+        transfer_manager
+            .expect_handle_prepare()
+            .withf(|transfer| transfer.source.is_none() && transfer.destination.is_none())
+            .times(1)
+            .returning(|_| futures::future::ok(Some(create_data_address())).boxed());
+
+        store
+            .expect_fetch_by_id()
+            .returning(|_| Box::pin(async { Ok(None) }));
+
+        store
+            .expect_save()
+            .withf(|transfer| {
+                transfer.status == TransferStatus::Prepared
+                    && transfer.consumer
+                    && transfer.destination.as_ref().map(|d| &d.0) == Some(&create_data_address())
+            })
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(()) }));
+
+        let manager = create_transfer_manager(transfer_manager, store);
+
+        let response = manager.prepare(create_prepare_req()).await.unwrap();
+
+        assert_eq!(response.state, Some(TransferStatus::Prepared));
+        assert_eq!(response.data_address, Some(create_data_address()));
+    }
+
+    #[tokio::test]
+    async fn prepare_transfer_is_idempotent() {
+        let transfer_manager = MockTransferManager::new();
+        let mut store = MockTransferRepo::new();
+
+        store.expect_fetch_by_id().returning(|_| {
+            let mut transfer = create_transfer(TransferStatus::Prepared);
+            transfer.destination = Some(create_data_address().into());
+            Box::pin(async { Ok(Some(transfer)) })
+        });
+
+        store.expect_save().never();
+
+        let manager = create_transfer_manager(transfer_manager, store);
+
+        let response = manager.prepare(create_prepare_req()).await.unwrap();
+
+        assert_eq!(response.data_address, Some(create_data_address()));
+    }
+
+    #[tokio::test]
+    async fn started_transfer_stores_source() {
+        let mut transfer_manager = MockTransferManager::new();
+        let mut store = MockTransferRepo::new();
+
+        transfer_manager
+            .expect_handle_started()
+            .withf(|transfer| {
+                transfer.source.as_ref().map(|s| &s.0) == Some(&create_data_address())
+            })
+            .times(1)
+            .returning(|_| futures::future::ok(()).boxed());
+
+        store.expect_fetch_by_id().returning(|_| {
+            let mut transfer = create_transfer(TransferStatus::Prepared);
+            transfer.source = None;
+            transfer.consumer = true;
+            Box::pin(async { Ok(Some(transfer)) })
+        });
+
+        store
+            .expect_transition()
+            .withf(|transfer, from, _| {
+                transfer.status == TransferStatus::Started
+                    && from == &TransferStatus::Prepared
+                    && transfer.source.as_ref().map(|s| &s.0) == Some(&create_data_address())
+            })
+            .times(1)
+            .returning(|_, _, _| futures::future::ok(true).boxed());
+
+        let manager = create_transfer_manager(transfer_manager, store);
+
+        manager
+            .started("process_id".to_string(), Some(create_data_address()))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn started_transfer_releases_state_when_completed_meanwhile() {
+        let mut transfer_manager = MockTransferManager::new();
+        let mut store = MockTransferRepo::new();
+
+        transfer_manager
+            .expect_handle_started()
+            .times(1)
+            .returning(|_| futures::future::ok(()).boxed());
+
+        transfer_manager
+            .expect_handle_terminate()
+            .withf(|id| id == "process_id")
+            .times(1)
+            .returning(|_| futures::future::ok(()).boxed());
+
+        // Completed by the provider once the notification of the start was read
+        let completed = Arc::new(AtomicBool::new(false));
+        let is_completed = completed.clone();
+        store.expect_fetch_by_id().returning(move |_| {
+            let status = if is_completed.load(Ordering::SeqCst) {
+                TransferStatus::Completed
+            } else {
+                TransferStatus::Prepared
+            };
+            let mut transfer = create_transfer(status);
+            transfer.consumer = true;
+            futures::future::ok(Some(transfer)).boxed()
+        });
+
+        store.expect_transition().returning(move |_, _, _| {
+            completed.store(true, Ordering::SeqCst);
+            futures::future::ok(false).boxed()
+        });
+
+        let manager = create_transfer_manager(transfer_manager, store);
+
+        let result = manager
+            .started("process_id".to_string(), Some(create_data_address()))
+            .await;
+
+        assert!(matches!(result, Err(TransferError::Modified(_))));
+    }
+
+    #[tokio::test]
+    async fn started_transfer_rejects_provider_transfer() {
+        let mut transfer_manager = MockTransferManager::new();
+        let mut store = MockTransferRepo::new();
+
+        transfer_manager.expect_handle_started().never();
+
+        store
+            .expect_fetch_by_id()
+            .returning(|_| Box::pin(async { Ok(Some(create_transfer(TransferStatus::Received))) }));
+
+        store.expect_save().never();
+
+        let manager = create_transfer_manager(transfer_manager, store);
+
+        let result = manager
+            .started("process_id".to_string(), Some(create_data_address()))
+            .await
+            .unwrap_err();
+
+        assert!(matches!(result, TransferError::NotConsumer(id) if id == "process_id"));
+    }
+
+    #[tokio::test]
+    async fn complete_transfer() {
+        let mut transfer_manager = MockTransferManager::new();
+        let mut store = MockTransferRepo::new();
+
+        transfer_manager
+            .expect_handle_terminate()
+            .withf(|id| id == "process_id")
+            .times(1)
+            .returning(|_| futures::future::ok(()).boxed());
+
+        store.expect_fetch_by_id().returning(|_| {
+            let mut transfer = create_transfer(TransferStatus::Started);
+            transfer.consumer = true;
+            transfer.callback_address = Some("http://control-plane/callback".to_string());
+            Box::pin(async { Ok(Some(transfer)) })
+        });
+
+        store
+            .expect_transition()
+            .withf(|transfer, from, notification| {
+                transfer.status == TransferStatus::Completed
+                    && transfer.terminated_at.is_some()
+                    && *from == TransferStatus::Started
+                    && notification
+                        .as_ref()
+                        .is_some_and(|n| n.kind == NotificationKind::Completed)
+            })
+            .times(1)
+            .returning(|_, _, _| Box::pin(async { Ok(true) }));
+
+        let events = TransferEvents::default();
+        let mut receiver = events.subscribe();
+
+        let manager = create_transfer_manager(transfer_manager, store)
+            .with_notifications(NotificationService::new())
+            .with_events(events);
+
+        manager.complete("process_id".to_string()).await.unwrap();
+
+        assert_eq!(
+            receiver.recv().await.unwrap(),
+            TransferEvent::Completed {
+                transfer_id: "process_id".to_string()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn complete_transfer_rejects_provider_transfer() {
+        let mut transfer_manager = MockTransferManager::new();
+        let mut store = MockTransferRepo::new();
+
+        transfer_manager.expect_handle_terminate().never();
+
+        store
+            .expect_fetch_by_id()
+            .returning(|_| Box::pin(async { Ok(Some(create_transfer(TransferStatus::Started))) }));
+
+        store.expect_transition().never();
+
+        let manager = create_transfer_manager(transfer_manager, store);
+
+        let result = manager
+            .complete("process_id".to_string())
+            .await
+            .unwrap_err();
+
+        assert!(matches!(result, TransferError::NotConsumer(id) if id == "process_id"));
+    }
+
+    #[tokio::test]
+    async fn complete_transfer_fails_when_prepared() {
+        let transfer_manager = MockTransferManager::new();
+        let mut store = MockTransferRepo::new();
+
+        store.expect_fetch_by_id().returning(|_| {
+            let mut transfer = create_transfer(TransferStatus::Prepared);
+            transfer.consumer = true;
+            Box::pin(async { Ok(Some(transfer)) })
+        });
+
+        let manager = create_transfer_manager(transfer_manager, store);
+
+        let result = manager
+            .complete("process_id".to_string())
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            result,
+            TransferError::IllegalTransition {
+                from: TransferStatus::Prepared,
+                to: TransferStatus::Completed,
+                ..
+            }
+        ));
+    }
+
+    fn create_transfer_manager(
+        mock: MockTransferManager,
+        mock_store: MockTransferRepo,
+    ) -> TransferService {
+        // let edr = EdrManager::builder()
+        //     .tokens(mock)
+        //     .proxy_url("http://localhost:8080/public")
+        //     .issuer("issuer")
+        //     .token_duration(Duration::days(1))
+        //     .token_url("http://localhost:8080/token")
+        //     .jwks_url("http://localhost:8080/.well-known/jwks.json")
+        //     .build();
+
+        let manager = TransferManagerRef::of(mock);
+        let store = TransferRepoRef::of(mock_store);
+
+        TransferService::new(manager, store)
+    }
+
+    fn create_data_address() -> DataAddress {
+        DataAddress::builder()
+            .endpoint_type(IDSA_NAMESPACE.to_iri("HTTP"))
+            .endpoint_properties(vec![])
+            .build()
+    }
+
+    fn create_transfer(status: TransferStatus) -> Transfer {
+        Transfer::builder()
+            .id("process_id".to_string())
+            .participant_id("participant_id".to_string())
+            .source(create_req().source_data_address)
+            .status(status)
+            .build()
+    }
+
+    fn create_prepare_req() -> DataFlowPrepareMessage {
+        DataFlowPrepareMessage::builder()
+            .participant_id("participant_id".to_string())
+            .process_id("process_id".to_string())
+            .properties(HashMap::new())
+            .flow_type(FlowType::Pull)
+            .dataset_id(Uuid::new_v4().to_string())
+            .agreement_id(Uuid::new_v4().to_string())
             .build()
     }
 
@@ -0,0 +1,613 @@
+use std::{path::PathBuf, sync::Arc};
+
+use bon::Builder;
+use chrono::{DateTime, Duration, Utc};
+use reqwest::{header::CONTENT_TYPE, StatusCode};
+use ring::hmac;
+use secrecy::{ExposeSecret, SecretString};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::{
+    io::AsyncWriteExt,
+    sync::mpsc::{self, error::TrySendError},
+};
+use tracing::{debug, error};
+use uuid::Uuid;
+
+use crate::{
+    core::{
+        model::transfer::{Transfer, TransferEdr, TransferStatus},
+        service::events::TransferEvent,
+    },
+    signaling::FlowType,
+};
+
+pub const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+pub const TIMESTAMP_HEADER: &str = "X-Webhook-Timestamp";
+pub const ID_HEADER: &str = "X-Webhook-Id";
+
+/// Receiver of the webhooks, subscribed to all the events when no event is listed
+#[derive(Clone, Debug, Deserialize)]
+pub struct WebhookTarget {
+    pub url: String,
+    #[serde(default)]
+    pub events: Vec<String>,
+}
+
+impl WebhookTarget {
+    pub fn accepts(&self, event: &TransferEvent) -> bool {
+        self.events.is_empty() || self.events.iter().any(|name| name == event.name())
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookPayload {
+    pub id: Uuid,
+    pub event: &'static str,
+    pub timestamp: DateTime<Utc>,
+    pub data: TransferEvent,
+    pub transfer: Option<WebhookTransfer>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookTransfer {
+    pub id: String,
+    pub participant_id: String,
+    pub status: TransferStatus,
+    pub flow_type: FlowType,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// EDR of the transfer, see [`TransferManager::edr`](crate::core::service::transfer::TransferManager::edr)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub edr: Option<TransferEdr>,
+}
+
+impl WebhookTransfer {
+    pub fn new(transfer: &Transfer, edr: Option<TransferEdr>) -> Self {
+        Self {
+            id: transfer.id.clone(),
+            participant_id: transfer.participant_id.clone(),
+            status: transfer.status.clone(),
+            flow_type: transfer.flow_type.clone(),
+            created_at: transfer.created_at,
+            updated_at: transfer.updated_at,
+            edr,
+        }
+    }
+}
+
+impl From<&Transfer> for WebhookTransfer {
+    fn from(transfer: &Transfer) -> Self {
+        Self::new(transfer, None)
+    }
+}
+
+/// Sends the transfer events to the webhook targets, retrying with an exponential
+/// backoff. The deliveries given up on are written to the dead-letter log.
+#[derive(Builder, Clone)]
+pub struct WebhookDispatcher {
+    #[builder(default)]
+    client: reqwest::Client,
+    targets: Vec<WebhookTarget>,
+    /// Signs `{timestamp}.{body}` with HMAC-SHA256
+    secret: SecretString,
+    /// Webhooks waiting for delivery to a target, see [`WebhookDispatcher::start`]
+    queue_size: usize,
+    max_attempts: i32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    /// JSON lines file of the failed deliveries
+    dead_letters: Option<PathBuf>,
+}
+
+/// Payload of a webhook, serialized once for all its targets
+struct Delivery {
+    payload: WebhookPayload,
+    body: String,
+}
+
+impl Delivery {
+    fn new(event: TransferEvent, transfer: Option<WebhookTransfer>) -> Option<Self> {
+        let payload = WebhookPayload {
+            id: Uuid::new_v4(),
+            event: event.name(),
+            timestamp: Utc::now(),
+            data: event,
+            transfer,
+        };
+
+        match serde_json::to_string(&payload) {
+            Ok(body) => Some(Self { payload, body }),
+            Err(err) => {
+                error!(
+                    "Failed to serialize webhook payload {}: {}",
+                    payload.id, err
+                );
+                None
+            }
+        }
+    }
+}
+
+enum DeliveryError {
+    Retryable(String),
+    /// The target rejected the payload, retrying would not help
+    Permanent(String),
+}
+
+impl WebhookDispatcher {
+    /// Delivers the event to the targets subscribed to it and returns how many received it
+    pub async fn dispatch(&self, event: TransferEvent, transfer: Option<WebhookTransfer>) -> usize {
+        let targets = self
+            .targets
+            .iter()
+            .filter(|target| target.accepts(&event))
+            .collect::<Vec<_>>();
+
+        if targets.is_empty() {
+            return 0;
+        }
+
+        let Some(delivery) = Delivery::new(event, transfer) else {
+            return 0;
+        };
+
+        futures::future::join_all(
+            targets
+                .into_iter()
+                .map(|target| self.send(target, &delivery)),
+        )
+        .await
+        .into_iter()
+        .filter(|delivered| *delivered)
+        .count()
+    }
+
+    /// Starts a worker per target delivering its webhooks one after the other, so that each
+    /// target receives the events in the order they were queued. The workers stop once the
+    /// returned queue is dropped and the webhooks left in it are delivered.
+    pub fn start(&self) -> WebhookQueue {
+        let workers = self
+            .targets
+            .iter()
+            .map(|target| {
+                let (sender, mut receiver) = mpsc::channel::<Arc<Delivery>>(self.queue_size);
+                let dispatcher = self.clone();
+                let worker_target = target.clone();
+
+                tokio::task::spawn(async move {
+                    while let Some(delivery) = receiver.recv().await {
+                        dispatcher.send(&worker_target, &delivery).await;
+                    }
+                });
+
+                (target.clone(), sender)
+            })
+            .collect();
+
+        WebhookQueue {
+            dispatcher: self.clone(),
+            workers,
+        }
+    }
+
+    async fn send(&self, target: &WebhookTarget, delivery: &Delivery) -> bool {
+        let mut attempts = 0;
+
+        loop {
+            attempts += 1;
+
+            let error = match self.deliver(target, delivery).await {
+                Ok(()) => return true,
+                Err(DeliveryError::Permanent(error)) => error,
+                Err(DeliveryError::Retryable(error)) if attempts < self.max_attempts => {
+                    debug!(
+                        "Failed to deliver webhook {} to {}: {}",
+                        delivery.payload.id, target.url, error
+                    );
+                    tokio::time::sleep(self.backoff(attempts).to_std().unwrap_or_default()).await;
+                    continue;
+                }
+                Err(DeliveryError::Retryable(error)) => error,
+            };
+
+            self.dead_letter(target, delivery, attempts, &error).await;
+            return false;
+        }
+    }
+
+    fn backoff(&self, attempts: i32) -> Duration {
+        let factor = 2_i32.saturating_pow(attempts.saturating_sub(1) as u32);
+        self.initial_backoff
+            .checked_mul(factor)
+            .unwrap_or(self.max_backoff)
+            .min(self.max_backoff)
+    }
+
+    async fn deliver(
+        &self,
+        target: &WebhookTarget,
+        delivery: &Delivery,
+    ) -> Result<(), DeliveryError> {
+        let timestamp = delivery.payload.timestamp.timestamp().to_string();
+
+        let response = self
+            .client
+            .post(&target.url)
+            .header(CONTENT_TYPE, "application/json")
+            .header(ID_HEADER, delivery.payload.id.to_string())
+            .header(TIMESTAMP_HEADER, &timestamp)
+            .header(
+                SIGNATURE_HEADER,
+                sign(&self.secret, &timestamp, &delivery.body),
+            )
+            .body(delivery.body.clone())
+            .send()
+            .await
+            .map_err(|err| DeliveryError::Retryable(err.to_string()))?;
+
+        let status = response.status();
+
+        if status.is_success() {
+            return Ok(());
+        }
+
+        let error = format!("{}: {}", status, response.text().await.unwrap_or_default());
+
+        if status.is_client_error()
+            && status != StatusCode::REQUEST_TIMEOUT
+            && status != StatusCode::TOO_MANY_REQUESTS
+        {
+            Err(DeliveryError::Permanent(error))
+        } else {
+            Err(DeliveryError::Retryable(error))
+        }
+    }
+
+    async fn dead_letter(
+        &self,
+        target: &WebhookTarget,
+        delivery: &Delivery,
+        attempts: i32,
+        error: &str,
+    ) {
+        let Delivery { payload, body } = delivery;
+
+        error!(
+            "Giving up on webhook {} for {} to {} after {} attempts: {}",
+            payload.id, payload.event, target.url, attempts, error
+        );
+
+        let Some(path) = &self.dead_letters else {
+            return;
+        };
+
+        let entry = json!({
+            "failedAt": Utc::now(),
+            "url": target.url,
+            "attempts": attempts,
+            "error": error,
+            "payload": serde_json::from_str::<serde_json::Value>(body).unwrap_or_default(),
+        });
+
+        let result = async {
+            let mut file = tokio::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .await?;
+            file.write_all(format!("{}\n", entry).as_bytes()).await
+        }
+        .await;
+
+        if let Err(err) = result {
+            error!(
+                "Failed to write webhook {} to the dead-letter log {}: {}",
+                payload.id,
+                path.display(),
+                err
+            );
+        }
+    }
+}
+
+/// Queues the webhooks of the targets, see [`WebhookDispatcher::start`]
+pub struct WebhookQueue {
+    dispatcher: WebhookDispatcher,
+    workers: Vec<(WebhookTarget, mpsc::Sender<Arc<Delivery>>)>,
+}
+
+impl WebhookQueue {
+    /// Queues the event for the targets subscribed to it and returns for how many it was
+    /// queued. The webhook is written to the dead-letter log when the queue of a target is full.
+    pub async fn enqueue(&self, event: TransferEvent, transfer: Option<WebhookTransfer>) -> usize {
+        let workers = self
+            .workers
+            .iter()
+            .filter(|(target, _)| target.accepts(&event))
+            .collect::<Vec<_>>();
+
+        if workers.is_empty() {
+            return 0;
+        }
+
+        let Some(delivery) = Delivery::new(event, transfer).map(Arc::new) else {
+            return 0;
+        };
+
+        let mut queued = 0;
+        for (target, sender) in workers {
+            let error = match sender.try_send(delivery.clone()) {
+                Ok(()) => {
+                    queued += 1;
+                    continue;
+                }
+                Err(TrySendError::Full(_)) => "Webhook queue is full",
+                Err(TrySendError::Closed(_)) => "Webhook queue is closed",
+            };
+
+            self.dispatcher
+                .dead_letter(target, &delivery, 0, error)
+                .await;
+        }
+
+        queued
+    }
+}
+
+/// Hex encoded HMAC-SHA256 of `{timestamp}.{body}`, prefixed with `sha256=`
+pub fn sign(secret: &SecretString, timestamp: &str, body: &str) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.expose_secret().as_bytes());
+    let tag = hmac::sign(&key, format!("{}.{}", timestamp, body).as_bytes());
+
+    let hex = tag
+        .as_ref()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<String>();
+
+    format!("sha256={}", hex)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use secrecy::SecretString;
+    use serde_json::{json, Value};
+    use wiremock::{
+        matchers::{header_exists, method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    use crate::{
+        core::{
+            model::transfer::{Transfer, TransferEdr, TransferStatus},
+            service::events::TransferEvent,
+        },
+        signaling::DataAddress,
+    };
+
+    use super::{
+        sign, WebhookDispatcher, WebhookTarget, WebhookTransfer, SIGNATURE_HEADER, TIMESTAMP_HEADER,
+    };
+
+    fn target(url: String, events: &[&str]) -> WebhookTarget {
+        WebhookTarget {
+            url,
+            events: events.iter().map(|event| event.to_string()).collect(),
+        }
+    }
+
+    fn create_dispatcher(targets: Vec<WebhookTarget>) -> WebhookDispatcher {
+        WebhookDispatcher::builder()
+            .targets(targets)
+            .secret(SecretString::from("secret"))
+            .queue_size(10)
+            .max_attempts(3)
+            .initial_backoff(Duration::milliseconds(1))
+            .max_backoff(Duration::milliseconds(10))
+            .build()
+    }
+
+    fn started() -> TransferEvent {
+        TransferEvent::Started {
+            transfer_id: "process_id".to_string(),
+            endpoint: Some("http://proxy/public".to_string()),
+        }
+    }
+
+    fn create_transfer() -> Transfer {
+        Transfer::builder()
+            .id("process_id".to_string())
+            .participant_id("participant_id".to_string())
+            .source(
+                DataAddress::builder()
+                    .endpoint_type("HttpData".to_string())
+                    .endpoint_properties(vec![])
+                    .build(),
+            )
+            .status(TransferStatus::Started)
+            .build()
+    }
+
+    async fn webhook_server(status: u16, expected: u64) -> MockServer {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/hook"))
+            .respond_with(ResponseTemplate::new(status))
+            .expect(expected)
+            .mount(&server)
+            .await;
+        server
+    }
+
+    #[tokio::test]
+    async fn dispatch_sends_signed_payload() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/hook"))
+            .and(header_exists(SIGNATURE_HEADER))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let dispatcher = create_dispatcher(vec![target(format!("{}/hook", server.uri()), &[])]);
+
+        let edr = TransferEdr::builder()
+            .endpoint("http://proxy/public")
+            .token_id("token_id")
+            .refreshable(true)
+            .build();
+
+        let delivered = dispatcher
+            .dispatch(
+                started(),
+                Some(WebhookTransfer::new(&create_transfer(), Some(edr))),
+            )
+            .await;
+
+        assert_eq!(delivered, 1);
+
+        let request = &server.received_requests().await.unwrap()[0];
+        let body = String::from_utf8(request.body.clone()).unwrap();
+        let header = |name: &str| request.headers.get(name).unwrap().to_str().unwrap();
+
+        assert_eq!(
+            header(SIGNATURE_HEADER),
+            sign(
+                &SecretString::from("secret"),
+                header(TIMESTAMP_HEADER),
+                &body
+            )
+        );
+
+        let payload = serde_json::from_str::<Value>(&body).unwrap();
+        assert_eq!(payload["event"], "started");
+        assert_eq!(payload["data"]["endpoint"], "http://proxy/public");
+        assert_eq!(payload["transfer"]["participantId"], "participant_id");
+        assert_eq!(payload["transfer"]["status"], "STARTED");
+        assert_eq!(
+            payload["transfer"]["edr"],
+            json!({
+                "endpoint": "http://proxy/public",
+                "tokenId": "token_id",
+                "expiresAt": null,
+                "refreshable": true
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn dispatch_filters_events() {
+        let server = webhook_server(204, 1).await;
+        let url = format!("{}/hook", server.uri());
+
+        let dispatcher = create_dispatcher(vec![
+            target(url.clone(), &["started"]),
+            target(url, &["terminated"]),
+        ]);
+
+        assert_eq!(dispatcher.dispatch(started(), None).await, 1);
+
+        server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn dispatch_retries_until_dead_letter() {
+        let server = webhook_server(503, 3).await;
+        let dead_letters = std::env::temp_dir().join(format!("{}.jsonl", uuid::Uuid::new_v4()));
+
+        let mut dispatcher = create_dispatcher(vec![target(format!("{}/hook", server.uri()), &[])]);
+        dispatcher.dead_letters = Some(dead_letters.clone());
+
+        assert_eq!(dispatcher.dispatch(started(), None).await, 0);
+        server.verify().await;
+
+        let log = tokio::fs::read_to_string(&dead_letters).await.unwrap();
+        let entry = serde_json::from_str::<Value>(log.lines().next().unwrap()).unwrap();
+
+        assert_eq!(entry["attempts"], 3);
+        assert!(entry["error"].as_str().unwrap().starts_with("503"));
+        assert_eq!(entry["payload"]["data"]["transferId"], "process_id");
+
+        tokio::fs::remove_file(dead_letters).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn dispatch_gives_up_on_rejection() {
+        let server = webhook_server(400, 1).await;
+
+        let dispatcher = create_dispatcher(vec![target(format!("{}/hook", server.uri()), &[])]);
+
+        assert_eq!(dispatcher.dispatch(started(), None).await, 0);
+        server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn queue_keeps_order_of_target() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/hook"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/hook"))
+            .respond_with(ResponseTemplate::new(204))
+            .mount(&server)
+            .await;
+
+        let dispatcher = create_dispatcher(vec![target(format!("{}/hook", server.uri()), &[])]);
+        let queue = dispatcher.start();
+
+        let suspended = TransferEvent::Suspended {
+            transfer_id: "process_id".to_string(),
+            reason: None,
+        };
+        let terminated = TransferEvent::Terminated {
+            transfer_id: "process_id".to_string(),
+            reason: None,
+        };
+
+        for event in [started(), suspended, terminated] {
+            assert_eq!(queue.enqueue(event, None).await, 1);
+        }
+
+        let mut events = vec![];
+        for _ in 0..100 {
+            events = server
+                .received_requests()
+                .await
+                .unwrap()
+                .iter()
+                .map(|request| {
+                    serde_json::from_slice::<Value>(&request.body).unwrap()["event"].clone()
+                })
+                .collect::<Vec<_>>();
+
+            if events.len() == 4 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+
+        assert_eq!(
+            events,
+            vec!["started", "started", "suspended", "terminated"]
+        );
+    }
+
+    #[test]
+    fn sign_is_hmac_sha256() {
+        // echo -n '1700000000.{}' | openssl dgst -sha256 -hmac secret
+        assert_eq!(
+            sign(&SecretString::from("secret"), "1700000000", "{}"),
+            "sha256=b8569b78799ff9e3cbff0fc2d63a33a2b57f3282abd07c37ae5e8e7d79a5f163"
+        );
+    }
+}
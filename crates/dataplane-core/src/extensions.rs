@@ -1,5 +1,11 @@
+pub mod config;
 pub mod repo;
 pub mod service;
 
-pub use repo::sqlite::sql_repo_extension;
+pub use repo::sql::sql_repo_extension;
+pub use service::events::transfer_events_extension;
+pub use service::notification::notification_extension;
+pub use service::reaper::reaper_extension;
+pub use service::registry::{endpoint_types_extension, transfer_managers_extension};
 pub use service::transfer::transfer_service_extension;
+pub use service::webhook::webhook_extension;
@@ -0,0 +1,17 @@
+use config::ConfigError;
+use miwa::core::{Configurable, FromMiwaContext, MiwaContext, MiwaError, MiwaResult};
+use serde::de::DeserializeOwned;
+
+/// Like [`ExtensionConfig`](miwa::core::ExtensionConfig) for sections that may be left out of
+/// the configuration, `None` when the section is missing
+pub struct OptionalConfig<T>(pub Option<T>);
+
+impl<'a, T: Configurable + DeserializeOwned> FromMiwaContext<'a> for OptionalConfig<T> {
+    fn from_context(context: &'a MiwaContext) -> MiwaResult<Self> {
+        match context.config().get::<T>(T::prefix()) {
+            Ok(cfg) => Ok(OptionalConfig(Some(cfg))),
+            Err(MiwaError::Config(ConfigError::NotFound(_))) => Ok(OptionalConfig(None)),
+            Err(err) => Err(err),
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use miwa::{
+    core::{Extension, ExtensionConfig, MiwaContext, MiwaResult},
+    derive::{extension, ExtensionConfig},
+};
+use serde::Deserialize;
+
+use crate::core::db::{
+    memory::transfer::InMemoryTransferRepo,
+    notification::NotificationRepoRef,
+    postgres::{notification::PgNotificationRepo, transfer::PgTransferRepo, PgPoolConfig},
+    sqlite::{notification::SqliteNotificationRepo, transfer::SqliteTransferRepo},
+    transfer::TransferRepoRef,
+};
+
+pub struct SqlRepoExtension {}
+
+#[async_trait::async_trait]
+impl Extension for SqlRepoExtension {
+    async fn start(&self) -> MiwaResult<()> {
+        Ok(())
+    }
+
+    async fn shutdown(&self) -> MiwaResult<()> {
+        Ok(())
+    }
+}
+
+#[derive(Deserialize, ExtensionConfig)]
+#[config(prefix = "db.transfers")]
+#[serde(rename_all = "lowercase")]
+pub enum TransferDbConfig {
+    /// Kept in memory without any migration, the transfers are lost on restart
+    Memory {},
+    Sqlite {
+        path: String,
+    },
+    Postgres {
+        url: String,
+        #[serde(default)]
+        pool: PgPoolConfig,
+    },
+}
+
+#[extension(
+    name = "Sql store extensions for dataplane",
+    provides(TransferRepoRef, NotificationRepoRef)
+)]
+pub async fn sql_repo_extension(
+    ctx: &MiwaContext,
+    ExtensionConfig(cfg): ExtensionConfig<TransferDbConfig>,
+) -> MiwaResult<SqlRepoExtension> {
+    let (transfers, notifications) = create_transfer_store(cfg).await?;
+    ctx.register(transfers);
+    ctx.register(notifications);
+    Ok(SqlRepoExtension {})
+}
+
+async fn create_transfer_store(
+    cfg: TransferDbConfig,
+) -> anyhow::Result<(TransferRepoRef, NotificationRepoRef)> {
+    match cfg {
+        TransferDbConfig::Memory {} => {
+            let store = InMemoryTransferRepo::new();
+            let notifications = store.notifications();
+
+            Ok((
+                TransferRepoRef::of(store),
+                NotificationRepoRef::of(notifications),
+            ))
+        }
+        TransferDbConfig::Sqlite { path } => {
+            let store = SqliteTransferRepo::connect(&format!("sqlite:{}", path)).await?;
+            store.migrate().await?;
+
+            let notifications = SqliteNotificationRepo::new(store.pool().clone());
+            Ok((
+                TransferRepoRef::of(store),
+                NotificationRepoRef::of(notifications),
+            ))
+        }
+        TransferDbConfig::Postgres { url, pool } => {
+            let store = PgTransferRepo::connect_with(&url, &pool).await?;
+            store.migrate().await?;
+
+            let notifications = PgNotificationRepo::new(store.pool().clone());
+            Ok((
+                TransferRepoRef::of(store),
+                NotificationRepoRef::of(notifications),
+            ))
+        }
+    }
+}
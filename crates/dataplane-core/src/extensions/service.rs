@@ -1 +1,6 @@
+pub mod events;
+pub mod notification;
+pub mod reaper;
+pub mod registry;
 pub mod transfer;
+pub mod webhook;
@@ -0,0 +1,25 @@
+use miwa::{
+    core::{Extension, MiwaContext, MiwaResult},
+    derive::extension,
+};
+
+use crate::core::service::events::TransferEvents;
+
+pub struct TransferEventsExtension;
+
+#[async_trait::async_trait]
+impl Extension for TransferEventsExtension {
+    async fn start(&self) -> MiwaResult<()> {
+        Ok(())
+    }
+
+    async fn shutdown(&self) -> MiwaResult<()> {
+        Ok(())
+    }
+}
+
+#[extension(name = "Transfer events extension", provides(TransferEvents))]
+pub async fn transfer_events_extension(ctx: &MiwaContext) -> MiwaResult<TransferEventsExtension> {
+    ctx.register(TransferEvents::default());
+    Ok(TransferEventsExtension)
+}
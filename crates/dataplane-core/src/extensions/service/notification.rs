@@ -0,0 +1,130 @@
+use std::sync::Mutex;
+
+use chrono::Duration;
+use miwa::{
+    core::{Extension, MiwaContext, MiwaResult},
+    derive::{extension, ExtensionConfig},
+};
+use serde::Deserialize;
+use tokio::task::JoinHandle;
+use tracing::error;
+
+use crate::{
+    core::{db::notification::NotificationRepoRef, service::notification::NotificationDispatcher},
+    extensions::config::OptionalConfig,
+};
+
+pub struct NotificationExtension {
+    dispatcher: NotificationDispatcher,
+    poll_interval: u64,
+    job: Mutex<Option<JoinHandle<()>>>,
+}
+
+#[async_trait::async_trait]
+impl Extension for NotificationExtension {
+    async fn start(&self) -> MiwaResult<()> {
+        let dispatcher = self.dispatcher.clone();
+        let poll_interval = std::time::Duration::from_secs(self.poll_interval);
+
+        let job = tokio::task::spawn(async move {
+            loop {
+                if let Err(err) = dispatcher.dispatch().await {
+                    error!("Failed to dispatch notifications: {}", err);
+                }
+                tokio::time::sleep(poll_interval).await;
+            }
+        });
+
+        *self.job.lock().unwrap() = Some(job);
+        Ok(())
+    }
+
+    async fn shutdown(&self) -> MiwaResult<()> {
+        if let Some(job) = self.job.lock().unwrap().take() {
+            job.abort();
+        }
+        Ok(())
+    }
+}
+
+/// Delivery of the transfer notifications to the control plane, durations are in seconds.
+/// The section is optional, the defaults apply when it is missing
+#[derive(Deserialize, ExtensionConfig, Clone)]
+#[config(prefix = "notifications")]
+pub struct NotificationConfig {
+    #[serde(default = "default_poll_interval")]
+    pub poll_interval: u64,
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: i32,
+    #[serde(default = "default_initial_backoff")]
+    pub initial_backoff: i64,
+    #[serde(default = "default_max_backoff")]
+    pub max_backoff: i64,
+    #[serde(default = "default_batch_size")]
+    pub batch_size: i32,
+    /// Delay after which a notification claimed by a replica that did not report back is
+    /// delivered again
+    #[serde(default = "default_lease")]
+    pub lease: i64,
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval: default_poll_interval(),
+            max_attempts: default_max_attempts(),
+            initial_backoff: default_initial_backoff(),
+            max_backoff: default_max_backoff(),
+            batch_size: default_batch_size(),
+            lease: default_lease(),
+        }
+    }
+}
+
+pub fn default_poll_interval() -> u64 {
+    5
+}
+
+pub fn default_max_attempts() -> i32 {
+    10
+}
+
+pub fn default_initial_backoff() -> i64 {
+    1
+}
+
+pub fn default_max_backoff() -> i64 {
+    60 * 5
+}
+
+pub fn default_batch_size() -> i32 {
+    50
+}
+
+pub fn default_lease() -> i64 {
+    60
+}
+
+#[extension(name = "Transfer notifications extension")]
+pub async fn notification_extension(
+    _ctx: &MiwaContext,
+    repo: NotificationRepoRef,
+    OptionalConfig(cfg): OptionalConfig<NotificationConfig>,
+) -> MiwaResult<NotificationExtension> {
+    let cfg = cfg.unwrap_or_default();
+
+    let dispatcher = NotificationDispatcher::builder()
+        .repo(repo)
+        .max_attempts(cfg.max_attempts)
+        .initial_backoff(Duration::seconds(cfg.initial_backoff))
+        .max_backoff(Duration::seconds(cfg.max_backoff))
+        .batch_size(cfg.batch_size)
+        .lease(Duration::seconds(cfg.lease))
+        .build();
+
+    Ok(NotificationExtension {
+        dispatcher,
+        poll_interval: cfg.poll_interval,
+        job: Mutex::new(None),
+    })
+}
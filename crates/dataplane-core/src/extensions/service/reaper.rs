@@ -0,0 +1,118 @@
+use std::sync::Mutex;
+
+use chrono::Duration;
+use miwa::{
+    core::{Extension, MiwaContext, MiwaResult},
+    derive::{extension, ExtensionConfig},
+};
+use serde::Deserialize;
+use tokio::task::JoinHandle;
+use tracing::error;
+
+use crate::{
+    core::service::{reaper::TransferReaper, transfer::TransferService},
+    extensions::config::OptionalConfig,
+};
+
+pub struct ReaperExtension {
+    reaper: TransferReaper,
+    enabled: bool,
+    interval: u64,
+    job: Mutex<Option<JoinHandle<()>>>,
+}
+
+#[async_trait::async_trait]
+impl Extension for ReaperExtension {
+    async fn start(&self) -> MiwaResult<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+
+        let reaper = self.reaper.clone();
+        let interval = std::time::Duration::from_secs(self.interval);
+
+        let job = tokio::task::spawn(async move {
+            loop {
+                if let Err(err) = reaper.run().await {
+                    error!("Failed to reap transfers: {}", err);
+                }
+                tokio::time::sleep(interval).await;
+            }
+        });
+
+        *self.job.lock().unwrap() = Some(job);
+        Ok(())
+    }
+
+    async fn shutdown(&self) -> MiwaResult<()> {
+        if let Some(job) = self.job.lock().unwrap().take() {
+            job.abort();
+        }
+        Ok(())
+    }
+}
+
+/// Maintenance of the transfers and of their EDRs, durations are in seconds. The section is
+/// optional, the defaults apply when it is missing. The reaper only runs when enabled, on a
+/// single one of the replicas sharing the database
+#[derive(Deserialize, ExtensionConfig, Clone)]
+#[config(prefix = "reaper")]
+pub struct ReaperConfig {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_interval")]
+    pub interval: u64,
+    /// Transfers not in a final state are terminated past this age, never when unset
+    pub max_age: Option<i64>,
+    /// Transfers in a final state are deleted once unchanged for this long, never when unset
+    pub retention: Option<i64>,
+    #[serde(default = "default_batch_size")]
+    pub batch_size: i32,
+}
+
+impl Default for ReaperConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            interval: default_interval(),
+            max_age: None,
+            retention: None,
+            batch_size: default_batch_size(),
+        }
+    }
+}
+
+pub fn default_enabled() -> bool {
+    false
+}
+
+pub fn default_interval() -> u64 {
+    60 * 60
+}
+
+pub fn default_batch_size() -> i32 {
+    50
+}
+
+#[extension(name = "Transfer reaper extension")]
+pub async fn reaper_extension(
+    _ctx: &MiwaContext,
+    transfers: TransferService,
+    OptionalConfig(cfg): OptionalConfig<ReaperConfig>,
+) -> MiwaResult<ReaperExtension> {
+    let cfg = cfg.unwrap_or_default();
+
+    let reaper = TransferReaper::builder()
+        .transfers(transfers)
+        .maybe_max_age(cfg.max_age.map(Duration::seconds))
+        .maybe_retention(cfg.retention.map(Duration::seconds))
+        .batch_size(cfg.batch_size)
+        .build();
+
+    Ok(ReaperExtension {
+        reaper,
+        enabled: cfg.enabled,
+        interval: cfg.interval,
+        job: Mutex::new(None),
+    })
+}
@@ -0,0 +1,47 @@
+use miwa::{
+    core::{Extension, MiwaContext, MiwaResult},
+    derive::extension,
+};
+
+use crate::core::service::registry::{EndpointTypes, TransferManagers};
+
+pub struct TransferManagersExtension;
+
+#[async_trait::async_trait]
+impl Extension for TransferManagersExtension {
+    async fn start(&self) -> MiwaResult<()> {
+        Ok(())
+    }
+
+    async fn shutdown(&self) -> MiwaResult<()> {
+        Ok(())
+    }
+}
+
+#[extension(name = "Transfer managers extension", provides(TransferManagers))]
+pub async fn transfer_managers_extension(
+    ctx: &MiwaContext,
+) -> MiwaResult<TransferManagersExtension> {
+    ctx.register(TransferManagers::default());
+    Ok(TransferManagersExtension)
+}
+
+pub struct EndpointTypesExtension;
+
+#[async_trait::async_trait]
+impl Extension for EndpointTypesExtension {
+    async fn start(&self) -> MiwaResult<()> {
+        Ok(())
+    }
+
+    async fn shutdown(&self) -> MiwaResult<()> {
+        Ok(())
+    }
+}
+
+/// Provides the default endpoint types, extensions register their own ones on top of them
+#[extension(name = "Endpoint types extension", provides(EndpointTypes))]
+pub async fn endpoint_types_extension(ctx: &MiwaContext) -> MiwaResult<EndpointTypesExtension> {
+    ctx.register(EndpointTypes::default());
+    Ok(EndpointTypesExtension)
+}
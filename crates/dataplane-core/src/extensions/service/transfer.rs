@@ -5,7 +5,10 @@ use miwa::{
 
 use crate::core::{
     db::transfer::TransferRepoRef,
-    service::transfer::{TransferManagerRef, TransferService},
+    service::{
+        events::TransferEvents, notification::NotificationService, registry::TransferManagers,
+        transfer::TransferService,
+    },
 };
 
 pub struct TransferServiceExtension;
@@ -24,9 +27,14 @@ impl Extension for TransferServiceExtension {
 #[extension(name = "Transfer Service sextension", provides(TransferService))]
 pub async fn transfer_service_extension(
     ctx: &MiwaContext,
-    manager: TransferManagerRef,
+    managers: TransferManagers,
     repo: TransferRepoRef,
+    events: TransferEvents,
 ) -> MiwaResult<TransferServiceExtension> {
-    ctx.register(TransferService::new(manager, repo));
+    ctx.register(
+        TransferService::new(managers, repo)
+            .with_notifications(NotificationService::new())
+            .with_events(events),
+    );
     Ok(TransferServiceExtension)
 }
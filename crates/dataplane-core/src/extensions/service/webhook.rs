@@ -0,0 +1,245 @@
+use std::{path::PathBuf, sync::Mutex};
+
+use chrono::Duration;
+use miwa::{
+    core::{Extension, MiwaContext, MiwaResult},
+    derive::{extension, ExtensionConfig},
+};
+use secrecy::SecretString;
+use serde::Deserialize;
+use tokio::{sync::broadcast::error::RecvError, task::JoinHandle};
+use tracing::{error, warn};
+
+use crate::{
+    core::service::{
+        events::{TransferEvent, TransferEvents},
+        transfer::TransferService,
+        webhook::{WebhookDispatcher, WebhookTarget, WebhookTransfer},
+    },
+    extensions::config::OptionalConfig,
+};
+
+pub struct WebhookExtension {
+    /// Missing when no target is configured
+    dispatcher: Option<WebhookDispatcher>,
+    events: TransferEvents,
+    transfers: TransferService,
+    job: Mutex<Option<JoinHandle<()>>>,
+}
+
+#[async_trait::async_trait]
+impl Extension for WebhookExtension {
+    async fn start(&self) -> MiwaResult<()> {
+        let Some(dispatcher) = &self.dispatcher else {
+            return Ok(());
+        };
+
+        let queue = dispatcher.start();
+        let transfers = self.transfers.clone();
+        let mut receiver = self.events.subscribe();
+
+        let job = tokio::task::spawn(async move {
+            loop {
+                let event = match receiver.recv().await {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(missed)) => {
+                        warn!("Webhooks missed {} transfer events", missed);
+                        continue;
+                    }
+                    Err(RecvError::Closed) => break,
+                };
+
+                let transfer = match event.transfer_id() {
+                    Some(id) => transfers.get(id).await.unwrap_or_else(|err| {
+                        error!("Failed to fetch transfer {} for webhooks: {}", id, err);
+                        None
+                    }),
+                    None => None,
+                };
+
+                let transfer = match transfer {
+                    Some(transfer) => {
+                        let edr = transfers.edr(&transfer).await.unwrap_or_else(|err| {
+                            error!(
+                                "Failed to look up the EDR of transfer {} for webhooks: {}",
+                                transfer.id, err
+                            );
+                            None
+                        });
+                        Some(WebhookTransfer::new(&transfer, edr))
+                    }
+                    None => None,
+                };
+
+                queue.enqueue(event, transfer).await;
+            }
+        });
+
+        *self.job.lock().unwrap() = Some(job);
+        Ok(())
+    }
+
+    async fn shutdown(&self) -> MiwaResult<()> {
+        if let Some(job) = self.job.lock().unwrap().take() {
+            job.abort();
+        }
+        Ok(())
+    }
+}
+
+/// Webhooks notified of the transfer events, durations are in seconds. No webhook is sent
+/// when the section is missing
+#[derive(Deserialize, ExtensionConfig, Clone)]
+#[config(prefix = "webhooks")]
+pub struct WebhookConfig {
+    #[serde(default)]
+    pub targets: Vec<WebhookTarget>,
+    /// HMAC-SHA256 key of the `X-Webhook-Signature` header, required with targets
+    pub secret: Option<SecretString>,
+    /// Webhooks waiting for delivery to each target, the ones beyond are dead-lettered
+    #[serde(default = "default_queue_size")]
+    pub queue_size: usize,
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: i32,
+    #[serde(default = "default_initial_backoff")]
+    pub initial_backoff: i64,
+    #[serde(default = "default_max_backoff")]
+    pub max_backoff: i64,
+    /// JSON lines file of the webhooks given up on
+    pub dead_letter_file: Option<PathBuf>,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            targets: vec![],
+            secret: None,
+            queue_size: default_queue_size(),
+            max_attempts: default_max_attempts(),
+            initial_backoff: default_initial_backoff(),
+            max_backoff: default_max_backoff(),
+            dead_letter_file: None,
+        }
+    }
+}
+
+impl WebhookConfig {
+    /// Checks that the webhooks can be signed and that the targets subscribe to known events
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.targets.is_empty() {
+            return Ok(());
+        }
+
+        if self.secret.is_none() {
+            anyhow::bail!("`webhooks.secret` is required to sign the webhooks");
+        }
+
+        for target in &self.targets {
+            if let Some(event) = target
+                .events
+                .iter()
+                .find(|event| !TransferEvent::NAMES.contains(&event.as_str()))
+            {
+                anyhow::bail!(
+                    "Unknown event {} of webhook target {}, expected one of {}",
+                    event,
+                    target.url,
+                    TransferEvent::NAMES.join(", ")
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub fn default_queue_size() -> usize {
+    1000
+}
+
+pub fn default_max_attempts() -> i32 {
+    5
+}
+
+pub fn default_initial_backoff() -> i64 {
+    1
+}
+
+pub fn default_max_backoff() -> i64 {
+    60
+}
+
+#[extension(name = "Transfer webhooks extension")]
+pub async fn webhook_extension(
+    _ctx: &MiwaContext,
+    events: TransferEvents,
+    transfers: TransferService,
+    OptionalConfig(cfg): OptionalConfig<WebhookConfig>,
+) -> MiwaResult<WebhookExtension> {
+    let cfg = cfg.unwrap_or_default();
+    cfg.validate()?;
+
+    let dispatcher = cfg
+        .secret
+        .filter(|_| !cfg.targets.is_empty())
+        .map(|secret| {
+            WebhookDispatcher::builder()
+                .targets(cfg.targets)
+                .secret(secret)
+                .queue_size(cfg.queue_size)
+                .max_attempts(cfg.max_attempts)
+                .initial_backoff(Duration::seconds(cfg.initial_backoff))
+                .max_backoff(Duration::seconds(cfg.max_backoff))
+                .maybe_dead_letters(cfg.dead_letter_file)
+                .build()
+        });
+
+    Ok(WebhookExtension {
+        dispatcher,
+        events,
+        transfers,
+        job: Mutex::new(None),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use secrecy::SecretString;
+
+    use crate::core::service::webhook::WebhookTarget;
+
+    use super::WebhookConfig;
+
+    fn config(events: &[&str], secret: Option<&str>) -> WebhookConfig {
+        WebhookConfig {
+            targets: vec![WebhookTarget {
+                url: "http://localhost:9000/hooks".to_string(),
+                events: events.iter().map(|event| event.to_string()).collect(),
+            }],
+            secret: secret.map(SecretString::from),
+            ..WebhookConfig::default()
+        }
+    }
+
+    #[test]
+    fn validate_accepts_known_events() {
+        assert!(config(&["started", "completed"], Some("secret"))
+            .validate()
+            .is_ok());
+        assert!(WebhookConfig::default().validate().is_ok());
+    }
+
+    #[test]
+    fn validate_requires_secret() {
+        assert!(config(&[], None).validate().is_err());
+    }
+
+    #[test]
+    fn validate_rejects_unknown_events() {
+        let err = config(&["started", "complete"], Some("secret"))
+            .validate()
+            .unwrap_err();
+
+        assert!(err.to_string().starts_with("Unknown event complete"));
+    }
+}
@@ -1,10 +1,18 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt};
 
 use bon::Builder;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use serde_with::{formats::PreferMany, serde_as, OneOrMany};
 
+use crate::core::model::{
+    namespace::{compact_iri, expand_iri},
+    transfer::{Transfer, TransferStatus},
+};
+
+pub mod jsonld;
+
 #[derive(Debug, Serialize, Deserialize, Clone, Builder)]
 #[serde(rename_all = "camelCase")]
 pub struct DataFlowStartMessage {
@@ -12,21 +20,139 @@ pub struct DataFlowStartMessage {
     dataset_id: String,
     pub participant_id: String,
     pub process_id: String,
-    flow_type: FlowType,
+    pub flow_type: FlowType,
+    pub transfer_type_destination: Option<String>,
     properties: HashMap<String, Value>,
     pub source_data_address: DataAddress,
+    pub destination_data_address: Option<DataAddress>,
+    pub callback_address: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/// Sent to the consumer dataplane before the transfer is requested to the provider
+#[derive(Debug, Serialize, Deserialize, Clone, Builder)]
+#[serde(rename_all = "camelCase")]
+pub struct DataFlowPrepareMessage {
+    agreement_id: String,
+    dataset_id: String,
+    pub participant_id: String,
+    pub process_id: String,
+    pub flow_type: FlowType,
+    pub transfer_type_destination: Option<String>,
+    properties: HashMap<String, Value>,
+    /// Requested destination, the dataplane may provision another one
+    pub destination_data_address: Option<DataAddress>,
+    pub callback_address: Option<String>,
+}
+
+/// Sent to the consumer dataplane once the provider started the transfer
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DataFlowStartedNotificationMessage {
+    /// Provider endpoint of PULL transfers
+    pub data_address: Option<DataAddress>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, sqlx::Type)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[sqlx(type_name = "text", rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum FlowType {
+    #[default]
     Pull,
+    Push,
+}
+
+impl DataFlowStartMessage {
+    pub fn agreement_id(&self) -> &str {
+        &self.agreement_id
+    }
+
+    pub fn dataset_id(&self) -> &str {
+        &self.dataset_id
+    }
+
+    pub fn properties(&self) -> &HashMap<String, Value> {
+        &self.properties
+    }
+
+    /// Looks up a property by term, compact IRI or IRI like [`DataAddress::get_property`]
+    pub fn property(&self, name: &str) -> Option<&Value> {
+        let name = expand_iri(name);
+        self.properties
+            .iter()
+            .find(|(key, _)| expand_iri(key) == name)
+            .map(|(_, value)| value)
+    }
+
+    /// End of the contract agreement from the `agreementEndDate` property, either an
+    /// RFC 3339 date or seconds since the epoch
+    pub fn agreement_end(&self) -> Option<DateTime<Utc>> {
+        match self.property("agreementEndDate")? {
+            Value::String(date) => DateTime::parse_from_rfc3339(date)
+                .ok()
+                .map(|date| date.with_timezone(&Utc)),
+            Value::Number(seconds) => DateTime::from_timestamp(seconds.as_i64()?, 0),
+            _ => None,
+        }
+    }
+
+    /// Transfer type such as `HttpData-PULL`, made of the destination type and the flow type
+    pub fn transfer_type(&self) -> Option<String> {
+        transfer_type(
+            self.transfer_type_destination.as_deref(),
+            self.destination_data_address.as_ref(),
+            &self.flow_type,
+        )
+    }
+}
+
+impl DataFlowPrepareMessage {
+    pub fn agreement_id(&self) -> &str {
+        &self.agreement_id
+    }
+
+    pub fn dataset_id(&self) -> &str {
+        &self.dataset_id
+    }
+
+    pub fn properties(&self) -> &HashMap<String, Value> {
+        &self.properties
+    }
+
+    /// Transfer type such as `HttpData-PULL`, made of the destination type and the flow type
+    pub fn transfer_type(&self) -> Option<String> {
+        transfer_type(
+            self.transfer_type_destination.as_deref(),
+            self.destination_data_address.as_ref(),
+            &self.flow_type,
+        )
+    }
+}
+
+fn transfer_type(
+    destination_type: Option<&str>,
+    destination: Option<&DataAddress>,
+    flow_type: &FlowType,
+) -> Option<String> {
+    destination_type
+        .or(destination.map(|address| address.endpoint_type.as_str()))
+        .map(|destination_type| format!("{}-{}", compact_iri(destination_type), flow_type))
+}
+
+impl fmt::Display for FlowType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FlowType::Pull => write!(f, "PULL"),
+            FlowType::Push => write!(f, "PUSH"),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct DataFlowResponseMessage {
     pub data_address: Option<DataAddress>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<TransferStatus>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -41,11 +167,52 @@ pub struct DataFlowSuspendMessage {
     pub reason: Option<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DataFlowStatusMessage {
+    pub process_id: String,
+    pub participant_id: String,
+    pub state: TransferStatus,
+    pub flow_type: FlowType,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suspension_reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub termination_reason: Option<String>,
+}
+
+impl From<Transfer> for DataFlowStatusMessage {
+    fn from(transfer: Transfer) -> Self {
+        Self {
+            process_id: transfer.id,
+            participant_id: transfer.participant_id,
+            state: transfer.status,
+            flow_type: transfer.flow_type,
+            created_at: transfer.created_at,
+            updated_at: transfer.updated_at,
+            suspension_reason: transfer.suspension_reason,
+            termination_reason: transfer.termination_reason,
+        }
+    }
+}
+
 impl DataFlowTerminateMessage {}
 
 impl DataFlowResponseMessage {
     pub fn new(data_address: Option<DataAddress>) -> Self {
-        Self { data_address }
+        Self {
+            data_address,
+            state: None,
+        }
+    }
+
+    /// Reports a consumer transfer ready to receive data at the given address
+    pub fn prepared(data_address: Option<DataAddress>) -> Self {
+        Self {
+            data_address,
+            state: Some(TransferStatus::Prepared),
+        }
     }
 }
 
@@ -61,12 +228,21 @@ pub struct DataAddress {
 }
 
 impl DataAddress {
+    /// Looks up a property by term, compact IRI or IRI, `baseUrl`, `edc:baseUrl` and
+    /// `https://w3id.org/edc/v0.0.1/ns/baseUrl` being the same property
     pub fn get_property(&self, name: &str) -> Option<&str> {
+        let name = expand_iri(name);
         self.endpoint_properties
             .iter()
-            .find(|p| p.name == name)
+            .find(|p| expand_iri(&p.name) == name)
             .map(|p| p.value.as_str())
     }
+
+    /// Whether the address is of the given endpoint type, `HttpData` matching
+    /// `https://w3id.org/edc/v0.0.1/ns/HttpData` as well
+    pub fn is_type(&self, name: &str) -> bool {
+        compact_iri(&self.endpoint_type) == name
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Builder, PartialEq)]
@@ -78,3 +254,91 @@ pub struct EndpointProperty {
     #[builder(into)]
     pub value: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use chrono::DateTime;
+    use serde_json::{json, Value};
+
+    use crate::core::model::namespace::EDC_NAMESPACE;
+
+    use super::{DataAddress, DataFlowStartMessage, EndpointProperty, FlowType};
+
+    fn data_address(name: &str) -> DataAddress {
+        DataAddress::builder()
+            .endpoint_type("HttpData".to_string())
+            .endpoint_properties(vec![EndpointProperty::builder()
+                .name(name)
+                .value("http://localhost:8080")
+                .build()])
+            .build()
+    }
+
+    #[test]
+    fn get_property_is_namespace_aware() {
+        let names = ["baseUrl", "edc:baseUrl", &EDC_NAMESPACE.to_iri("baseUrl")];
+
+        for stored in names {
+            let address = data_address(stored);
+            for name in names {
+                assert_eq!(
+                    address.get_property(name),
+                    Some("http://localhost:8080"),
+                    "{} stored as {}",
+                    name,
+                    stored
+                );
+            }
+            assert_eq!(address.get_property("dspace:baseUrl"), None);
+        }
+    }
+
+    fn start_message(properties: HashMap<String, Value>) -> DataFlowStartMessage {
+        DataFlowStartMessage::builder()
+            .participant_id("participant_id".to_string())
+            .process_id("process_id".to_string())
+            .source_data_address(data_address("baseUrl"))
+            .properties(properties)
+            .flow_type(FlowType::Pull)
+            .dataset_id("dataset_id".to_string())
+            .agreement_id("agreement_id".to_string())
+            .build()
+    }
+
+    #[test]
+    fn agreement_end_from_properties() {
+        let end = DateTime::from_timestamp(1_900_000_000, 0);
+
+        let properties = [
+            ("agreementEndDate", json!("2030-03-17T17:46:40Z")),
+            ("edc:agreementEndDate", json!(1_900_000_000)),
+        ];
+
+        for (name, value) in properties {
+            let message = start_message(HashMap::from([(name.to_string(), value)]));
+            assert_eq!(message.agreement_end(), end, "{}", name);
+        }
+
+        let invalid = start_message(HashMap::from([(
+            EDC_NAMESPACE.to_iri("agreementEndDate"),
+            json!("tomorrow"),
+        )]));
+        assert_eq!(invalid.agreement_end(), None);
+        assert_eq!(start_message(HashMap::new()).agreement_end(), None);
+    }
+
+    #[test]
+    fn transfer_type_from_destination() {
+        let mut message = start_message(HashMap::new());
+        assert_eq!(message.transfer_type(), None);
+
+        message.flow_type = FlowType::Push;
+        message.destination_data_address = Some(data_address("baseUrl"));
+        assert_eq!(message.transfer_type().as_deref(), Some("HttpData-PUSH"));
+
+        message.transfer_type_destination = Some(EDC_NAMESPACE.to_iri("File"));
+        assert_eq!(message.transfer_type().as_deref(), Some("File-PUSH"));
+    }
+}
@@ -0,0 +1,507 @@
+//! Subset of the JSON-LD expansion and compaction algorithms needed for the signaling
+//! messages. Only inline contexts are supported, remote contexts are rejected.
+
+use std::collections::BTreeMap;
+
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum JsonLdError {
+    #[error("Remote context {0} is not supported")]
+    RemoteContext(String),
+    #[error("Invalid context: {0}")]
+    InvalidContext(String),
+    #[error("Invalid {keyword} value")]
+    InvalidKeywordValue { keyword: String },
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct Context {
+    vocab: Option<String>,
+    /// Ordered so that compaction picks the same term across runs
+    terms: BTreeMap<String, String>,
+}
+
+impl Context {
+    pub fn parse(context: &Value) -> Result<Self, JsonLdError> {
+        Context::default().merge(context)
+    }
+
+    /// Applies a local context on top of this one
+    pub fn merge(&self, context: &Value) -> Result<Self, JsonLdError> {
+        match context {
+            Value::Null => Ok(Context::default()),
+            Value::Array(contexts) => contexts
+                .iter()
+                .try_fold(self.clone(), |active, context| active.merge(context)),
+            Value::String(url) => Err(JsonLdError::RemoteContext(url.clone())),
+            Value::Object(definitions) => {
+                let mut active = self.clone();
+
+                match definitions.get("@vocab") {
+                    Some(Value::String(vocab)) => {
+                        active.vocab = active.expand_iri(vocab, true).or(Some(vocab.clone()))
+                    }
+                    Some(Value::Null) => active.vocab = None,
+                    Some(_) => {
+                        return Err(JsonLdError::InvalidKeywordValue {
+                            keyword: "@vocab".to_string(),
+                        })
+                    }
+                    None => {}
+                }
+
+                let terms = definitions
+                    .iter()
+                    .filter(|(term, _)| !term.starts_with('@'))
+                    .map(|(term, definition)| match definition {
+                        Value::String(iri) => Ok((term, Some(iri))),
+                        Value::Object(definition) => match definition.get("@id") {
+                            Some(Value::String(iri)) => Ok((term, Some(iri))),
+                            _ => Err(JsonLdError::InvalidContext(format!(
+                                "term {} has no @id",
+                                term
+                            ))),
+                        },
+                        Value::Null => Ok((term, None)),
+                        _ => Err(JsonLdError::InvalidContext(format!(
+                            "invalid definition of {}",
+                            term
+                        ))),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                // Prefixes first, as the other terms may be compact IRIs using them
+                let (prefixes, compact): (Vec<_>, Vec<_>) =
+                    terms.into_iter().partition(|(_, iri)| {
+                        iri.is_none_or(|iri| iri.contains("://") || !iri.contains(':'))
+                    });
+
+                for (term, iri) in prefixes.into_iter().chain(compact) {
+                    match iri {
+                        Some(iri) => {
+                            let iri = active.expand_iri(iri, true).unwrap_or(iri.clone());
+                            active.terms.insert(term.clone(), iri);
+                        }
+                        None => {
+                            active.terms.remove(term);
+                        }
+                    }
+                }
+
+                Ok(active)
+            }
+            _ => Err(JsonLdError::InvalidContext(
+                "expected an object, an array or null".to_string(),
+            )),
+        }
+    }
+
+    /// Expands a term or a compact IRI, undefined terms are dropped unless `vocab` is set
+    pub fn expand_iri(&self, value: &str, vocab: bool) -> Option<String> {
+        if value.starts_with('@') {
+            return Some(value.to_string());
+        }
+
+        if let Some(iri) = self.terms.get(value) {
+            return Some(iri.clone());
+        }
+
+        if let Some((prefix, suffix)) = value.split_once(':') {
+            if suffix.starts_with("//") {
+                return Some(value.to_string());
+            }
+            return Some(match self.terms.get(prefix) {
+                Some(iri) => format!("{}{}", iri, suffix),
+                None => value.to_string(),
+            });
+        }
+
+        match (&self.vocab, vocab) {
+            (Some(vocab), true) => Some(format!("{}{}", vocab, value)),
+            _ => None,
+        }
+    }
+
+    /// Shortest representation of the IRI: a term, a vocabulary relative term or a compact IRI
+    pub fn compact_iri(&self, iri: &str) -> String {
+        if iri.starts_with('@') {
+            return iri.to_string();
+        }
+
+        if let Some(term) = self
+            .terms
+            .iter()
+            .filter(|(_, value)| *value == iri)
+            .map(|(term, _)| term)
+            .min_by_key(|term| (term.len(), *term))
+        {
+            return term.clone();
+        }
+
+        if let Some(term) = self
+            .vocab
+            .as_deref()
+            .and_then(|vocab| iri.strip_prefix(vocab))
+            .filter(|term| !term.is_empty() && !term.contains(':'))
+            .filter(|term| !self.terms.contains_key(*term))
+        {
+            return term.to_string();
+        }
+
+        self.terms
+            .iter()
+            .filter_map(|(prefix, ns)| {
+                iri.strip_prefix(ns.as_str())
+                    .filter(|suffix| !suffix.is_empty())
+                    .map(|suffix| format!("{}:{}", prefix, suffix))
+            })
+            .min_by(|a, b| (a.len(), a).cmp(&(b.len(), b)))
+            .unwrap_or_else(|| iri.to_string())
+    }
+}
+
+/// Expands the document, `context` being applied before the document's own context
+pub fn expand(document: &Value, context: &Context) -> Result<Value, JsonLdError> {
+    Ok(match expand_element(document, context)? {
+        None => Value::Array(vec![]),
+        Some(Value::Array(nodes)) => Value::Array(nodes),
+        Some(node) => Value::Array(vec![node]),
+    })
+}
+
+/// Compacts an expanded document, the output carries `context` as `@context`
+pub fn compact(expanded: &Value, context: &Value) -> Result<Value, JsonLdError> {
+    let active = Context::parse(context)?;
+
+    Ok(match compact_element(expanded, &active) {
+        Value::Object(mut node) => {
+            node.insert("@context".to_string(), context.clone());
+            Value::Object(node)
+        }
+        Value::Array(nodes) if nodes.is_empty() => Value::Object(Map::new()),
+        other => other,
+    })
+}
+
+/// Expands then compacts the document against `context`, the incoming messages may be
+/// expanded or use other prefixes than the ones the signaling models are written with
+pub fn normalize(document: &Value, context: &Value) -> Result<Value, JsonLdError> {
+    compact(&expand(document, &Context::parse(context)?)?, context)
+}
+
+fn expand_element(element: &Value, context: &Context) -> Result<Option<Value>, JsonLdError> {
+    match element {
+        Value::Null => Ok(None),
+        Value::Array(items) => {
+            let mut expanded = vec![];
+            for item in items {
+                match expand_element(item, context)? {
+                    Some(Value::Array(nested)) => expanded.extend(nested),
+                    Some(value) => expanded.push(value),
+                    None => {}
+                }
+            }
+            Ok(Some(Value::Array(expanded)))
+        }
+        Value::Object(object) => {
+            let context = match object.get("@context") {
+                Some(local) => context.merge(local)?,
+                None => context.clone(),
+            };
+
+            if object.contains_key("@value") {
+                let mut value = object.clone();
+                value.remove("@context");
+                return Ok(Some(Value::Object(value)));
+            }
+
+            let mut node = Map::new();
+            for (key, value) in object.iter().filter(|(key, _)| *key != "@context") {
+                let Some(iri) = context.expand_iri(key, true) else {
+                    continue;
+                };
+
+                match iri.as_str() {
+                    "@id" => {
+                        node.insert(iri, value.clone());
+                    }
+                    "@type" => {
+                        node.insert(iri, expand_types(value, &context)?);
+                    }
+                    _ => {
+                        let expanded = match expand_element(value, &context)? {
+                            None => continue,
+                            Some(Value::Array(values)) => values,
+                            Some(value) => vec![value],
+                        };
+                        node.insert(iri, Value::Array(expanded));
+                    }
+                }
+            }
+            Ok(Some(Value::Object(node)))
+        }
+        scalar => Ok(Some(Value::Object(Map::from_iter([(
+            "@value".to_string(),
+            scalar.clone(),
+        )])))),
+    }
+}
+
+fn expand_types(value: &Value, context: &Context) -> Result<Value, JsonLdError> {
+    let invalid = || JsonLdError::InvalidKeywordValue {
+        keyword: "@type".to_string(),
+    };
+
+    let types = match value {
+        Value::String(kind) => vec![kind.as_str()],
+        Value::Array(kinds) => kinds
+            .iter()
+            .map(|kind| kind.as_str().ok_or_else(invalid))
+            .collect::<Result<Vec<_>, _>>()?,
+        _ => return Err(invalid()),
+    };
+
+    Ok(Value::Array(
+        types
+            .into_iter()
+            .map(|kind| {
+                Value::String(
+                    context
+                        .expand_iri(kind, true)
+                        .unwrap_or_else(|| kind.to_string()),
+                )
+            })
+            .collect(),
+    ))
+}
+
+fn compact_element(element: &Value, context: &Context) -> Value {
+    match element {
+        Value::Array(items) if items.len() == 1 => compact_element(&items[0], context),
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| compact_element(item, context))
+                .collect(),
+        ),
+        Value::Object(object) if object.contains_key("@value") => object["@value"].clone(),
+        Value::Object(object) => Value::Object(
+            object
+                .iter()
+                .map(|(key, value)| {
+                    let value = if key == "@type" {
+                        compact_types(value, context)
+                    } else {
+                        compact_element(value, context)
+                    };
+                    (context.compact_iri(key), value)
+                })
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+fn compact_types(value: &Value, context: &Context) -> Value {
+    let compacted = value
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .map(|kind| Value::String(context.compact_iri(kind)))
+        .collect::<Vec<_>>();
+
+    match <[Value; 1]>::try_from(compacted) {
+        Ok([kind]) => kind,
+        Err(kinds) => Value::Array(kinds),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::signaling::DataFlowStartMessage;
+
+    use super::{normalize, Context, JsonLdError};
+
+    fn context() -> serde_json::Value {
+        json!({
+            "@vocab": "https://w3id.org/edc/v0.0.1/ns/",
+            "edc": "https://w3id.org/edc/v0.0.1/ns/",
+            "dspace": "https://w3id.org/dspace/v0.8/"
+        })
+    }
+
+    fn compacted() -> serde_json::Value {
+        json!({
+            "@context": context(),
+            "@type": "DataFlowStartMessage",
+            "processId": "process_id",
+            "participantId": "participant_id",
+            "agreementId": "agreement_id",
+            "datasetId": "dataset_id",
+            "flowType": "PULL",
+            "properties": {},
+            "sourceDataAddress": {
+                "@type": "dspace:DataAddress",
+                "dspace:endpointType": "HttpData",
+                "dspace:endpointProperties": [
+                    {
+                        "dspace:name": "https://w3id.org/edc/v0.0.1/ns/baseUrl",
+                        "dspace:value": "http://localhost:8080"
+                    },
+                    {
+                        "dspace:name": "https://w3id.org/edc/v0.0.1/ns/proxyPath",
+                        "dspace:value": "true"
+                    }
+                ]
+            }
+        })
+    }
+
+    #[test]
+    fn normalize_keeps_compacted_messages() {
+        assert_eq!(normalize(&compacted(), &context()).unwrap(), compacted());
+    }
+
+    #[test]
+    fn normalize_without_context() {
+        let mut message = compacted();
+        message.as_object_mut().unwrap().remove("@context");
+
+        assert_eq!(normalize(&message, &context()).unwrap(), compacted());
+    }
+
+    #[test]
+    fn normalize_expanded_messages() {
+        let expanded = json!([{
+            "@type": ["https://w3id.org/edc/v0.0.1/ns/DataFlowStartMessage"],
+            "https://w3id.org/edc/v0.0.1/ns/processId": [{ "@value": "process_id" }],
+            "https://w3id.org/edc/v0.0.1/ns/participantId": [{ "@value": "participant_id" }],
+            "https://w3id.org/edc/v0.0.1/ns/agreementId": [{ "@value": "agreement_id" }],
+            "https://w3id.org/edc/v0.0.1/ns/datasetId": [{ "@value": "dataset_id" }],
+            "https://w3id.org/edc/v0.0.1/ns/flowType": [{ "@value": "PULL" }],
+            "https://w3id.org/edc/v0.0.1/ns/properties": [{}],
+            "https://w3id.org/edc/v0.0.1/ns/sourceDataAddress": [{
+                "@type": ["https://w3id.org/dspace/v0.8/DataAddress"],
+                "https://w3id.org/dspace/v0.8/endpointType": [{ "@value": "HttpData" }],
+                "https://w3id.org/dspace/v0.8/endpointProperties": [
+                    {
+                        "https://w3id.org/dspace/v0.8/name": [{ "@value": "https://w3id.org/edc/v0.0.1/ns/baseUrl" }],
+                        "https://w3id.org/dspace/v0.8/value": [{ "@value": "http://localhost:8080" }]
+                    },
+                    {
+                        "https://w3id.org/dspace/v0.8/name": [{ "@value": "https://w3id.org/edc/v0.0.1/ns/proxyPath" }],
+                        "https://w3id.org/dspace/v0.8/value": [{ "@value": "true" }]
+                    }
+                ]
+            }]
+        }]);
+
+        assert_eq!(normalize(&expanded, &context()).unwrap(), compacted());
+    }
+
+    #[test]
+    fn normalize_other_prefixes() {
+        let message = json!({
+            "@context": {
+                "ns": "https://w3id.org/edc/v0.0.1/ns/",
+                "ds": "https://w3id.org/dspace/v0.8/"
+            },
+            "@type": "ns:DataFlowStartMessage",
+            "ns:processId": "process_id",
+            "ns:participantId": "participant_id",
+            "ns:agreementId": "agreement_id",
+            "ns:datasetId": "dataset_id",
+            "ns:flowType": "PULL",
+            "ns:properties": {},
+            "ns:sourceDataAddress": {
+                "@type": "ds:DataAddress",
+                "ds:endpointType": "HttpData",
+                "ds:endpointProperties": [
+                    {
+                        "ds:name": "https://w3id.org/edc/v0.0.1/ns/baseUrl",
+                        "ds:value": "http://localhost:8080"
+                    },
+                    {
+                        "ds:name": "https://w3id.org/edc/v0.0.1/ns/proxyPath",
+                        "ds:value": "true"
+                    }
+                ]
+            }
+        });
+
+        let normalized = normalize(&message, &context()).unwrap();
+        assert_eq!(normalized, compacted());
+
+        let message: DataFlowStartMessage = serde_json::from_value(normalized).unwrap();
+        assert_eq!(message.process_id, "process_id");
+        assert_eq!(
+            message.source_data_address.get_property("baseUrl"),
+            Some("http://localhost:8080")
+        );
+    }
+
+    #[test]
+    fn normalize_single_endpoint_property() {
+        let mut message = compacted();
+        message["sourceDataAddress"]["dspace:endpointProperties"]
+            .as_array_mut()
+            .unwrap()
+            .truncate(1);
+
+        let message: DataFlowStartMessage =
+            serde_json::from_value(normalize(&message, &context()).unwrap()).unwrap();
+
+        assert_eq!(message.source_data_address.endpoint_properties.len(), 1);
+    }
+
+    #[test]
+    fn drops_null_values() {
+        let message = json!({ "reason": null });
+
+        assert_eq!(
+            normalize(&message, &context()).unwrap(),
+            json!({ "@context": context() })
+        );
+    }
+
+    #[test]
+    fn rejects_remote_contexts() {
+        let message = json!({
+            "@context": "https://w3id.org/dspace/2024/1/context.json",
+            "reason": "reason"
+        });
+
+        assert_eq!(
+            normalize(&message, &context()),
+            Err(JsonLdError::RemoteContext(
+                "https://w3id.org/dspace/2024/1/context.json".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn compacts_terms_sharing_an_iri_the_same_way() {
+        let context = Context::parse(&json!({
+            "zeta": "https://example.com/ns/type",
+            "alpha": "https://example.com/ns/type",
+            "typ": "https://example.com/ns/type",
+            "ex": "https://example.com/ns/",
+            "ab": "https://example.com/ns/",
+            "other": "https://example.com/other/"
+        }))
+        .unwrap();
+
+        for _ in 0..10 {
+            assert_eq!(context.compact_iri("https://example.com/ns/type"), "typ");
+            assert_eq!(
+                context.compact_iri("https://example.com/ns/name"),
+                "ab:name"
+            );
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use crate::store::Tester;
+use chrono::{Duration, SubsecRound, Utc};
+use edc_dataplane_core::core::{
+    db::notification::NotificationRepo,
+    model::notification::{Notification, NotificationKind, NotificationStatus},
+};
+use uuid::Uuid;
+
+mod notification_memory;
+mod notification_postgres;
+mod notification_sqlite;
+
+pub fn create_notification(transfer_id: &str) -> Notification {
+    // Postgres stores timestamps with microseconds precision
+    let now = Utc::now().trunc_subsecs(6);
+
+    Notification::builder()
+        .transfer_id(transfer_id)
+        .callback_address("http://control-plane/callback")
+        .kind(NotificationKind::Terminated)
+        .reason("reason")
+        .next_attempt_at(now)
+        .created_at(now)
+        .updated_at(now)
+        .build()
+}
+
+fn ids(notifications: Vec<Notification>) -> Vec<String> {
+    notifications
+        .into_iter()
+        .map(|notification| notification.id)
+        .collect()
+}
+
+pub async fn save<T: NotificationRepo>(tester: impl Tester<T>) {
+    let store = tester.store();
+
+    let notification = create_notification(&Uuid::new_v4().to_string());
+
+    store.save(notification.clone()).await.unwrap();
+
+    let saved = store.fetch_by_id(&notification.id).await.unwrap().unwrap();
+
+    assert_eq!(saved, notification);
+}
+
+pub async fn update<T: NotificationRepo>(tester: impl Tester<T>) {
+    let store = tester.store();
+
+    let notification = create_notification(&Uuid::new_v4().to_string());
+    let mut updated = notification.clone();
+
+    store.save(notification).await.unwrap();
+
+    updated.attempts = 2;
+    updated.last_error = Some("503 Service Unavailable".to_string());
+    updated.next_attempt_at += Duration::seconds(2);
+    updated.updated_at += Duration::seconds(1);
+
+    store.save(updated.clone()).await.unwrap();
+
+    let saved = store.fetch_by_id(&updated.id).await.unwrap().unwrap();
+
+    assert_eq!(saved, updated);
+}
+
+pub async fn fetch_by_transfer<T: NotificationRepo>(tester: impl Tester<T>) {
+    let store = tester.store();
+
+    let transfer_id = Uuid::new_v4().to_string();
+    let first = create_notification(&transfer_id);
+    let second = create_notification(&transfer_id);
+
+    store.save(first.clone()).await.unwrap();
+    store.save(second.clone()).await.unwrap();
+    store
+        .save(create_notification(&Uuid::new_v4().to_string()))
+        .await
+        .unwrap();
+
+    let mut fetched = ids(store.fetch_by_transfer(&transfer_id).await.unwrap());
+    fetched.sort();
+
+    let mut expected = vec![first.id, second.id];
+    expected.sort();
+
+    assert_eq!(fetched, expected);
+}
+
+pub async fn claim_due<T: NotificationRepo>(tester: impl Tester<T>) {
+    let store = tester.store();
+
+    let now = Utc::now().trunc_subsecs(6);
+    let until = now + Duration::seconds(60);
+
+    let mut late = create_notification("1");
+    late.next_attempt_at = now - Duration::seconds(10);
+    late.created_at = now - Duration::seconds(10);
+
+    let mut due = create_notification("2");
+    due.next_attempt_at = now - Duration::seconds(5);
+    due.created_at = now - Duration::seconds(5);
+
+    let mut scheduled = create_notification("3");
+    scheduled.next_attempt_at = now + Duration::seconds(60);
+
+    let mut delivered = create_notification("4");
+    delivered.next_attempt_at = now - Duration::seconds(20);
+    delivered.status = NotificationStatus::Delivered;
+
+    let mut failed = create_notification("5");
+    failed.next_attempt_at = now - Duration::seconds(20);
+    failed.status = NotificationStatus::Failed;
+
+    for notification in [&due, &scheduled, &late, &delivered, &failed] {
+        store.save(notification.clone()).await.unwrap();
+    }
+
+    let claimed = store.claim_due(now, until, 1).await.unwrap();
+    assert_eq!(ids(claimed.clone()), vec![late.id.clone()]);
+    assert_eq!(claimed[0].next_attempt_at, until);
+
+    let claimed = store.claim_due(now, until, 10).await.unwrap();
+    assert_eq!(ids(claimed), vec![due.id.clone()]);
+
+    let saved = store.fetch_by_id(&due.id).await.unwrap().unwrap();
+    assert_eq!(saved.next_attempt_at, until);
+}
+
+pub async fn claim_due_skips_claimed<T: NotificationRepo>(tester: impl Tester<T>) {
+    let store = tester.store();
+
+    let now = Utc::now().trunc_subsecs(6);
+
+    let mut first = create_notification("1");
+    first.next_attempt_at = now - Duration::seconds(10);
+    first.created_at = now - Duration::seconds(10);
+
+    let mut second = create_notification("2");
+    second.next_attempt_at = now;
+
+    store.save(first.clone()).await.unwrap();
+    store.save(second.clone()).await.unwrap();
+
+    let until = now + Duration::seconds(60);
+    let (left, right) = tokio::join!(
+        store.claim_due(now, until, 10),
+        store.claim_due(now, until, 10)
+    );
+
+    let mut claimed = ids(left.unwrap());
+    claimed.extend(ids(right.unwrap()));
+    claimed.sort();
+
+    let mut expected = vec![first.id, second.id];
+    expected.sort();
+
+    assert_eq!(claimed, expected);
+    assert!(store.claim_due(now, until, 10).await.unwrap().is_empty());
+
+    // Claimed again once the lease expired
+    assert_eq!(store.claim_due(until, until, 10).await.unwrap().len(), 2);
+}
+
+#[macro_export]
+macro_rules! generate_notification_store_tests {
+    ($tester:ident) => {
+        macro_rules! test {
+            ($title: ident, $func: path) => {
+                $crate::declare_test_fn!($tester, $title, $func);
+            };
+        }
+
+        test!(save, $crate::store::notification::save);
+        test!(update, $crate::store::notification::update);
+        test!(
+            fetch_by_transfer,
+            $crate::store::notification::fetch_by_transfer
+        );
+        test!(claim_due, $crate::store::notification::claim_due);
+        test!(
+            claim_due_skips_claimed,
+            $crate::store::notification::claim_due_skips_claimed
+        );
+    };
+}
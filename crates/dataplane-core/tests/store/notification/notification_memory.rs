@@ -0,0 +1,19 @@
+use async_trait::async_trait;
+use edc_dataplane_core::core::db::memory::notification::InMemoryNotificationRepo;
+
+use crate::{generate_notification_store_tests, store::Tester};
+
+pub struct MemoryTester(InMemoryNotificationRepo);
+
+#[async_trait]
+impl Tester<InMemoryNotificationRepo> for MemoryTester {
+    async fn create() -> Self {
+        MemoryTester(InMemoryNotificationRepo::new())
+    }
+
+    fn store(&self) -> &InMemoryNotificationRepo {
+        &self.0
+    }
+}
+
+generate_notification_store_tests!(MemoryTester);
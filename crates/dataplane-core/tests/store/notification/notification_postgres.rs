@@ -0,0 +1,29 @@
+use async_trait::async_trait;
+use edc_dataplane_core::core::db::postgres::{
+    notification::PgNotificationRepo, transfer::PgTransferRepo,
+};
+
+use crate::{
+    generate_notification_store_tests,
+    store::{create_pg_database, Tester},
+};
+
+pub struct PgTester(PgNotificationRepo);
+
+#[async_trait]
+impl Tester<PgNotificationRepo> for PgTester {
+    async fn create() -> Self {
+        let transfers = PgTransferRepo::connect(&create_pg_database().await)
+            .await
+            .unwrap();
+
+        transfers.migrate().await.unwrap();
+        PgTester(PgNotificationRepo::new(transfers.pool().clone()))
+    }
+
+    fn store(&self) -> &PgNotificationRepo {
+        &self.0
+    }
+}
+
+generate_notification_store_tests!(PgTester);
@@ -0,0 +1,26 @@
+use async_trait::async_trait;
+use edc_dataplane_core::core::db::sqlite::{
+    notification::SqliteNotificationRepo, transfer::SqliteTransferRepo,
+};
+
+use crate::{generate_notification_store_tests, store::Tester};
+
+pub struct SqliteTester(SqliteNotificationRepo);
+
+#[async_trait]
+impl Tester<SqliteNotificationRepo> for SqliteTester {
+    async fn create() -> Self {
+        let transfers = SqliteTransferRepo::connect("sqlite::memory:")
+            .await
+            .unwrap();
+
+        transfers.migrate().await.unwrap();
+        SqliteTester(SqliteNotificationRepo::new(transfers.pool().clone()))
+    }
+
+    fn store(&self) -> &SqliteNotificationRepo {
+        &self.0
+    }
+}
+
+generate_notification_store_tests!(SqliteTester);
@@ -1,15 +1,26 @@
-use crate::store::Tester;
-use edc_dataplane_core::core::db::transfer::TransferQuery;
+use crate::store::{OutboxTester, Tester};
+use chrono::{Duration, SubsecRound, Utc};
+use edc_dataplane_core::core::db::notification::NotificationRepo;
 use edc_dataplane_core::core::db::transfer::TransferRepo;
+use edc_dataplane_core::core::db::transfer::{SortOrder, TransferQuery, TransferSortField};
+use edc_dataplane_core::core::model::notification::{Notification, NotificationKind};
 use edc_dataplane_core::{
     core::model::transfer::{Transfer, TransferStatus},
-    signaling::DataAddress,
+    signaling::{DataAddress, FlowType},
 };
+use serde_json::json;
+use sqlx::types::Json;
+use std::collections::HashMap;
 use uuid::Uuid;
 
+mod transfer_memory;
+mod transfer_postgres;
 mod transfer_sqlite;
 
 pub fn create_transfer(id: &str) -> Transfer {
+    // Postgres stores timestamps with microseconds precision
+    let now = Utc::now().trunc_subsecs(6);
+
     Transfer::builder()
         .id(id.to_string())
         .source(
@@ -20,9 +31,22 @@ pub fn create_transfer(id: &str) -> Transfer {
         )
         .participant_id("participant_id".to_string())
         .status(TransferStatus::Started)
+        .transfer_type("HttpData-PULL")
+        .agreement_id("agreement_id")
+        .dataset_id("dataset_id")
+        .properties(Json(HashMap::from([(
+            "https://w3id.org/edc/v0.0.1/ns/key".to_string(),
+            json!({"nested": ["value", 1]}),
+        )])))
+        .created_at(now)
+        .updated_at(now)
         .build()
 }
 
+fn ids(transfers: Vec<Transfer>) -> Vec<String> {
+    transfers.into_iter().map(|transfer| transfer.id).collect()
+}
+
 pub async fn save<T: TransferRepo>(tester: impl Tester<T>) {
     let store = tester.store();
 
@@ -37,6 +61,89 @@ pub async fn save<T: TransferRepo>(tester: impl Tester<T>) {
     assert_eq!(saved, transfer);
 }
 
+pub async fn create<T: TransferRepo>(tester: impl Tester<T>) {
+    let store = tester.store();
+
+    let id = Uuid::new_v4().to_string();
+
+    let mut transfer = create_transfer(&id);
+    transfer.status = TransferStatus::Received;
+
+    assert!(store.create(transfer.clone()).await.unwrap());
+
+    let mut other = create_transfer(&id);
+    other.participant_id = "other_participant".to_string();
+
+    assert!(!store.create(other).await.unwrap());
+    assert_eq!(store.fetch_by_id(&id).await.unwrap().unwrap(), transfer);
+}
+
+pub async fn save_push<T: TransferRepo>(tester: impl Tester<T>) {
+    let store = tester.store();
+
+    let id = Uuid::new_v4().to_string();
+
+    let mut transfer = create_transfer(&id);
+    transfer.flow_type = FlowType::Push;
+    transfer.destination = Some(
+        DataAddress::builder()
+            .endpoint_type("destination".to_string())
+            .endpoint_properties(vec![])
+            .build()
+            .into(),
+    );
+
+    store.save(transfer.clone()).await.unwrap();
+
+    let saved = store.fetch_by_id(&id).await.unwrap().unwrap();
+
+    assert_eq!(saved, transfer);
+}
+
+pub async fn save_callback_address<T: TransferRepo>(tester: impl Tester<T>) {
+    let store = tester.store();
+
+    let id = Uuid::new_v4().to_string();
+
+    let mut transfer = create_transfer(&id);
+    transfer.callback_address = Some("http://control-plane/callback".to_string());
+
+    store.save(transfer.clone()).await.unwrap();
+
+    let saved = store.fetch_by_id(&id).await.unwrap().unwrap();
+
+    assert_eq!(saved, transfer);
+}
+
+pub async fn update_consumer_source<T: TransferRepo>(tester: impl Tester<T>) {
+    let store = tester.store();
+
+    let id = Uuid::new_v4().to_string();
+
+    let mut transfer = create_transfer(&id);
+    transfer.status = TransferStatus::Prepared;
+    transfer.source = None;
+    transfer.consumer = true;
+
+    store.save(transfer.clone()).await.unwrap();
+
+    assert_eq!(store.fetch_by_id(&id).await.unwrap().unwrap(), transfer);
+
+    transfer.status = TransferStatus::Started;
+    transfer.source = Some(
+        DataAddress::builder()
+            .endpoint_type("provider".to_string())
+            .endpoint_properties(vec![])
+            .build()
+            .into(),
+    );
+    transfer.updated_at += Duration::seconds(1);
+
+    store.save(transfer.clone()).await.unwrap();
+
+    assert_eq!(store.fetch_by_id(&id).await.unwrap().unwrap(), transfer);
+}
+
 pub async fn update<T: TransferRepo>(tester: impl Tester<T>) {
     let store = tester.store();
 
@@ -77,6 +184,59 @@ pub async fn delete<T: TransferRepo>(tester: impl Tester<T>) {
     assert_eq!(transfers.len(), 1);
 }
 
+pub async fn delete_notifications<T: TransferRepo>(tester: impl Tester<T> + OutboxTester) {
+    let store = tester.store();
+
+    let transfer = create_transfer("1");
+    store.save(transfer.clone()).await.unwrap();
+
+    let mut terminated = transfer.clone();
+    terminated.status = TransferStatus::Terminated;
+
+    let notification = Notification::builder()
+        .transfer_id("1")
+        .callback_address("http://control-plane/callback")
+        .kind(NotificationKind::Terminated)
+        .build();
+
+    assert!(store
+        .transition(terminated, TransferStatus::Started, Some(notification))
+        .await
+        .unwrap());
+
+    store.delete("1").await.unwrap();
+
+    assert!(store.fetch_by_id("1").await.unwrap().is_none());
+    assert!(tester
+        .outbox()
+        .fetch_by_transfer("1")
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+pub async fn update_lifecycle<T: TransferRepo>(tester: impl Tester<T>) {
+    let store = tester.store();
+
+    let transfer = create_transfer("1");
+    let mut updated = transfer.clone();
+    let now = Utc::now().trunc_subsecs(6);
+
+    updated.status = TransferStatus::Terminated;
+    updated.updated_at = now;
+    updated.suspension_reason = Some("suspended".to_string());
+    updated.suspended_at = Some(now);
+    updated.termination_reason = Some("terminated".to_string());
+    updated.terminated_at = Some(now);
+
+    store.save(transfer.clone()).await.unwrap();
+    store.save(updated.clone()).await.unwrap();
+
+    let saved = store.fetch_by_id("1").await.unwrap().unwrap();
+
+    assert_eq!(saved, updated);
+}
+
 pub async fn change_status<T: TransferRepo>(tester: impl Tester<T>) {
     let store = tester.store();
 
@@ -101,18 +261,337 @@ pub async fn change_status<T: TransferRepo>(tester: impl Tester<T>) {
     assert_eq!(transfers[0], updated);
 }
 
+pub async fn change_status_rejects_illegal_transition<T: TransferRepo>(tester: impl Tester<T>) {
+    let store = tester.store();
+
+    let mut transfer = create_transfer("1");
+    transfer.status = TransferStatus::Terminated;
+
+    store.save(transfer.clone()).await.unwrap();
+
+    let err = store
+        .change_status(transfer.id.clone(), TransferStatus::Started)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Transfer 1 cannot transition from Terminated to Started"
+    );
+
+    assert!(store
+        .change_status(transfer.id.clone(), TransferStatus::Prepared)
+        .await
+        .is_err());
+
+    assert_eq!(store.fetch_by_id("1").await.unwrap().unwrap(), transfer);
+}
+
+pub async fn transition<T: TransferRepo>(tester: impl Tester<T> + OutboxTester) {
+    let store = tester.store();
+
+    let transfer = create_transfer("1");
+    store.save(transfer.clone()).await.unwrap();
+
+    let mut terminated = transfer.clone();
+    terminated.status = TransferStatus::Terminated;
+    terminated.termination_reason = Some("reason".to_string());
+    terminated.terminated_at = Some(transfer.updated_at);
+
+    let notification = Notification::builder()
+        .transfer_id("1")
+        .callback_address("http://control-plane/callback")
+        .kind(NotificationKind::Terminated)
+        .reason("reason")
+        .next_attempt_at(transfer.updated_at)
+        .created_at(transfer.updated_at)
+        .updated_at(transfer.updated_at)
+        .build();
+
+    assert!(store
+        .transition(
+            terminated.clone(),
+            TransferStatus::Started,
+            Some(notification.clone())
+        )
+        .await
+        .unwrap());
+
+    assert_eq!(store.fetch_by_id("1").await.unwrap().unwrap(), terminated);
+    assert_eq!(
+        tester.outbox().fetch_by_transfer("1").await.unwrap(),
+        vec![notification]
+    );
+}
+
+pub async fn transition_skips_changed_transfer<T: TransferRepo>(
+    tester: impl Tester<T> + OutboxTester,
+) {
+    let store = tester.store();
+
+    let mut transfer = create_transfer("1");
+    transfer.status = TransferStatus::Suspended;
+    store.save(transfer.clone()).await.unwrap();
+
+    let mut terminated = transfer.clone();
+    terminated.status = TransferStatus::Terminated;
+
+    let notification = Notification::builder()
+        .transfer_id("1")
+        .callback_address("http://control-plane/callback")
+        .kind(NotificationKind::Terminated)
+        .build();
+
+    assert!(!store
+        .transition(terminated, TransferStatus::Started, Some(notification))
+        .await
+        .unwrap());
+
+    assert_eq!(store.fetch_by_id("1").await.unwrap().unwrap(), transfer);
+    assert!(tester
+        .outbox()
+        .fetch_by_transfer("1")
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+pub async fn query_filters<T: TransferRepo>(tester: impl Tester<T>) {
+    let store = tester.store();
+
+    let now = Utc::now().trunc_subsecs(6);
+
+    let mut first = create_transfer("1");
+    first.created_at = now - Duration::hours(2);
+    first.updated_at = now - Duration::hours(2);
+
+    let mut second = create_transfer("2");
+    second.participant_id = "other".to_string();
+    second.created_at = now - Duration::hours(1);
+    second.updated_at = now;
+
+    let mut third = create_transfer("3");
+    third.status = TransferStatus::Suspended;
+    third.created_at = now;
+    third.updated_at = now;
+
+    for transfer in [&first, &second, &third] {
+        store.save(transfer.clone()).await.unwrap();
+    }
+
+    let by_participant = store
+        .query(TransferQuery::builder().participant_id("other").build())
+        .await
+        .unwrap();
+    assert_eq!(ids(by_participant), vec!["2"]);
+
+    let by_status = store
+        .query(
+            TransferQuery::builder()
+                .status(TransferStatus::Started)
+                .build(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(ids(by_status), vec!["1", "2"]);
+
+    let by_created = store
+        .query(
+            TransferQuery::builder()
+                .created_after(now - Duration::minutes(90))
+                .created_before(now)
+                .build(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(ids(by_created), vec!["2"]);
+
+    let by_updated = store
+        .query(
+            TransferQuery::builder()
+                .updated_after(now - Duration::minutes(30))
+                .build(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(ids(by_updated), vec!["2", "3"]);
+
+    let by_ids = store
+        .query(
+            TransferQuery::builder()
+                .ids(vec!["1".to_string(), "3".to_string(), "4".to_string()])
+                .build(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(ids(by_ids), vec!["1", "3"]);
+
+    let by_no_ids = store
+        .query(TransferQuery::builder().ids(vec![]).build())
+        .await
+        .unwrap();
+    assert!(by_no_ids.is_empty());
+}
+
+pub async fn query_agreement_ended<T: TransferRepo>(tester: impl Tester<T>) {
+    let store = tester.store();
+
+    let now = Utc::now().trunc_subsecs(6);
+
+    let mut ended = create_transfer("1");
+    ended.agreement_end = Some(now - Duration::minutes(1));
+
+    let mut running = create_transfer("2");
+    running.agreement_end = Some(now + Duration::days(1));
+
+    let unbounded = create_transfer("3");
+
+    for transfer in [&ended, &running, &unbounded] {
+        store.save(transfer.clone()).await.unwrap();
+    }
+
+    assert_eq!(store.fetch_by_id("1").await.unwrap().unwrap(), ended);
+
+    let by_agreement = store
+        .query(TransferQuery::builder().agreement_ended_before(now).build())
+        .await
+        .unwrap();
+    assert_eq!(ids(by_agreement), vec!["1"]);
+}
+
+pub async fn query_agreement<T: TransferRepo>(tester: impl Tester<T>) {
+    let store = tester.store();
+
+    let first = create_transfer("1");
+
+    let mut second = create_transfer("2");
+    second.agreement_id = Some("other".to_string());
+    second.transfer_type = Some("HttpData-PUSH".to_string());
+
+    let mut third = create_transfer("3");
+    third.agreement_id = Some("other".to_string());
+    third.dataset_id = Some("other".to_string());
+
+    let mut legacy = create_transfer("4");
+    legacy.agreement_id = None;
+    legacy.dataset_id = None;
+    legacy.properties = None;
+
+    for transfer in [&first, &second, &third, &legacy] {
+        store.save(transfer.clone()).await.unwrap();
+    }
+
+    assert_eq!(store.fetch_by_id("4").await.unwrap().unwrap(), legacy);
+
+    let by_agreement = store
+        .query(TransferQuery::builder().agreement_id("other").build())
+        .await
+        .unwrap();
+    assert_eq!(ids(by_agreement), vec!["2", "3"]);
+
+    let by_dataset = store
+        .query(TransferQuery::builder().dataset_id("dataset_id").build())
+        .await
+        .unwrap();
+    assert_eq!(ids(by_dataset), vec!["1", "2"]);
+
+    let by_type = store
+        .query(
+            TransferQuery::builder()
+                .agreement_id("other")
+                .transfer_type("HttpData-PULL")
+                .build(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(ids(by_type), vec!["3"]);
+}
+
+pub async fn query_sort_and_paginate<T: TransferRepo>(tester: impl Tester<T>) {
+    let store = tester.store();
+
+    let now = Utc::now().trunc_subsecs(6);
+
+    for i in 0..5 {
+        let mut transfer = create_transfer(&i.to_string());
+        transfer.created_at = now + Duration::seconds(i);
+        transfer.updated_at = now - Duration::seconds(i);
+        store.save(transfer).await.unwrap();
+    }
+
+    let page = |offset: i32, sort_by: TransferSortField, sort_order: SortOrder| {
+        TransferQuery::builder()
+            .limit(2)
+            .offset(offset)
+            .sort_by(sort_by)
+            .sort_order(sort_order)
+            .build()
+    };
+
+    let first = store
+        .query(page(0, TransferSortField::CreatedAt, SortOrder::Desc))
+        .await
+        .unwrap();
+    assert_eq!(ids(first), vec!["4", "3"]);
+
+    let last = store
+        .query(page(4, TransferSortField::CreatedAt, SortOrder::Desc))
+        .await
+        .unwrap();
+    assert_eq!(ids(last), vec!["0"]);
+
+    let by_updated = store
+        .query(page(0, TransferSortField::UpdatedAt, SortOrder::Asc))
+        .await
+        .unwrap();
+    assert_eq!(ids(by_updated), vec!["4", "3"]);
+}
+
 #[macro_export]
 macro_rules! generate_transfer_store_tests {
     ($tester:ident) => {
         macro_rules! test {
             ($title: ident, $func: path) => {
-                crate::declare_test_fn!($tester, $title, $func);
+                $crate::declare_test_fn!($tester, $title, $func);
             };
         }
 
-        test!(save, crate::store::transfer::save);
-        test!(update, crate::store::transfer::update);
-        test!(delete, crate::store::transfer::delete);
-        test!(change_status, crate::store::transfer::change_status);
+        test!(save, $crate::store::transfer::save);
+        test!(create, $crate::store::transfer::create);
+        test!(save_push, $crate::store::transfer::save_push);
+        test!(
+            save_callback_address,
+            $crate::store::transfer::save_callback_address
+        );
+        test!(update, $crate::store::transfer::update);
+        test!(
+            update_consumer_source,
+            $crate::store::transfer::update_consumer_source
+        );
+        test!(update_lifecycle, $crate::store::transfer::update_lifecycle);
+        test!(delete, $crate::store::transfer::delete);
+        test!(
+            delete_notifications,
+            $crate::store::transfer::delete_notifications
+        );
+        test!(change_status, $crate::store::transfer::change_status);
+        test!(
+            change_status_rejects_illegal_transition,
+            $crate::store::transfer::change_status_rejects_illegal_transition
+        );
+        test!(transition, $crate::store::transfer::transition);
+        test!(
+            transition_skips_changed_transfer,
+            $crate::store::transfer::transition_skips_changed_transfer
+        );
+        test!(query_filters, $crate::store::transfer::query_filters);
+        test!(
+            query_agreement_ended,
+            $crate::store::transfer::query_agreement_ended
+        );
+        test!(query_agreement, $crate::store::transfer::query_agreement);
+        test!(
+            query_sort_and_paginate,
+            $crate::store::transfer::query_sort_and_paginate
+        );
     };
 }
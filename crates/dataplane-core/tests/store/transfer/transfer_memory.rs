@@ -0,0 +1,34 @@
+use async_trait::async_trait;
+use edc_dataplane_core::core::db::memory::{
+    notification::InMemoryNotificationRepo, transfer::InMemoryTransferRepo,
+};
+
+use crate::{
+    generate_transfer_store_tests,
+    store::{OutboxTester, Tester},
+};
+
+pub struct MemoryTester(InMemoryTransferRepo, InMemoryNotificationRepo);
+
+#[async_trait]
+impl Tester<InMemoryTransferRepo> for MemoryTester {
+    async fn create() -> Self {
+        let store = InMemoryTransferRepo::new();
+        let outbox = store.notifications();
+        MemoryTester(store, outbox)
+    }
+
+    fn store(&self) -> &InMemoryTransferRepo {
+        &self.0
+    }
+}
+
+impl OutboxTester for MemoryTester {
+    type Outbox = InMemoryNotificationRepo;
+
+    fn outbox(&self) -> &InMemoryNotificationRepo {
+        &self.1
+    }
+}
+
+generate_transfer_store_tests!(MemoryTester);
@@ -0,0 +1,38 @@
+use async_trait::async_trait;
+use edc_dataplane_core::core::db::postgres::{
+    notification::PgNotificationRepo, transfer::PgTransferRepo,
+};
+
+use crate::{
+    generate_transfer_store_tests,
+    store::{create_pg_database, OutboxTester, Tester},
+};
+
+pub struct PgTester(PgTransferRepo, PgNotificationRepo);
+
+#[async_trait]
+impl Tester<PgTransferRepo> for PgTester {
+    async fn create() -> Self {
+        let store = PgTransferRepo::connect(&create_pg_database().await)
+            .await
+            .unwrap();
+
+        store.migrate().await.unwrap();
+        let outbox = PgNotificationRepo::new(store.pool().clone());
+        PgTester(store, outbox)
+    }
+
+    fn store(&self) -> &PgTransferRepo {
+        &self.0
+    }
+}
+
+impl OutboxTester for PgTester {
+    type Outbox = PgNotificationRepo;
+
+    fn outbox(&self) -> &PgNotificationRepo {
+        &self.1
+    }
+}
+
+generate_transfer_store_tests!(PgTester);
@@ -1,9 +1,14 @@
 use async_trait::async_trait;
-use edc_dataplane_core::core::db::sqlite::transfer::SqliteTransferRepo;
+use edc_dataplane_core::core::db::sqlite::{
+    notification::SqliteNotificationRepo, transfer::SqliteTransferRepo,
+};
 
-use crate::{generate_transfer_store_tests, store::Tester};
+use crate::{
+    generate_transfer_store_tests,
+    store::{OutboxTester, Tester},
+};
 
-pub struct SqliteTester(SqliteTransferRepo);
+pub struct SqliteTester(SqliteTransferRepo, SqliteNotificationRepo);
 
 #[async_trait]
 impl Tester<SqliteTransferRepo> for SqliteTester {
@@ -13,7 +18,8 @@ impl Tester<SqliteTransferRepo> for SqliteTester {
             .unwrap();
 
         store.migrate().await.unwrap();
-        SqliteTester(store)
+        let outbox = SqliteNotificationRepo::new(store.pool().clone());
+        SqliteTester(store, outbox)
     }
 
     fn store(&self) -> &SqliteTransferRepo {
@@ -21,4 +27,12 @@ impl Tester<SqliteTransferRepo> for SqliteTester {
     }
 }
 
+impl OutboxTester for SqliteTester {
+    type Outbox = SqliteNotificationRepo;
+
+    fn outbox(&self) -> &SqliteNotificationRepo {
+        &self.1
+    }
+}
+
 generate_transfer_store_tests!(SqliteTester);
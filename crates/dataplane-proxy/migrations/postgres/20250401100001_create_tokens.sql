@@ -0,0 +1,7 @@
+-- Add migration script here
+
+CREATE TABLE IF NOT EXISTS tokens (
+    transfer_id      TEXT PRIMARY KEY,
+    token_id         UUID NOT NULL,
+    refresh_token_id UUID NOT NULL
+)
@@ -0,0 +1,11 @@
+-- Add migration script here
+
+CREATE TABLE IF NOT EXISTS consumer_edrs (
+    transfer_id      TEXT PRIMARY KEY,
+    endpoint         TEXT NOT NULL,
+    access_token     TEXT NOT NULL,
+    refresh_token    TEXT,
+    refresh_endpoint TEXT,
+    expires_at       TIMESTAMPTZ,
+    updated_at       TIMESTAMPTZ NOT NULL
+)
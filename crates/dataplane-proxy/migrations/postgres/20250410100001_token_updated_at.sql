@@ -0,0 +1,2 @@
+-- Entries saved recently are kept by the purge, they may belong to a start in flight
+ALTER TABLE tokens ADD COLUMN updated_at TIMESTAMPTZ NOT NULL DEFAULT now();
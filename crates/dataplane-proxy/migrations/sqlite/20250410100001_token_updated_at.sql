@@ -0,0 +1,2 @@
+-- Entries saved recently are kept by the purge, they may belong to a start in flight
+ALTER TABLE tokens ADD COLUMN updated_at TIMESTAMP NOT NULL DEFAULT '1970-01-01 00:00:00';
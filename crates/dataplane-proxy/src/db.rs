@@ -1,2 +1,5 @@
+pub mod consumer;
 pub mod edr;
+pub mod memory;
+pub mod postgres;
 pub mod sqlite;
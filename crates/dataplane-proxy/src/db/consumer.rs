@@ -0,0 +1,19 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use miwa::derive::interface;
+
+#[cfg(test)]
+use mockall::{automock, predicate::*};
+
+use crate::model::consumer::ConsumerEdr;
+
+#[async_trait]
+#[interface]
+#[cfg_attr(test, automock)]
+pub trait ConsumerEdrRepo {
+    async fn save(&self, edr: ConsumerEdr) -> anyhow::Result<()>;
+    async fn fetch_by_id(&self, transfer_id: &str) -> anyhow::Result<Option<ConsumerEdr>>;
+    async fn delete(&self, transfer_id: &str) -> anyhow::Result<()>;
+    /// Ids of the transfers whose entry was last saved before `updated_before`
+    async fn transfer_ids(&self, updated_before: DateTime<Utc>) -> anyhow::Result<Vec<String>>;
+}
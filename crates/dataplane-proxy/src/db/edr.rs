@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use miwa::derive::interface;
 
 #[cfg(test)]
@@ -13,4 +14,6 @@ pub trait EdrRepo {
     async fn save(&self, edr: EdrEntry) -> anyhow::Result<()>;
     async fn fetch_by_id(&self, transfer_id: &str) -> anyhow::Result<Option<EdrEntry>>;
     async fn delete(&self, transfer_id: &str) -> anyhow::Result<()>;
+    /// Ids of the transfers whose entry was last saved before `updated_before`
+    async fn transfer_ids(&self, updated_before: DateTime<Utc>) -> anyhow::Result<Vec<String>>;
 }
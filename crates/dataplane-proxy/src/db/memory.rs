@@ -0,0 +1,2 @@
+pub mod consumer;
+pub mod edr;
@@ -0,0 +1,44 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+
+use crate::{db::consumer::ConsumerEdrRepo, model::consumer::ConsumerEdr};
+
+/// Keeps the EDRs received from the providers in memory, they are lost on restart
+#[derive(Clone, Default)]
+pub struct InMemoryConsumerEdrRepo {
+    edrs: Arc<DashMap<String, ConsumerEdr>>,
+}
+
+impl InMemoryConsumerEdrRepo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl ConsumerEdrRepo for InMemoryConsumerEdrRepo {
+    async fn save(&self, edr: ConsumerEdr) -> anyhow::Result<()> {
+        self.edrs.insert(edr.transfer_id.clone(), edr);
+        Ok(())
+    }
+
+    async fn fetch_by_id(&self, transfer_id: &str) -> anyhow::Result<Option<ConsumerEdr>> {
+        Ok(self.edrs.get(transfer_id).map(|edr| edr.clone()))
+    }
+
+    async fn delete(&self, transfer_id: &str) -> anyhow::Result<()> {
+        self.edrs.remove(transfer_id);
+        Ok(())
+    }
+
+    async fn transfer_ids(&self, updated_before: DateTime<Utc>) -> anyhow::Result<Vec<String>> {
+        Ok(self
+            .edrs
+            .iter()
+            .filter(|edr| edr.updated_at < updated_before)
+            .map(|edr| edr.key().clone())
+            .collect())
+    }
+}
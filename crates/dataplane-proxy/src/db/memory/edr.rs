@@ -0,0 +1,45 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+
+use crate::{db::edr::EdrRepo, model::edr::EdrEntry};
+
+/// Keeps the EDR entries in memory, they are lost on restart
+#[derive(Clone, Default)]
+pub struct InMemoryEdrRepo {
+    /// The entries with the time they were saved
+    edrs: Arc<DashMap<String, (EdrEntry, DateTime<Utc>)>>,
+}
+
+impl InMemoryEdrRepo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl EdrRepo for InMemoryEdrRepo {
+    async fn save(&self, edr: EdrEntry) -> anyhow::Result<()> {
+        self.edrs.insert(edr.transfer_id.clone(), (edr, Utc::now()));
+        Ok(())
+    }
+
+    async fn fetch_by_id(&self, transfer_id: &str) -> anyhow::Result<Option<EdrEntry>> {
+        Ok(self.edrs.get(transfer_id).map(|edr| edr.0.clone()))
+    }
+
+    async fn delete(&self, transfer_id: &str) -> anyhow::Result<()> {
+        self.edrs.remove(transfer_id);
+        Ok(())
+    }
+
+    async fn transfer_ids(&self, updated_before: DateTime<Utc>) -> anyhow::Result<Vec<String>> {
+        Ok(self
+            .edrs
+            .iter()
+            .filter(|edr| edr.1 < updated_before)
+            .map(|edr| edr.key().clone())
+            .collect())
+    }
+}
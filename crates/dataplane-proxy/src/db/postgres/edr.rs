@@ -0,0 +1,94 @@
+use chrono::{DateTime, Utc};
+use edc_dataplane_core::core::db::postgres::PgPoolConfig;
+use sqlx::PgPool;
+
+use crate::{db::edr::EdrRepo, model::edr::EdrEntry};
+
+#[derive(Clone)]
+pub struct PgEdrRepo {
+    pool: PgPool,
+}
+
+impl PgEdrRepo {
+    pub async fn connect(url: &str) -> anyhow::Result<Self> {
+        Self::connect_with(url, &PgPoolConfig::default()).await
+    }
+
+    pub async fn connect_with(url: &str, cfg: &PgPoolConfig) -> anyhow::Result<Self> {
+        let pool = cfg.connect(url).await?;
+        Ok(Self { pool })
+    }
+
+    pub fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+}
+
+#[async_trait::async_trait]
+impl EdrRepo for PgEdrRepo {
+    async fn save(&self, edr: EdrEntry) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO tokens (transfer_id, token_id, refresh_token_id, updated_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (transfer_id) DO UPDATE
+            SET token_id = EXCLUDED.token_id, refresh_token_id = EXCLUDED.refresh_token_id,
+                updated_at = EXCLUDED.updated_at
+            "#,
+        )
+        .bind(edr.transfer_id)
+        .bind(edr.token_id)
+        .bind(edr.refresh_token_id)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn fetch_by_id(&self, transfer_id: &str) -> anyhow::Result<Option<EdrEntry>> {
+        sqlx::query_as::<_, EdrEntry>(
+            r#"
+            SELECT * FROM tokens where transfer_id = $1
+            "#,
+        )
+        .bind(transfer_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map(Ok)?
+    }
+
+    async fn delete(&self, transfer_id: &str) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            DELETE FROM tokens where transfer_id = $1
+            "#,
+        )
+        .bind(transfer_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn transfer_ids(&self, updated_before: DateTime<Utc>) -> anyhow::Result<Vec<String>> {
+        sqlx::query_scalar(
+            r#"
+            SELECT transfer_id FROM tokens WHERE updated_at < $1
+            "#,
+        )
+        .bind(updated_before)
+        .fetch_all(&self.pool)
+        .await
+        .map(Ok)?
+    }
+}
+
+impl PgEdrRepo {
+    pub async fn migrate(&self) -> anyhow::Result<()> {
+        let mut migrator = sqlx::migrate!("./migrations/postgres");
+        // The transfers store may share the same database and migrations table
+        migrator.set_ignore_missing(true);
+        migrator.run(&self.pool).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+
+use crate::{db::consumer::ConsumerEdrRepo, model::consumer::ConsumerEdr};
+
+/// Shares the pool and the migrations of [`super::edr::SqliteEdrRepo`]
+#[derive(Clone)]
+pub struct SqliteConsumerEdrRepo {
+    pool: SqlitePool,
+}
+
+impl SqliteConsumerEdrRepo {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl ConsumerEdrRepo for SqliteConsumerEdrRepo {
+    async fn save(&self, edr: ConsumerEdr) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO consumer_edrs (transfer_id, endpoint, access_token, refresh_token,
+                refresh_endpoint, expires_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (transfer_id) DO UPDATE SET
+                endpoint = excluded.endpoint,
+                access_token = excluded.access_token,
+                refresh_token = excluded.refresh_token,
+                refresh_endpoint = excluded.refresh_endpoint,
+                expires_at = excluded.expires_at,
+                updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(edr.transfer_id)
+        .bind(edr.endpoint)
+        .bind(edr.access_token)
+        .bind(edr.refresh_token)
+        .bind(edr.refresh_endpoint)
+        .bind(edr.expires_at)
+        .bind(edr.updated_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn fetch_by_id(&self, transfer_id: &str) -> anyhow::Result<Option<ConsumerEdr>> {
+        sqlx::query_as::<_, ConsumerEdr>(
+            r#"
+            SELECT * FROM consumer_edrs where transfer_id = $1
+            "#,
+        )
+        .bind(transfer_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map(Ok)?
+    }
+
+    async fn delete(&self, transfer_id: &str) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            DELETE FROM consumer_edrs where transfer_id = $1
+            "#,
+        )
+        .bind(transfer_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn transfer_ids(&self, updated_before: DateTime<Utc>) -> anyhow::Result<Vec<String>> {
+        sqlx::query_scalar(
+            r#"
+            SELECT transfer_id FROM consumer_edrs WHERE updated_at < $1
+            "#,
+        )
+        .bind(updated_before)
+        .fetch_all(&self.pool)
+        .await
+        .map(Ok)?
+    }
+}
@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use sqlx::SqlitePool;
 
 use crate::{db::edr::EdrRepo, model::edr::EdrEntry};
@@ -12,6 +13,10 @@ impl SqliteEdrRepo {
         let pool = SqlitePool::connect(url).await?;
         Ok(Self { pool })
     }
+
+    pub fn pool(&self) -> &SqlitePool {
+        &self.pool
+    }
 }
 
 #[async_trait::async_trait]
@@ -49,19 +54,32 @@ impl EdrRepo for SqliteEdrRepo {
 
         Ok(())
     }
+
+    async fn transfer_ids(&self, updated_before: DateTime<Utc>) -> anyhow::Result<Vec<String>> {
+        sqlx::query_scalar(
+            r#"
+            SELECT transfer_id FROM tokens WHERE updated_at < $1
+            "#,
+        )
+        .bind(updated_before)
+        .fetch_all(&self.pool)
+        .await
+        .map(Ok)?
+    }
 }
 
 impl SqliteEdrRepo {
     async fn internal_save(&self, edr: EdrEntry) -> anyhow::Result<()> {
         sqlx::query(
             r#"
-            INSERT INTO tokens (transfer_id, token_id, refresh_token_id)
-            VALUES ($1, $2, $3)
+            INSERT INTO tokens (transfer_id, token_id, refresh_token_id, updated_at)
+            VALUES ($1, $2, $3, $4)
             "#,
         )
         .bind(edr.transfer_id)
         .bind(edr.token_id)
         .bind(edr.refresh_token_id)
+        .bind(Utc::now())
         .execute(&self.pool)
         .await?;
         Ok(())
@@ -70,12 +88,13 @@ impl SqliteEdrRepo {
     async fn internal_update(&self, edr: EdrEntry) -> anyhow::Result<()> {
         sqlx::query(
             r#"
-            UPDATE tokens SET token_id=$1, refresh_token_id=$2
-            WHERE transfer_id = $3
+            UPDATE tokens SET token_id=$1, refresh_token_id=$2, updated_at=$3
+            WHERE transfer_id = $4
             "#,
         )
         .bind(edr.token_id)
         .bind(edr.refresh_token_id)
+        .bind(Utc::now())
         .bind(edr.transfer_id)
         .execute(&self.pool)
         .await?;
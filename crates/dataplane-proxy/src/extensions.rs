@@ -3,7 +3,7 @@ pub mod manager;
 pub mod repo;
 pub mod web;
 
-pub use config::{KeyFormat, Proxy};
+pub use config::{ConsumerApiKey, ConsumerProxy, KeyFormat, Proxy};
 pub use manager::transfer_proxy_extension;
-pub use repo::sqlite::proxy_sql_repo_extension;
+pub use repo::sql::proxy_sql_repo_extension;
 pub use web::proxy_api_extension;
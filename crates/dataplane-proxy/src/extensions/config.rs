@@ -24,6 +24,11 @@ pub struct Proxy {
     pub token_leeway: u64,
     #[serde(default = "default_renewal")]
     pub renewal: TokenRenewal,
+    /// Seconds during which a saved EDR is kept by the purge, even without a live transfer
+    #[serde(default = "default_purge_grace")]
+    pub purge_grace: u64,
+    /// Enables the consumer proxy, which holds the EDRs received from providers
+    pub consumer: Option<ConsumerProxy>,
 }
 
 #[derive(Deserialize, Clone)]
@@ -48,6 +53,26 @@ pub struct TokenRenewal {
     pub bind: IpAddr,
 }
 
+#[derive(Deserialize, Clone)]
+pub struct ConsumerProxy {
+    #[serde(default = "default_consumer_port")]
+    pub port: u16,
+    #[serde(default = "default_consumer_bind")]
+    pub bind: IpAddr,
+    /// Credential required on every request to the consumer proxy
+    pub api_key: ConsumerApiKey,
+    /// Seconds before the expiration at which the access token is refreshed
+    #[serde(default = "default_refresh_leeway")]
+    pub refresh_leeway: u64,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct ConsumerApiKey {
+    #[serde(default = "default_api_key_header")]
+    pub header: String,
+    pub key: SecretString,
+}
+
 pub fn default_renewal() -> TokenRenewal {
     TokenRenewal {
         port: default_renewal_port(),
@@ -59,6 +84,22 @@ pub fn default_renewal_port() -> u16 {
     8788
 }
 
+pub fn default_consumer_port() -> u16 {
+    8790
+}
+
+pub fn default_consumer_bind() -> IpAddr {
+    IpAddr::V4(Ipv4Addr::LOCALHOST)
+}
+
+pub fn default_api_key_header() -> String {
+    "x-api-key".to_string()
+}
+
+pub fn default_refresh_leeway() -> u64 {
+    30
+}
+
 pub fn default_proxy_port() -> u16 {
     8789
 }
@@ -70,6 +111,10 @@ pub fn default_refresh_token_duration() -> u64 {
     60 * 60 * 24 * 30
 }
 
+pub fn default_purge_grace() -> u64 {
+    60 * 5
+}
+
 pub fn default_token_leeway() -> u64 {
     60
 }
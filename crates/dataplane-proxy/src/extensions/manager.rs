@@ -1,5 +1,8 @@
 use chrono::Duration;
-use edc_dataplane_core::core::service::transfer::TransferManagerRef;
+use edc_dataplane_core::core::service::{
+    registry::{EndpointTypes, TransferManagers},
+    transfer::TransferManagerRef,
+};
 use jsonwebtoken::Algorithm;
 use miwa::core::ExtensionConfig;
 use miwa::{
@@ -8,8 +11,8 @@ use miwa::{
 };
 use std::str::FromStr;
 
-use crate::db::edr::EdrRepoRef;
-use crate::service::edr::EdrManager;
+use crate::db::{consumer::ConsumerEdrRepoRef, edr::EdrRepoRef};
+use crate::service::{consumer::ConsumerEdrManager, edr::EdrManager};
 use crate::{manager::TransferProxyManager, service::token::TokenManagerImpl};
 
 use super::config::Proxy;
@@ -27,25 +30,57 @@ impl Extension for TransferManagerExtension {
     }
 }
 
-#[extension(name = "Transfer Pull manager extension", provides(TransferManagerRef))]
+/// Transfer types handled by the pull proxy
+pub const TRANSFER_TYPES: [&str; 1] = ["HttpData-PULL"];
+
+#[extension(name = "Transfer Pull manager extension")]
 pub async fn transfer_proxy_extension(
-    ctx: &MiwaContext,
+    _ctx: &MiwaContext,
     ExtensionConfig(cfg): ExtensionConfig<Proxy>,
     edrs: EdrRepoRef,
+    consumer_edrs: ConsumerEdrRepoRef,
+    managers: TransferManagers,
+    endpoint_types: EndpointTypes,
 ) -> MiwaResult<TransferManagerExtension> {
-    ctx.register(TransferManagerRef::of(manager_from_config(cfg, edrs)?));
+    let manager =
+        manager_from_config(cfg, edrs, consumer_edrs)?.with_endpoint_types(endpoint_types);
+    managers.register(TRANSFER_TYPES, TransferManagerRef::of(manager))?;
     Ok(TransferManagerExtension)
 }
 
 pub fn manager_from_config(
     proxy: Proxy,
     edrs: EdrRepoRef,
+    consumer_edrs: ConsumerEdrRepoRef,
 ) -> anyhow::Result<TransferProxyManager<TokenManagerImpl>> {
     let token_manager = create_token_manager(proxy.clone())?;
 
+    let consumer = create_consumer_edr_manager(consumer_edrs, &proxy);
+
+    let purge_grace = Duration::seconds(proxy.purge_grace as i64);
+
     let edr_manager = create_edr_manager(edrs.clone(), token_manager, proxy)?;
 
-    Ok(TransferProxyManager::new(edr_manager, edrs))
+    let manager = TransferProxyManager::new(edr_manager, edrs).with_purge_grace(purge_grace);
+
+    Ok(match consumer {
+        Some(consumer) => manager.with_consumer(consumer),
+        None => manager,
+    })
+}
+
+/// Only created when the consumer proxy is enabled
+pub fn create_consumer_edr_manager(
+    consumer_edrs: ConsumerEdrRepoRef,
+    proxy: &Proxy,
+) -> Option<ConsumerEdrManager> {
+    proxy.consumer.as_ref().map(|consumer| {
+        ConsumerEdrManager::builder()
+            .store(consumer_edrs)
+            .client_id(proxy.issuer.clone())
+            .leeway(Duration::seconds(consumer.refresh_leeway as i64))
+            .build()
+    })
 }
 
 pub fn create_token_manager(proxy: Proxy) -> anyhow::Result<TokenManagerImpl> {
@@ -1 +1 @@
-pub mod sqlite;
+pub mod sql;
@@ -0,0 +1,84 @@
+use edc_dataplane_core::core::db::postgres::PgPoolConfig;
+use miwa::{
+    core::{Extension, ExtensionConfig, MiwaContext, MiwaResult},
+    derive::{extension, ExtensionConfig},
+};
+use serde::Deserialize;
+
+use crate::db::{
+    consumer::ConsumerEdrRepoRef,
+    edr::EdrRepoRef,
+    memory::{consumer::InMemoryConsumerEdrRepo, edr::InMemoryEdrRepo},
+    postgres::{consumer::PgConsumerEdrRepo, edr::PgEdrRepo},
+    sqlite::{consumer::SqliteConsumerEdrRepo, edr::SqliteEdrRepo},
+};
+
+pub struct SqlRepoExtension {}
+
+#[async_trait::async_trait]
+impl Extension for SqlRepoExtension {
+    async fn start(&self) -> MiwaResult<()> {
+        Ok(())
+    }
+
+    async fn shutdown(&self) -> MiwaResult<()> {
+        Ok(())
+    }
+}
+
+#[derive(Deserialize, ExtensionConfig)]
+#[config(prefix = "db.tokens")]
+#[serde(rename_all = "lowercase")]
+pub enum TokenDbConfig {
+    /// Kept in memory without any migration, the EDRs are lost on restart
+    Memory {},
+    Sqlite {
+        path: String,
+    },
+    Postgres {
+        url: String,
+        #[serde(default)]
+        pool: PgPoolConfig,
+    },
+}
+
+#[extension(
+    name = "Sql store extensions for dataplane proxy",
+    provides(EdrRepoRef, ConsumerEdrRepoRef)
+)]
+pub async fn proxy_sql_repo_extension(
+    ctx: &MiwaContext,
+    ExtensionConfig(cfg): ExtensionConfig<TokenDbConfig>,
+) -> MiwaResult<SqlRepoExtension> {
+    let (edrs, consumer_edrs) = create_token_store(cfg).await?;
+    ctx.register(edrs);
+    ctx.register(consumer_edrs);
+    Ok(SqlRepoExtension {})
+}
+
+async fn create_token_store(
+    cfg: TokenDbConfig,
+) -> anyhow::Result<(EdrRepoRef, ConsumerEdrRepoRef)> {
+    match cfg {
+        TokenDbConfig::Memory {} => Ok((
+            EdrRepoRef::of(InMemoryEdrRepo::new()),
+            ConsumerEdrRepoRef::of(InMemoryConsumerEdrRepo::new()),
+        )),
+        TokenDbConfig::Sqlite { path } => {
+            let store = SqliteEdrRepo::connect(&format!("sqlite:{}", path)).await?;
+            store.migrate().await?;
+
+            let consumer_edrs = SqliteConsumerEdrRepo::new(store.pool().clone());
+
+            Ok((EdrRepoRef::of(store), ConsumerEdrRepoRef::of(consumer_edrs)))
+        }
+        TokenDbConfig::Postgres { url, pool } => {
+            let store = PgEdrRepo::connect_with(&url, &pool).await?;
+            store.migrate().await?;
+
+            let consumer_edrs = PgConsumerEdrRepo::new(store.pool().clone());
+
+            Ok((EdrRepoRef::of(store), ConsumerEdrRepoRef::of(consumer_edrs)))
+        }
+    }
+}
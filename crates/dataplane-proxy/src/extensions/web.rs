@@ -7,16 +7,19 @@ use miwa::{
 use tokio::sync::Mutex;
 
 use crate::{
-    db::edr::EdrRepoRef,
+    db::{consumer::ConsumerEdrRepoRef, edr::EdrRepoRef},
     extensions::{
         config::Proxy,
-        manager::{create_edr_manager, create_token_manager},
+        manager::{create_consumer_edr_manager, create_edr_manager, create_token_manager},
     },
     service::{refresh::RefreshManager, token::TokenManagerImpl},
-    web::state::Context,
+    web::state::{ConsumerContext, Context},
 };
 use edc_dataplane_core::{
-    core::{db::transfer::TransferRepoRef, service::transfer::TransferService},
+    core::{
+        db::transfer::TransferRepoRef,
+        service::{events::TransferEvents, registry::EndpointTypes, transfer::TransferService},
+    },
     web::{start_server, ServerHandle},
 };
 
@@ -24,7 +27,9 @@ use crate::web;
 pub struct DataPlaneProxyApiExtension {
     cfg: Proxy,
     ctx: Context<TokenManagerImpl>,
+    consumer_ctx: Option<ConsumerContext>,
     handle: Arc<Mutex<Option<ServerHandle>>>,
+    consumer_handle: Arc<Mutex<Option<ServerHandle>>>,
 }
 
 #[async_trait::async_trait]
@@ -41,6 +46,19 @@ impl Extension for DataPlaneProxyApiExtension {
 
         self.handle.lock().await.replace(token_server);
 
+        if let (Some(consumer), Some(ctx)) = (&self.cfg.consumer, &self.consumer_ctx) {
+            let consumer_server = start_server(
+                consumer.bind,
+                consumer.port,
+                web::consumer_app(consumer.api_key.clone()),
+                ctx.clone(),
+                "Consumer proxy",
+            )
+            .await?;
+
+            self.consumer_handle.lock().await.replace(consumer_server);
+        }
+
         crate::web::proxy::server::start(&self.cfg, self.ctx.clone()).await;
         Ok(())
     }
@@ -55,16 +73,26 @@ pub async fn proxy_api_extension(
     ExtensionConfig(cfg): ExtensionConfig<Proxy>,
     repo: TransferRepoRef,
     edrs: EdrRepoRef,
+    consumer_edrs: ConsumerEdrRepoRef,
     transfer_service: TransferService,
+    events: TransferEvents,
+    endpoint_types: EndpointTypes,
 ) -> MiwaResult<DataPlaneProxyApiExtension> {
     let tokens = create_token_manager(cfg.clone())?;
     let edr_manager = create_edr_manager(edrs, tokens.clone(), cfg.clone())?;
 
-    let refresh_manager = RefreshManager::new(edr_manager, repo);
-    let ctx = Context::new(transfer_service, tokens, refresh_manager);
+    let consumer_ctx = create_consumer_edr_manager(consumer_edrs, &cfg)
+        .map(|consumer| ConsumerContext::new(transfer_service.clone(), consumer));
+
+    let refresh_manager = RefreshManager::new(edr_manager, repo).with_events(events.clone());
+    let ctx = Context::new(transfer_service, tokens, refresh_manager)
+        .with_events(events)
+        .with_endpoint_types(endpoint_types);
     Ok(DataPlaneProxyApiExtension {
         cfg,
         ctx,
+        consumer_ctx,
         handle: Arc::default(),
+        consumer_handle: Arc::default(),
     })
 }
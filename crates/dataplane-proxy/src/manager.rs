@@ -1,39 +1,86 @@
+use std::collections::HashSet;
+
 use async_trait::async_trait;
+use chrono::{Duration, Utc};
 use edc_dataplane_core::{
     core::{
-        model::transfer::{types::TransferKind, Transfer},
-        service::transfer::TransferManager,
+        db::transfer::{TransferQuery, TransferRepoRef},
+        model::{
+            namespace::IDSA_NAMESPACE,
+            transfer::{Transfer, TransferEdr, TransferStatus},
+        },
+        service::{
+            registry::EndpointTypes,
+            transfer::{TransferError, TransferManager, TransferResult},
+        },
     },
-    signaling::DataAddress,
+    signaling::{DataAddress, FlowType},
 };
+use tracing::debug;
 
 use crate::{
     db::edr::EdrRepoRef,
     model::edr::EdrEntry,
-    service::{edr::EdrManager, token::TokenManager},
+    service::{
+        consumer::{ConsumerEdrError, ConsumerEdrManager},
+        edr::EdrManager,
+        token::TokenManager,
+    },
 };
 
 pub struct TransferProxyManager<T: TokenManager> {
     edrs: EdrManager<T>,
     tokens: EdrRepoRef,
+    consumer: Option<ConsumerEdrManager>,
+    endpoint_types: EndpointTypes,
+    /// Entries saved within the grace period are not purged, their transfer may not be
+    /// saved yet
+    purge_grace: Duration,
 }
 
 impl<T: TokenManager> TransferProxyManager<T> {
     pub fn new(edrs: EdrManager<T>, tokens: EdrRepoRef) -> Self {
-        Self { edrs, tokens }
+        Self {
+            edrs,
+            tokens,
+            consumer: None,
+            endpoint_types: EndpointTypes::default(),
+            purge_grace: Duration::minutes(5),
+        }
+    }
+
+    pub fn with_purge_grace(mut self, purge_grace: Duration) -> Self {
+        self.purge_grace = purge_grace;
+        self
+    }
+
+    /// Handles the sources of the given types instead of the default ones
+    pub fn with_endpoint_types(mut self, endpoint_types: EndpointTypes) -> Self {
+        self.endpoint_types = endpoint_types;
+        self
+    }
+
+    /// Stores the EDRs received for consumer pull transfers
+    pub fn with_consumer(mut self, consumer: ConsumerEdrManager) -> Self {
+        self.consumer = Some(consumer);
+        self
     }
 }
 
 #[async_trait]
 impl<T: TokenManager + Send + Sync + 'static> TransferManager for TransferProxyManager<T> {
-    async fn can_handle(&self, transfer: &Transfer) -> anyhow::Result<bool> {
-        let _ = TransferKind::try_from(&transfer.source.0)?;
+    async fn can_handle(&self, transfer: &Transfer) -> TransferResult<bool> {
+        let source = transfer.source_address()?;
 
-        Ok(true)
+        Ok(transfer.flow_type == FlowType::Pull && self.endpoint_types.pull_url(source)?.is_some())
     }
 
-    async fn handle_start(&self, transfer: &Transfer) -> anyhow::Result<Option<DataAddress>> {
-        let edr = self.edrs.create_edr(transfer).await?;
+    async fn handle_start(&self, transfer: &Transfer) -> TransferResult<Option<DataAddress>> {
+        let edr = self
+            .edrs
+            .create_edr(transfer)
+            .await
+            .map_err(|err| TransferError::Generic(err.into()))?;
 
         let entry = EdrEntry::builder()
             .transfer_id(transfer.id.clone())
@@ -41,16 +88,162 @@ impl<T: TokenManager + Send + Sync + 'static> TransferManager for TransferProxyM
             .token_id(edr.token_id)
             .build();
 
-        self.tokens.save(entry).await?;
+        self.tokens
+            .save(entry)
+            .await
+            .map_err(TransferError::Storage)?;
 
         Ok(Some(edr.data_address))
     }
 
-    async fn handle_suspend(&self, _id: &str) -> anyhow::Result<()> {
-        // todo handle suspend
-        Ok(())
+    async fn handle_started(&self, transfer: &Transfer) -> TransferResult<()> {
+        let Some(consumer) = &self.consumer else {
+            return Ok(());
+        };
+
+        let is_edr = transfer
+            .source
+            .as_ref()
+            .is_some_and(|source| source.0.endpoint_type == IDSA_NAMESPACE.to_iri("HTTP"));
+
+        if transfer.flow_type != FlowType::Pull || !is_edr {
+            return Ok(());
+        }
+
+        consumer.save(transfer).await.map_err(|err| match err {
+            ConsumerEdrError::DataAddress(err) => err.into(),
+            err => TransferError::Generic(err.into()),
+        })
+    }
+
+    async fn reissue(&self, transfer: &Transfer) -> TransferResult<Option<DataAddress>> {
+        let Some(entry) = self
+            .tokens
+            .fetch_by_id(&transfer.id)
+            .await
+            .map_err(TransferError::Storage)?
+        else {
+            return self.handle_start(transfer).await;
+        };
+
+        let edr = self
+            .edrs
+            .reissue_edr(&entry, transfer)
+            .map_err(|err| TransferError::Generic(err.into()))?;
+
+        Ok(Some(edr.data_address))
+    }
+
+    async fn rollback_start(&self, transfer: &Transfer) -> TransferResult<()> {
+        // A resumed transfer keeps its entry with revoked ids, as it was before the start
+        if transfer.status == TransferStatus::Suspended {
+            self.edrs.revoke(&transfer.id).await
+        } else {
+            self.edrs.delete(&transfer.id).await
+        }
+        .map_err(TransferError::Storage)
+    }
+
+    async fn handle_suspend(&self, id: &str) -> TransferResult<()> {
+        self.edrs.revoke(id).await.map_err(TransferError::Storage)
+    }
+    async fn handle_terminate(&self, id: &str) -> TransferResult<()> {
+        if let Some(consumer) = &self.consumer {
+            consumer.delete(id).await.map_err(TransferError::Storage)?;
+        }
+        self.edrs.delete(id).await.map_err(TransferError::Storage)
     }
-    async fn handle_terminate(&self, id: &str) -> anyhow::Result<()> {
-        self.edrs.delete(id).await
+
+    async fn edr(&self, transfer: &Transfer) -> TransferResult<Option<TransferEdr>> {
+        if transfer.consumer {
+            let Some(consumer) = &self.consumer else {
+                return Ok(None);
+            };
+
+            return match consumer.fetch(&transfer.id).await {
+                Ok(edr) => Ok(Some(
+                    TransferEdr::builder()
+                        .endpoint(edr.endpoint.clone())
+                        .maybe_expires_at(edr.expires_at)
+                        .refreshable(edr.is_refreshable())
+                        .build(),
+                )),
+                Err(ConsumerEdrError::NotFound(_)) => Ok(None),
+                Err(err) => Err(TransferError::Generic(err.into())),
+            };
+        }
+
+        let entry = self
+            .tokens
+            .fetch_by_id(&transfer.id)
+            .await
+            .map_err(TransferError::Storage)?;
+
+        Ok(entry.map(|entry| self.edrs.details(&entry, transfer)))
     }
+
+    async fn purge(&self, transfers: &TransferRepoRef, batch_size: i32) -> TransferResult<usize> {
+        let mut purged = 0;
+        let updated_before = Utc::now() - self.purge_grace;
+
+        let ids = self
+            .tokens
+            .transfer_ids(updated_before)
+            .await
+            .map_err(TransferError::Storage)?;
+
+        for id in dead_transfers(transfers, ids, batch_size).await? {
+            debug!("Purging EDR entry of transfer {}", id);
+            self.edrs
+                .delete(&id)
+                .await
+                .map_err(TransferError::Storage)?;
+            purged += 1;
+        }
+
+        if let Some(consumer) = &self.consumer {
+            let ids = consumer
+                .transfer_ids(updated_before)
+                .await
+                .map_err(TransferError::Storage)?;
+
+            for id in dead_transfers(transfers, ids, batch_size).await? {
+                debug!("Purging consumer EDR of transfer {}", id);
+                consumer.delete(&id).await.map_err(TransferError::Storage)?;
+                purged += 1;
+            }
+        }
+
+        Ok(purged)
+    }
+}
+
+/// Ids of the transfers missing from `transfers` or in a final state, looked up one page of
+/// `batch_size` ids at a time
+async fn dead_transfers(
+    transfers: &TransferRepoRef,
+    ids: Vec<String>,
+    batch_size: i32,
+) -> TransferResult<Vec<String>> {
+    let mut dead = vec![];
+
+    for page in ids.chunks(batch_size.max(1) as usize) {
+        let live = transfers
+            .query(
+                TransferQuery::builder()
+                    .ids(page.to_vec())
+                    .limit(page.len() as i32)
+                    .build(),
+            )
+            .await
+            .map_err(TransferError::Storage)?
+            .into_iter()
+            .filter(|transfer| !transfer.status.is_final())
+            .map(|transfer| transfer.id)
+            .collect::<HashSet<_>>();
+
+        dead.extend(page.iter().filter(|id| !live.contains(*id)).cloned());
+    }
+
+    Ok(dead)
 }
@@ -1,2 +1,3 @@
+pub mod consumer;
 pub mod edr;
 pub mod token;
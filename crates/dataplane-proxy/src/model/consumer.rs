@@ -0,0 +1,76 @@
+use bon::Builder;
+use chrono::{DateTime, Duration, Utc};
+use edc_dataplane_core::core::model::{
+    namespace::EDC_NAMESPACE,
+    transfer::{types::DataAddressError, Transfer},
+};
+use sqlx::FromRow;
+
+/// EDR received from the provider for a consumer transfer
+#[derive(Builder, FromRow, Clone, Debug, PartialEq)]
+pub struct ConsumerEdr {
+    #[builder(into)]
+    pub transfer_id: String,
+    #[builder(into)]
+    pub endpoint: String,
+    #[builder(into)]
+    pub access_token: String,
+    #[builder(into)]
+    pub refresh_token: Option<String>,
+    #[builder(into)]
+    pub refresh_endpoint: Option<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    #[builder(default = Utc::now())]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ConsumerEdr {
+    /// Whether the access token expires within `leeway`
+    pub fn expires_within(&self, leeway: Duration) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| expires_at <= Utc::now() + leeway)
+    }
+
+    pub fn is_refreshable(&self) -> bool {
+        self.refresh_token.is_some() && self.refresh_endpoint.is_some()
+    }
+}
+
+impl TryFrom<&Transfer> for ConsumerEdr {
+    type Error = DataAddressError;
+
+    fn try_from(transfer: &Transfer) -> Result<Self, Self::Error> {
+        let address = transfer.source_address()?;
+        let property = |name: &str| address.get_property(&EDC_NAMESPACE.to_iri(name));
+
+        let endpoint = property("endpoint")
+            .ok_or_else(|| DataAddressError::MissingProperty("endpoint".to_string()))?;
+
+        // EDC names the access token `authorization`
+        let access_token = property("access_token")
+            .or_else(|| property("authorization"))
+            .ok_or_else(|| DataAddressError::MissingProperty("access_token".to_string()))?;
+
+        let expires_in = property("expires_in")
+            .map(|value| {
+                value
+                    .parse::<i64>()
+                    .map_err(|err| DataAddressError::InvalidProperty {
+                        name: "expires_in".to_string(),
+                        reason: err.to_string(),
+                    })
+            })
+            .transpose()?;
+
+        Ok(ConsumerEdr::builder()
+            .transfer_id(&transfer.id)
+            .endpoint(endpoint)
+            .access_token(access_token)
+            .maybe_refresh_token(property("refresh_token"))
+            .maybe_refresh_endpoint(property("refresh_endpoint"))
+            .maybe_expires_at(
+                expires_in.map(|expires_in| Utc::now() + Duration::seconds(expires_in)),
+            )
+            .build())
+    }
+}
@@ -1,3 +1,4 @@
+pub mod consumer;
 pub mod edr;
 pub mod refresh;
 pub mod token;
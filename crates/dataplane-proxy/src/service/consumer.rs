@@ -0,0 +1,327 @@
+use std::sync::Arc;
+
+use bon::Builder;
+use chrono::{DateTime, Duration, Utc};
+use dashmap::DashMap;
+use edc_dataplane_core::core::model::transfer::{types::DataAddressError, Transfer};
+use reqwest::header::AUTHORIZATION;
+use serde::Deserialize;
+use serde_with::{serde_as, DisplayFromStr, PickFirst};
+use thiserror::Error;
+use tokio::sync::Mutex;
+use tracing::debug;
+
+use crate::{db::consumer::ConsumerEdrRepoRef, model::consumer::ConsumerEdr};
+
+/// Holds the EDRs received by the consumer and keeps their access tokens fresh
+#[derive(Clone, Builder)]
+pub struct ConsumerEdrManager {
+    store: ConsumerEdrRepoRef,
+    #[builder(default)]
+    client: reqwest::Client,
+    /// Sent as `client_id` on the refresh requests
+    #[builder(into)]
+    client_id: String,
+    /// Access tokens expiring within the leeway are refreshed before use
+    leeway: Duration,
+    /// Serializes the refreshes of each transfer
+    #[builder(skip)]
+    refresh_locks: Arc<DashMap<String, Arc<Mutex<()>>>>,
+}
+
+#[serde_as]
+#[derive(Deserialize)]
+struct RefreshResponse {
+    access_token: String,
+    refresh_token: Option<String>,
+    // EDC returns a number, this dataplane a string
+    #[serde_as(as = "Option<PickFirst<(_, DisplayFromStr)>>")]
+    #[serde(default)]
+    expires_in: Option<i64>,
+}
+
+impl ConsumerEdrManager {
+    pub async fn save(&self, transfer: &Transfer) -> Result<(), ConsumerEdrError> {
+        let edr = ConsumerEdr::try_from(transfer)?;
+        debug!("Storing EDR for consumer transfer {}", edr.transfer_id);
+        self.store
+            .save(edr)
+            .await
+            .map_err(ConsumerEdrError::Generic)
+    }
+
+    pub async fn delete(&self, transfer_id: &str) -> anyhow::Result<()> {
+        self.store.delete(transfer_id).await
+    }
+
+    pub async fn transfer_ids(&self, updated_before: DateTime<Utc>) -> anyhow::Result<Vec<String>> {
+        self.store.transfer_ids(updated_before).await
+    }
+
+    /// Returns the EDR of the transfer, refreshing its access token when it is about to expire
+    pub async fn get(&self, transfer_id: &str) -> Result<ConsumerEdr, ConsumerEdrError> {
+        let edr = self.fetch(transfer_id).await?;
+
+        if edr.expires_within(self.leeway) && edr.is_refreshable() {
+            self.refresh(transfer_id, &edr.access_token).await
+        } else {
+            Ok(edr)
+        }
+    }
+
+    /// Refreshes the access token after the provider rejected `rejected`. When another
+    /// request already rotated it, the current token is returned instead.
+    pub async fn refresh(
+        &self,
+        transfer_id: &str,
+        rejected: &str,
+    ) -> Result<ConsumerEdr, ConsumerEdrError> {
+        let lock = self
+            .refresh_locks
+            .entry(transfer_id.to_string())
+            .or_default()
+            .clone();
+        let result = {
+            let _guard = lock.lock().await;
+            self.refresh_locked(transfer_id, rejected).await
+        };
+
+        drop(lock);
+        self.refresh_locks
+            .remove_if(transfer_id, |_, lock| Arc::strong_count(lock) == 1);
+
+        result
+    }
+
+    async fn refresh_locked(
+        &self,
+        transfer_id: &str,
+        rejected: &str,
+    ) -> Result<ConsumerEdr, ConsumerEdrError> {
+        let mut edr = self.fetch(transfer_id).await?;
+
+        if edr.access_token != rejected {
+            return Ok(edr);
+        }
+
+        let (Some(refresh_token), Some(refresh_endpoint)) = (
+            edr.refresh_token.as_deref(),
+            edr.refresh_endpoint.as_deref(),
+        ) else {
+            return Err(ConsumerEdrError::NotRefreshable(transfer_id.to_string()));
+        };
+
+        debug!(
+            "Refreshing access token of consumer transfer {}",
+            transfer_id
+        );
+
+        let response = self
+            .client
+            .post(refresh_endpoint)
+            .header(AUTHORIZATION, &edr.access_token)
+            .form(&[
+                ("grant_type", "refresh_token"),
+                ("refresh_token", refresh_token),
+                ("client_id", &self.client_id),
+            ])
+            .send()
+            .await
+            .map_err(|err| ConsumerEdrError::Refresh(err.to_string()))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(ConsumerEdrError::Refresh(format!("{}: {}", status, body)));
+        }
+
+        let tokens = response
+            .json::<RefreshResponse>()
+            .await
+            .map_err(|err| ConsumerEdrError::Refresh(err.to_string()))?;
+
+        let now = Utc::now();
+        edr.access_token = tokens.access_token;
+        if let Some(refresh_token) = tokens.refresh_token {
+            edr.refresh_token = Some(refresh_token);
+        }
+        edr.expires_at = tokens
+            .expires_in
+            .map(|expires_in| now + Duration::seconds(expires_in));
+        edr.updated_at = now;
+
+        self.store
+            .save(edr.clone())
+            .await
+            .map_err(ConsumerEdrError::Generic)?;
+
+        Ok(edr)
+    }
+
+    /// Returns the stored EDR of the transfer as is
+    pub async fn fetch(&self, transfer_id: &str) -> Result<ConsumerEdr, ConsumerEdrError> {
+        self.store
+            .fetch_by_id(transfer_id)
+            .await
+            .map_err(ConsumerEdrError::Generic)?
+            .ok_or_else(|| ConsumerEdrError::NotFound(transfer_id.to_string()))
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ConsumerEdrError {
+    #[error("No EDR for transfer {0}")]
+    NotFound(String),
+    #[error("The EDR of transfer {0} cannot be refreshed")]
+    NotRefreshable(String),
+    #[error("Failed to refresh the access token: {0}")]
+    Refresh(String),
+    #[error(transparent)]
+    DataAddress(#[from] DataAddressError),
+    #[error(transparent)]
+    Generic(anyhow::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+    use futures::FutureExt;
+    use serde_json::json;
+    use wiremock::{
+        matchers::{body_string_contains, header, method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    use crate::{
+        db::consumer::{ConsumerEdrRepoRef, MockConsumerEdrRepo},
+        model::consumer::ConsumerEdr,
+    };
+
+    use super::{ConsumerEdrError, ConsumerEdrManager};
+
+    fn create_edr(refresh_endpoint: &str, expires_in: i64) -> ConsumerEdr {
+        ConsumerEdr::builder()
+            .transfer_id("process_id")
+            .endpoint("http://provider/public")
+            .access_token("access")
+            .refresh_token("refresh")
+            .refresh_endpoint(refresh_endpoint)
+            .expires_at(Utc::now() + Duration::seconds(expires_in))
+            .build()
+    }
+
+    fn create_manager(store: MockConsumerEdrRepo) -> ConsumerEdrManager {
+        ConsumerEdrManager::builder()
+            .store(ConsumerEdrRepoRef::of(store))
+            .client_id("consumer")
+            .leeway(Duration::seconds(30))
+            .build()
+    }
+
+    fn stored(store: &mut MockConsumerEdrRepo, edr: ConsumerEdr) {
+        store
+            .expect_fetch_by_id()
+            .returning(move |_| futures::future::ok(Some(edr.clone())).boxed());
+    }
+
+    async fn token_server(expected: u64) -> MockServer {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .and(header("authorization", "access"))
+            .and(body_string_contains("grant_type=refresh_token"))
+            .and(body_string_contains("refresh_token=refresh"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "access_token": "rotated",
+                "refresh_token": "rotated_refresh",
+                "expires_in": "600"
+            })))
+            .expect(expected)
+            .mount(&server)
+            .await;
+        server
+    }
+
+    #[tokio::test]
+    async fn get_keeps_valid_token() {
+        let server = token_server(0).await;
+
+        let mut store = MockConsumerEdrRepo::new();
+        stored(
+            &mut store,
+            create_edr(&format!("{}/token", server.uri()), 600),
+        );
+
+        let edr = create_manager(store).get("process_id").await.unwrap();
+
+        assert_eq!(edr.access_token, "access");
+        server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn get_refreshes_near_expiry() {
+        let server = token_server(1).await;
+
+        let mut store = MockConsumerEdrRepo::new();
+        stored(
+            &mut store,
+            create_edr(&format!("{}/token", server.uri()), 10),
+        );
+
+        store
+            .expect_save()
+            .withf(|edr| {
+                edr.access_token == "rotated"
+                    && edr.refresh_token.as_deref() == Some("rotated_refresh")
+                    && edr
+                        .expires_at
+                        .is_some_and(|expires_at| expires_at > Utc::now() + Duration::seconds(500))
+            })
+            .times(1)
+            .returning(|_| futures::future::ok(()).boxed());
+
+        let edr = create_manager(store).get("process_id").await.unwrap();
+
+        assert_eq!(edr.access_token, "rotated");
+        server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn refresh_skips_already_rotated_token() {
+        let server = token_server(0).await;
+
+        let mut store = MockConsumerEdrRepo::new();
+        stored(
+            &mut store,
+            create_edr(&format!("{}/token", server.uri()), 600),
+        );
+
+        let edr = create_manager(store)
+            .refresh("process_id", "stale")
+            .await
+            .unwrap();
+
+        assert_eq!(edr.access_token, "access");
+        server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn refresh_fails_when_rejected() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .respond_with(ResponseTemplate::new(400))
+            .mount(&server)
+            .await;
+
+        let mut store = MockConsumerEdrRepo::new();
+        stored(
+            &mut store,
+            create_edr(&format!("{}/token", server.uri()), 600),
+        );
+
+        let result = create_manager(store).refresh("process_id", "access").await;
+
+        assert!(matches!(result, Err(ConsumerEdrError::Refresh(_))));
+    }
+}
@@ -3,7 +3,7 @@ use chrono::{Duration, Utc};
 use edc_dataplane_core::{
     core::model::{
         namespace::{EDC_NAMESPACE, IDSA_NAMESPACE},
-        transfer::Transfer,
+        transfer::{Transfer, TransferEdr, TransferStatus},
     },
     signaling::{DataAddress, EndpointProperty},
 };
@@ -39,9 +39,21 @@ pub struct EdrManager<T: TokenManager> {
 
 impl<T: TokenManager> EdrManager<T> {
     pub async fn create_edr(&self, req: &Transfer) -> Result<Edr, EdrError> {
-        let token_id: TokenId = Uuid::new_v4().into();
-        let refresh_token_id: RefreshTokenId = Uuid::new_v4().into();
+        self.issue_edr(Uuid::new_v4().into(), Uuid::new_v4().into(), req)
+    }
+
+    /// Issues new tokens bound to the ids of the current entry, the tokens issued
+    /// before stay valid
+    pub fn reissue_edr(&self, entry: &EdrEntry, req: &Transfer) -> Result<Edr, EdrError> {
+        self.issue_edr(entry.token_id, entry.refresh_token_id, req)
+    }
 
+    fn issue_edr(
+        &self,
+        token_id: TokenId,
+        refresh_token_id: RefreshTokenId,
+        req: &Transfer,
+    ) -> Result<Edr, EdrError> {
         let data_address = DataAddress::builder()
             .endpoint_type(IDSA_NAMESPACE.to_iri("HTTP"))
             .endpoint_properties(self.endpoint_properties(token_id, refresh_token_id, req)?)
@@ -54,6 +66,19 @@ impl<T: TokenManager> EdrManager<T> {
             .build())
     }
 
+    /// EDR of the entry without its tokens, the access token issued at the start of the
+    /// transfer expiring after the token duration
+    pub fn details(&self, entry: &EdrEntry, transfer: &Transfer) -> TransferEdr {
+        let started = transfer.status == TransferStatus::Started;
+
+        TransferEdr::builder()
+            .endpoint(self.proxy_url.clone())
+            .token_id(Uuid::from(entry.token_id).to_string())
+            .maybe_expires_at(started.then(|| transfer.updated_at + self.token_duration))
+            .refreshable(started)
+            .build()
+    }
+
     pub async fn get_by_transfer_id(&self, transfer_id: &str) -> anyhow::Result<Option<EdrEntry>> {
         self.store.fetch_by_id(transfer_id).await
     }
@@ -66,6 +91,17 @@ impl<T: TokenManager> EdrManager<T> {
         self.store.delete(transfer_id).await
     }
 
+    /// Invalidates the access and refresh tokens issued for a transfer by rotating
+    /// the ids of the current token pair, without issuing new tokens.
+    pub async fn revoke(&self, transfer_id: &str) -> anyhow::Result<()> {
+        if let Some(mut entry) = self.store.fetch_by_id(transfer_id).await? {
+            entry.token_id = Uuid::new_v4().into();
+            entry.refresh_token_id = Uuid::new_v4().into();
+            self.store.save(entry).await?;
+        }
+        Ok(())
+    }
+
     pub async fn refresh_token(&self, req: TokenRequest) -> Result<TokenResponse, EdrError> {
         let token_id: TokenId = Uuid::new_v4().into();
         let refresh_token_id: RefreshTokenId = Uuid::new_v4().into();
@@ -217,10 +253,10 @@ mod tests {
         token_manager
             .expect_issue::<EdrClaims>()
             .withf(|claims| {
-                claims.iss == "issuer".to_string()
-                    && claims.aud == "http://localhost:8080/public".to_string()
-                    && claims.sub == "participant_id".to_string()
-                    && claims.transfer_id == "process_id".to_string()
+                claims.iss == "issuer"
+                    && claims.aud == "http://localhost:8080/public"
+                    && claims.sub == "participant_id"
+                    && claims.transfer_id == "process_id"
             })
             .returning(|_: &EdrClaims| Ok("token".to_string()));
 
@@ -305,6 +341,43 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_revoke_edr() {
+        let mut store = MockEdrRepo::new();
+        let entry = EdrEntry::builder()
+            .transfer_id("process_id")
+            .token_id(Uuid::new_v4())
+            .refresh_token_id(Uuid::new_v4())
+            .build();
+
+        let fetched = entry.clone();
+        store
+            .expect_fetch_by_id()
+            .returning(move |_| Box::pin(futures::future::ok(Some(fetched.clone()))));
+
+        store
+            .expect_save()
+            .withf(move |saved| {
+                saved.transfer_id == entry.transfer_id
+                    && saved.token_id != entry.token_id
+                    && saved.refresh_token_id != entry.refresh_token_id
+            })
+            .times(1)
+            .returning(|_| Box::pin(futures::future::ok(())));
+
+        let edr_manager = EdrManager::builder()
+            .proxy_url("http://localhost:8080/public")
+            .issuer("issuer")
+            .tokens(MockTokenManager::new())
+            .token_duration(Duration::hours(1))
+            .token_url("http://localhost:8080/token")
+            .jwks_url("http://localhost:8080/.well-known/jwks.json")
+            .store(EdrRepoRef::of(store))
+            .build();
+
+        edr_manager.revoke("process_id").await.unwrap();
+    }
+
     fn create_transfer() -> Transfer {
         Transfer::builder()
             .participant_id("participant_id".to_string())
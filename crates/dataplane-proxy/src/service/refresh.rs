@@ -1,6 +1,7 @@
 use edc_dataplane_core::core::{
     db::transfer::TransferRepoRef,
     model::transfer::{Transfer, TransferStatus},
+    service::events::{TransferEvent, TransferEvents},
 };
 use thiserror::Error;
 use uuid::Uuid;
@@ -19,11 +20,22 @@ use super::{
 pub struct RefreshManager<T: TokenManager> {
     pub(crate) edrs: EdrManager<T>,
     store: TransferRepoRef,
+    events: Option<TransferEvents>,
 }
 
 impl<T: TokenManager> RefreshManager<T> {
     pub fn new(edrs: EdrManager<T>, store: TransferRepoRef) -> Self {
-        Self { edrs, store }
+        Self {
+            edrs,
+            store,
+            events: None,
+        }
+    }
+
+    /// Publishes the refreshed tokens
+    pub fn with_events(mut self, events: TransferEvents) -> Self {
+        self.events = Some(events);
+        self
     }
 
     async fn get_transfer(&self, id: &str) -> Result<Transfer, RefreshError> {
@@ -71,6 +83,12 @@ impl<T: TokenManager> RefreshManager<T> {
 
         self.edrs.save(edr_entry).await?;
 
+        if let Some(events) = &self.events {
+            events.publish(TransferEvent::TokenRefreshed {
+                transfer_id: claims.claims.transfer_id,
+            });
+        }
+
         token_response
     }
 }
@@ -1,6 +1,7 @@
 pub mod api;
+pub mod consumer;
 pub mod proxy;
 pub mod router;
 pub mod state;
 
-pub use router::token_app;
+pub use router::{consumer_app, token_app};
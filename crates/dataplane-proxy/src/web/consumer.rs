@@ -0,0 +1,213 @@
+use axum::{
+    body::{Body, Bytes},
+    extract::{Path, RawQuery, Request, State},
+    http::{header, HeaderMap, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use edc_dataplane_core::core::model::transfer::TransferStatus;
+use reqwest::Url;
+use secrecy::ExposeSecret;
+use serde::Deserialize;
+use serde_json::json;
+use tracing::{debug, error, warn};
+
+use crate::{
+    extensions::ConsumerApiKey, model::consumer::ConsumerEdr, service::consumer::ConsumerEdrError,
+    web::state::ConsumerContext,
+};
+
+/// Headers which are not forwarded between the client, the proxy and the provider
+static HOP_BY_HOP: [header::HeaderName; 6] = [
+    header::CONNECTION,
+    header::CONTENT_LENGTH,
+    header::HOST,
+    header::PROXY_AUTHORIZATION,
+    header::TRANSFER_ENCODING,
+    header::UPGRADE,
+];
+
+#[derive(Deserialize)]
+pub struct ProxyPath {
+    id: String,
+    path: Option<String>,
+}
+
+/// Rejects the requests which do not carry the API key of the consumer proxy
+pub async fn authenticate(
+    State(api_key): State<ConsumerApiKey>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ConsumerProxyError> {
+    let authorized = request.headers().get(&api_key.header).is_some_and(|value| {
+        constant_time_eq(value.as_bytes(), api_key.key.expose_secret().as_bytes())
+    });
+
+    if !authorized {
+        warn!(
+            "Rejected consumer proxy request {} {}",
+            request.method(),
+            request.uri().path()
+        );
+        return Err(ConsumerProxyError::Unauthorized);
+    }
+
+    Ok(next.run(request).await)
+}
+
+/// Forwards the request to the provider with the access token of the transfer, refreshing
+/// the token and retrying once when the provider rejects it
+pub async fn proxy_request(
+    State(ctx): State<ConsumerContext>,
+    Path(ProxyPath { id, path }): Path<ProxyPath>,
+    RawQuery(query): RawQuery,
+    method: Method,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Response, ConsumerProxyError> {
+    ctx.transfers()
+        .get(&id)
+        .await
+        .map_err(|err| ConsumerProxyError::Generic(err.into()))?
+        .filter(|transfer| transfer.status == TransferStatus::Started)
+        .ok_or(ConsumerProxyError::InvalidTransfer)?;
+
+    let request = UpstreamRequest {
+        path: path_segments(path.as_deref().unwrap_or_default())?,
+        query,
+        method,
+        headers,
+        body,
+    };
+
+    let edr = ctx.edrs().get(&id).await?;
+    let mut response = request.send(&ctx, &edr).await?;
+
+    if response.status() == StatusCode::UNAUTHORIZED && edr.is_refreshable() {
+        debug!("Access token of transfer {} rejected by the provider", id);
+        let edr = ctx.edrs().refresh(&id, &edr.access_token).await?;
+        response = request.send(&ctx, &edr).await?;
+    }
+
+    Ok(into_response(response))
+}
+
+/// Splits the decoded path into segments, rejecting the ones which would leave the endpoint
+/// of the provider
+fn path_segments(path: &str) -> Result<Vec<String>, ConsumerProxyError> {
+    if path.is_empty() {
+        return Ok(vec![]);
+    }
+
+    path.split('/')
+        .map(|segment| match segment {
+            "." | ".." => Err(ConsumerProxyError::InvalidPath),
+            segment => Ok(segment.to_string()),
+        })
+        .collect()
+}
+
+struct UpstreamRequest {
+    path: Vec<String>,
+    query: Option<String>,
+    method: Method,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+impl UpstreamRequest {
+    async fn send(
+        &self,
+        ctx: &ConsumerContext,
+        edr: &ConsumerEdr,
+    ) -> Result<reqwest::Response, ConsumerProxyError> {
+        let mut url = Url::parse(&edr.endpoint)
+            .map_err(|err| ConsumerProxyError::Upstream(err.to_string()))?;
+        if !self.path.is_empty() {
+            url.path_segments_mut()
+                .map_err(|_| {
+                    ConsumerProxyError::Upstream(format!("Invalid endpoint {}", edr.endpoint))
+                })?
+                .pop_if_empty()
+                .extend(&self.path);
+        }
+        url.set_query(self.query.as_deref());
+
+        let mut headers = self.headers.clone();
+        for name in HOP_BY_HOP.iter().chain([&header::AUTHORIZATION]) {
+            headers.remove(name);
+        }
+
+        ctx.client()
+            .request(self.method.clone(), url)
+            .headers(headers)
+            .header(header::AUTHORIZATION, &edr.access_token)
+            .body(self.body.clone())
+            .send()
+            .await
+            .map_err(|err| ConsumerProxyError::Upstream(err.to_string()))
+    }
+}
+
+/// Streams the response of the provider back to the client
+fn into_response(upstream: reqwest::Response) -> Response {
+    let status = upstream.status();
+    let mut headers = upstream.headers().clone();
+    for name in HOP_BY_HOP.iter() {
+        headers.remove(name);
+    }
+
+    let mut response = Response::new(Body::from_stream(upstream.bytes_stream()));
+    *response.status_mut() = status;
+    *response.headers_mut() = headers;
+    response
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConsumerProxyError {
+    #[error("Invalid Transfer")]
+    InvalidTransfer,
+    #[error("Unauthorized")]
+    Unauthorized,
+    #[error("Invalid path")]
+    InvalidPath,
+    #[error(transparent)]
+    Edr(#[from] ConsumerEdrError),
+    #[error("Failed to reach the provider: {0}")]
+    Upstream(String),
+    #[error(transparent)]
+    Generic(anyhow::Error),
+}
+
+impl IntoResponse for ConsumerProxyError {
+    fn into_response(self) -> Response {
+        let status = match &self {
+            ConsumerProxyError::InvalidTransfer => StatusCode::FORBIDDEN,
+            ConsumerProxyError::Unauthorized => StatusCode::UNAUTHORIZED,
+            ConsumerProxyError::InvalidPath => StatusCode::BAD_REQUEST,
+            ConsumerProxyError::Edr(ConsumerEdrError::NotFound(_)) => StatusCode::FORBIDDEN,
+            ConsumerProxyError::Edr(ConsumerEdrError::Refresh(_)) => StatusCode::BAD_GATEWAY,
+            ConsumerProxyError::Upstream(_) => StatusCode::BAD_GATEWAY,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+
+        if status.is_server_error() {
+            error!("Failed to proxy consumer request: {}", self);
+        }
+
+        let body = Json(json!({
+            "error": self.to_string(),
+        }));
+        (status, body).into_response()
+    }
+}
+
+fn constant_time_eq(left: &[u8], right: &[u8]) -> bool {
+    left.len() == right.len()
+        && left
+            .iter()
+            .zip(right)
+            .fold(0, |acc, (left, right)| acc | (left ^ right))
+            == 0
+}
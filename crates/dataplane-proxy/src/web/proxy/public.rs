@@ -1,8 +1,8 @@
 use std::str;
 
 use axum::http::{uri::InvalidUri, Uri};
-use edc_dataplane_core::core::model::transfer::types::HttpData;
 use edc_dataplane_core::core::model::transfer::{Transfer, TransferStatus};
+use edc_dataplane_core::core::service::events::TransferEvent;
 use futures::TryFutureExt;
 use pingora::http::RequestHeader;
 use pingora::{upstreams::peer::HttpPeer, Result};
@@ -100,11 +100,27 @@ impl<T: TokenManager + Send + Sync + Clone + 'static> PublicProxy<T> {
         &self,
         session: &Session,
     ) -> std::result::Result<TransferRequest, ProxyError> {
-        self.validate_token(session.req_header())
-            .and_then(|claims| self.fetch_edr(claims))
+        let claims = self
+            .validate_token(session.req_header())
+            .await
+            .inspect_err(|err| self.denied(None, err))?;
+
+        let transfer_id = claims.transfer_id.clone();
+
+        self.fetch_edr(claims)
             .and_then(|edr| self.fetch_transfer(edr))
             .and_then(|transfer| self.parse_transfer(transfer))
             .await
+            .inspect_err(|err| self.denied(Some(transfer_id), err))
+    }
+
+    fn denied(&self, transfer_id: Option<String>, err: &ProxyError) {
+        if err.to_response_code() == 403 {
+            self.ctx.publish(TransferEvent::ProxyAccessDenied {
+                transfer_id,
+                reason: err.to_string(),
+            });
+        }
     }
 
     async fn handle_upstream_request(
@@ -146,7 +162,7 @@ impl<T: TokenManager + Send + Sync + Clone + 'static> PublicProxy<T> {
             .transfers()
             .get(&edr.transfer_id)
             .await
-            .map_err(ProxyError::Generic)?
+            .map_err(|err| ProxyError::Generic(err.into()))?
             .filter(|transfer| transfer.status == TransferStatus::Started)
             .ok_or_else(|| ProxyError::InvalidTransfer)
     }
@@ -165,9 +181,23 @@ impl<T: TokenManager + Send + Sync + Clone + 'static> PublicProxy<T> {
         &self,
         transfer: Transfer,
     ) -> std::result::Result<TransferRequest, ProxyError> {
-        let data = HttpData::try_from(transfer.source.as_ref())?;
+        let source = transfer
+            .source_address()
+            .map_err(|err| ProxyError::Generic(err.into()))?;
+
+        let base_url = self
+            .ctx
+            .endpoint_types()
+            .pull_url(source)
+            .map_err(|err| ProxyError::Generic(err.into()))?
+            .ok_or_else(|| {
+                ProxyError::Generic(anyhow::anyhow!(
+                    "Endpoint type {} cannot be proxied",
+                    source.endpoint_type
+                ))
+            })?;
 
-        Ok(TransferRequest { data })
+        Ok(TransferRequest { base_url })
     }
 
     fn can_handle(&self, session: &Session) -> bool {
@@ -205,25 +235,23 @@ impl ProxyError {
 }
 
 pub struct TransferRequest {
-    data: HttpData,
+    base_url: Uri,
 }
 
 impl TransferRequest {
     pub fn upstream_host(&self) -> &str {
-        self.data.base_url.host().unwrap()
+        self.base_url.host().unwrap()
     }
 
     pub fn is_tls(&self) -> bool {
-        self.data
-            .base_url
+        self.base_url
             .scheme()
             .map(|f| f.as_str() == "https")
             .unwrap_or_default()
     }
 
     pub fn upstream_port(&self) -> u16 {
-        self.data
-            .base_url
+        self.base_url
             .port_u16()
             .unwrap_or_else(|| if self.is_tls() { 443 } else { 80 })
     }
@@ -232,7 +260,7 @@ impl TransferRequest {
         let req_path = session.req_header().uri.path().replace(PUBLIC_PATH, "");
 
         Uri::builder()
-            .path_and_query(&(self.data.base_url.path().to_string() + &req_path))
+            .path_and_query(&(self.base_url.path().to_string() + &req_path))
             .build()
             .unwrap()
     }
@@ -1,13 +1,15 @@
 use axum::{
-    routing::{get, post},
+    middleware,
+    routing::{any, get, post},
     Router,
 };
 
-use crate::service::token::TokenManager;
+use crate::{extensions::ConsumerApiKey, service::token::TokenManager};
 
 use super::{
     api::{jwks::jwks, token::refresh_token},
-    state::Context,
+    consumer::{authenticate, proxy_request},
+    state::{ConsumerContext, Context},
 };
 
 pub fn token_app<T: TokenManager + Send + Sync + Clone + 'static>() -> Router<Context<T>> {
@@ -15,3 +17,10 @@ pub fn token_app<T: TokenManager + Send + Sync + Clone + 'static>() -> Router<Co
         .route("/.well-known/jwks.json", get(jwks))
         .route("/api/v1/token", post(refresh_token))
 }
+
+pub fn consumer_app(api_key: ConsumerApiKey) -> Router<ConsumerContext> {
+    Router::new()
+        .route("/api/v1/transfers/:id", any(proxy_request))
+        .route("/api/v1/transfers/:id/*path", any(proxy_request))
+        .route_layer(middleware::from_fn_with_state(api_key, authenticate))
+}
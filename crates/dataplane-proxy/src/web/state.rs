@@ -1,12 +1,20 @@
-use edc_dataplane_core::core::service::transfer::TransferService;
+use edc_dataplane_core::core::service::{
+    events::{TransferEvent, TransferEvents},
+    registry::EndpointTypes,
+    transfer::TransferService,
+};
 
-use crate::service::{edr::EdrManager, refresh::RefreshManager, token::TokenManager};
+use crate::service::{
+    consumer::ConsumerEdrManager, edr::EdrManager, refresh::RefreshManager, token::TokenManager,
+};
 
 #[derive(Clone)]
 pub struct Context<T: TokenManager + Clone> {
     transfers: TransferService,
     tokens: T,
     refresh_manager: RefreshManager<T>,
+    events: Option<TransferEvents>,
+    endpoint_types: EndpointTypes,
 }
 
 impl<T: TokenManager + Clone> Context<T> {
@@ -15,13 +23,31 @@ impl<T: TokenManager + Clone> Context<T> {
             transfers,
             tokens,
             refresh_manager,
+            events: None,
+            endpoint_types: EndpointTypes::default(),
         }
     }
 
+    /// Publishes the requests denied by the proxy
+    pub fn with_events(mut self, events: TransferEvents) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Proxies the sources of the given types instead of the default ones
+    pub fn with_endpoint_types(mut self, endpoint_types: EndpointTypes) -> Self {
+        self.endpoint_types = endpoint_types;
+        self
+    }
+
     pub fn transfers(&self) -> &TransferService {
         &self.transfers
     }
 
+    pub fn endpoint_types(&self) -> &EndpointTypes {
+        &self.endpoint_types
+    }
+
     pub fn tokens(&self) -> &T {
         &self.tokens
     }
@@ -33,4 +59,40 @@ impl<T: TokenManager + Clone> Context<T> {
     pub fn edrs(&self) -> &EdrManager<T> {
         &self.refresh_manager.edrs
     }
+
+    pub fn publish(&self, event: TransferEvent) {
+        if let Some(events) = &self.events {
+            events.publish(event);
+        }
+    }
+}
+
+/// State of the consumer proxy
+#[derive(Clone)]
+pub struct ConsumerContext {
+    transfers: TransferService,
+    edrs: ConsumerEdrManager,
+    client: reqwest::Client,
+}
+
+impl ConsumerContext {
+    pub fn new(transfers: TransferService, edrs: ConsumerEdrManager) -> Self {
+        Self {
+            transfers,
+            edrs,
+            client: reqwest::Client::default(),
+        }
+    }
+
+    pub fn transfers(&self) -> &TransferService {
+        &self.transfers
+    }
+
+    pub fn edrs(&self) -> &ConsumerEdrManager {
+        &self.edrs
+    }
+
+    pub fn client(&self) -> &reqwest::Client {
+        &self.client
+    }
 }
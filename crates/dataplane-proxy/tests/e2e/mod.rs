@@ -18,7 +18,11 @@ use edc_connector_client::{
 use edc_dataplane_core::core::model::namespace::EDC_NAMESPACE;
 use edc_dataplane_proxy::extensions::{proxy_api_extension, proxy_sql_repo_extension};
 
-use edc_dataplane_core::extensions::{sql_repo_extension, transfer_service_extension};
+use edc_dataplane_core::extensions::{
+    endpoint_types_extension, notification_extension, reaper_extension, sql_repo_extension,
+    transfer_events_extension, transfer_managers_extension, transfer_service_extension,
+    webhook_extension,
+};
 use edc_dataplane_proxy::extensions::transfer_proxy_extension;
 use edc_dataplane_signaling::extensions::{registration_extension, signaling_api_extension};
 use miwa::core::{Miwa, MiwaHandle};
@@ -61,8 +65,14 @@ async fn init_dataplane(token_expiration: u64, refresh_token_expiration: u64) ->
         .build()
         .unwrap()
         .add_extension(sql_repo_extension)
+        .add_extension(transfer_events_extension)
+        .add_extension(transfer_managers_extension)
+        .add_extension(endpoint_types_extension)
         .add_extension(proxy_sql_repo_extension)
         .add_extension(transfer_service_extension)
+        .add_extension(notification_extension)
+        .add_extension(webhook_extension)
+        .add_extension(reaper_extension)
         .add_extension(transfer_proxy_extension)
         .add_extension(registration_extension)
         .add_extension(signaling_api_extension)
@@ -83,6 +93,11 @@ fn runtime_config(token_expiration: u64, refresh_token_expiration: u64) -> Value
             "transfer_types":["HttpData-PULL"],
             "source_types":["HttpData"]
         },
+        "notifications": {
+            "poll_interval": 1
+        },
+        "webhooks": {},
+        "reaper": {},
         "proxy": {
             "issuer": "issuer",
             "token_duration": token_expiration,
@@ -166,7 +181,7 @@ pub async fn seed_contract_negotiation(
     provider: &EdcConnectorClient,
     data_address: DataAddress,
 ) -> (String, String) {
-    let (asset_id, _, _) = seed(&provider, data_address).await;
+    let (asset_id, _, _) = seed(provider, data_address).await;
 
     let dataset_request = DatasetRequest::builder()
         .counter_party_address(PROVIDER_PROTOCOL)
@@ -231,7 +246,7 @@ pub async fn seed_transfer_process(
         seed_contract_negotiation(consumer, provider, data_address).await;
 
     wait_for_negotiation_state(
-        &consumer,
+        consumer,
         &contract_negotiation_id,
         ContractNegotiationState::Finalized,
     )
@@ -264,7 +279,7 @@ pub async fn seed_transfer_process(
         .await
         .unwrap();
 
-    wait_for_transfer_state(&consumer, response.id(), TransferProcessState::Started).await;
+    wait_for_transfer_state(consumer, response.id(), TransferProcessState::Started).await;
 
     (
         response.id().to_string(),
@@ -158,7 +158,7 @@ async fn transfer_pull_test_with_terminate() {
 
     provider
         .transfer_processes()
-        .terminate(&tp.correlation_id().unwrap(), "termination")
+        .terminate(tp.correlation_id().unwrap(), "termination")
         .await
         .unwrap();
 
@@ -167,6 +167,10 @@ async fn transfer_pull_test_with_terminate() {
     let response = fetch_data(&edr).await;
 
     assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = renew_token(&edr, "consumer").await;
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 }
 
 #[tokio::test]
@@ -211,6 +215,10 @@ async fn transfer_pull_test_with_suspend_and_resume() {
 
     assert_eq!(response.status(), StatusCode::FORBIDDEN);
 
+    let response = renew_token(&edr, "consumer").await;
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
     consumer
         .transfer_processes()
         .resume(&transfer_id)
@@ -224,6 +232,10 @@ async fn transfer_pull_test_with_suspend_and_resume() {
     // Old one not working
     assert_eq!(response.status(), StatusCode::FORBIDDEN);
 
+    let response = renew_token(&edr, "consumer").await;
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
     let edr = consumer
         .edrs()
         .get_data_address(&transfer_id)
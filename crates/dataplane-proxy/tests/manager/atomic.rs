@@ -0,0 +1,245 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use edc_dataplane_core::core::{
+    db::{
+        sqlite::transfer::SqliteTransferRepo,
+        transfer::{TransferQuery, TransferRepo, TransferRepoRef},
+    },
+    model::{
+        notification::Notification,
+        transfer::{Transfer, TransferStatus},
+    },
+    service::transfer::TransferError,
+};
+use edc_dataplane_proxy::{
+    db::{
+        edr::{EdrRepo, EdrRepoRef},
+        sqlite::edr::SqliteEdrRepo,
+    },
+    model::edr::EdrEntry,
+};
+use uuid::Uuid;
+
+use crate::manager::{assert_status, create_start_message, edr_entry, setup_with, Setup};
+
+/// Failures injected into the wrapped repos
+#[derive(Clone, Default)]
+struct Faults {
+    transfer_save: Arc<AtomicBool>,
+    edr_save: Arc<AtomicBool>,
+    edr_delete: Arc<AtomicBool>,
+    /// The entries saved through the repo, i.e. the ids of the issued tokens
+    saved_edrs: Arc<Mutex<Vec<EdrEntry>>>,
+}
+
+impl Faults {
+    fn fail(flag: &AtomicBool, what: &str) -> anyhow::Result<()> {
+        if flag.load(Ordering::SeqCst) {
+            anyhow::bail!("Injected failure on {}", what)
+        }
+        Ok(())
+    }
+}
+
+struct FaultyTransferRepo {
+    inner: SqliteTransferRepo,
+    faults: Faults,
+}
+
+#[async_trait]
+impl TransferRepo for FaultyTransferRepo {
+    async fn save(&self, transfer: Transfer) -> anyhow::Result<()> {
+        Faults::fail(&self.faults.transfer_save, "transfer save")?;
+        self.inner.save(transfer).await
+    }
+
+    async fn create(&self, transfer: Transfer) -> anyhow::Result<bool> {
+        self.inner.create(transfer).await
+    }
+
+    async fn fetch_by_id(&self, transfer_id: &str) -> anyhow::Result<Option<Transfer>> {
+        self.inner.fetch_by_id(transfer_id).await
+    }
+
+    async fn delete(&self, transfer_id: &str) -> anyhow::Result<()> {
+        self.inner.delete(transfer_id).await
+    }
+
+    async fn query(&self, query: TransferQuery) -> anyhow::Result<Vec<Transfer>> {
+        self.inner.query(query).await
+    }
+
+    async fn change_status(
+        &self,
+        transfer_id: String,
+        status: TransferStatus,
+    ) -> anyhow::Result<()> {
+        self.inner.change_status(transfer_id, status).await
+    }
+
+    async fn transition(
+        &self,
+        transfer: Transfer,
+        from: TransferStatus,
+        notification: Option<Notification>,
+    ) -> anyhow::Result<bool> {
+        Faults::fail(&self.faults.transfer_save, "transfer save")?;
+        self.inner.transition(transfer, from, notification).await
+    }
+}
+
+struct FaultyEdrRepo {
+    inner: SqliteEdrRepo,
+    faults: Faults,
+}
+
+#[async_trait]
+impl EdrRepo for FaultyEdrRepo {
+    async fn save(&self, edr: EdrEntry) -> anyhow::Result<()> {
+        Faults::fail(&self.faults.edr_save, "EDR save")?;
+        self.faults.saved_edrs.lock().unwrap().push(edr.clone());
+        self.inner.save(edr).await
+    }
+
+    async fn fetch_by_id(&self, transfer_id: &str) -> anyhow::Result<Option<EdrEntry>> {
+        self.inner.fetch_by_id(transfer_id).await
+    }
+
+    async fn delete(&self, transfer_id: &str) -> anyhow::Result<()> {
+        Faults::fail(&self.faults.edr_delete, "EDR delete")?;
+        self.inner.delete(transfer_id).await
+    }
+
+    async fn transfer_ids(&self, updated_before: DateTime<Utc>) -> anyhow::Result<Vec<String>> {
+        self.inner.transfer_ids(updated_before).await
+    }
+}
+
+async fn faulty_setup() -> (Setup, Faults) {
+    let faults = Faults::default();
+    let transfer_faults = faults.clone();
+    let edr_faults = faults.clone();
+
+    let setup = setup_with(
+        |inner| {
+            TransferRepoRef::of(FaultyTransferRepo {
+                inner,
+                faults: transfer_faults,
+            })
+        },
+        |inner| {
+            EdrRepoRef::of(FaultyEdrRepo {
+                inner,
+                faults: edr_faults,
+            })
+        },
+    )
+    .await;
+
+    (setup, faults)
+}
+
+#[tokio::test]
+async fn start_leaves_no_trace_when_edr_save_fails() {
+    let (setup, faults) = faulty_setup().await;
+    let id = Uuid::new_v4().to_string();
+
+    faults.edr_save.store(true, Ordering::SeqCst);
+
+    let result = setup.service.start(create_start_message(&id)).await;
+
+    assert!(matches!(result, Err(TransferError::Storage(_))));
+    assert!(setup.service.get(&id).await.unwrap().is_none());
+    assert!(edr_entry(&setup, &id).await.is_none());
+}
+
+#[tokio::test]
+async fn start_removes_edr_when_transfer_save_fails() {
+    let (setup, faults) = faulty_setup().await;
+    let id = Uuid::new_v4().to_string();
+
+    faults.transfer_save.store(true, Ordering::SeqCst);
+
+    let result = setup.service.start(create_start_message(&id)).await;
+
+    assert!(matches!(result, Err(TransferError::Storage(_))));
+    assert_eq!(faults.saved_edrs.lock().unwrap().len(), 1);
+    assert!(setup.service.get(&id).await.unwrap().is_none());
+    assert!(edr_entry(&setup, &id).await.is_none());
+
+    faults.transfer_save.store(false, Ordering::SeqCst);
+
+    setup
+        .service
+        .start(create_start_message(&id))
+        .await
+        .unwrap();
+
+    assert_status(&setup, &id, TransferStatus::Started).await;
+    assert!(edr_entry(&setup, &id).await.is_some());
+}
+
+#[tokio::test]
+async fn resume_revokes_tokens_when_transfer_save_fails() {
+    let (setup, faults) = faulty_setup().await;
+    let id = Uuid::new_v4().to_string();
+
+    setup
+        .service
+        .start(create_start_message(&id))
+        .await
+        .unwrap();
+
+    setup
+        .service
+        .suspend(id.clone(), Some("suspend".to_string()))
+        .await
+        .unwrap();
+
+    faults.transfer_save.store(true, Ordering::SeqCst);
+    let saved = faults.saved_edrs.lock().unwrap().len();
+
+    let result = setup.service.start(create_start_message(&id)).await;
+
+    assert!(matches!(result, Err(TransferError::Storage(_))));
+    assert_status(&setup, &id, TransferStatus::Suspended).await;
+
+    let issued = faults.saved_edrs.lock().unwrap()[saved].clone();
+    let current = edr_entry(&setup, &id).await.unwrap();
+
+    assert_ne!(current.token_id, issued.token_id);
+    assert_ne!(current.refresh_token_id, issued.refresh_token_id);
+}
+
+#[tokio::test]
+async fn start_reports_transfer_failure_when_rollback_fails() {
+    let (setup, faults) = faulty_setup().await;
+    let id = Uuid::new_v4().to_string();
+
+    faults.transfer_save.store(true, Ordering::SeqCst);
+    faults.edr_delete.store(true, Ordering::SeqCst);
+
+    let result = setup.service.start(create_start_message(&id)).await;
+
+    assert!(
+        matches!(result, Err(TransferError::Storage(err)) if err.to_string() == "Injected failure on transfer save")
+    );
+    assert!(setup.service.get(&id).await.unwrap().is_none());
+
+    // The EDR whose deletion failed is left behind without a transfer, until purged
+    let orphan = edr_entry(&setup, &id).await.unwrap();
+    assert_eq!(
+        faults.saved_edrs.lock().unwrap()[0].token_id,
+        orphan.token_id
+    );
+
+    faults.edr_delete.store(false, Ordering::SeqCst);
+
+    assert_eq!(setup.service.purge(50).await.unwrap(), 1);
+    assert!(edr_entry(&setup, &id).await.is_none());
+}
@@ -0,0 +1,231 @@
+use std::net::{IpAddr, Ipv4Addr};
+
+use edc_dataplane_core::{
+    core::model::{
+        namespace::{EDC_NAMESPACE, IDSA_NAMESPACE},
+        transfer::TransferStatus,
+    },
+    signaling::{DataAddress, EndpointProperty},
+    web::{start_server, ServerHandle},
+};
+use edc_dataplane_proxy::{
+    db::consumer::ConsumerEdrRepoRef,
+    extensions::manager::create_consumer_edr_manager,
+    web::{consumer_app, state::ConsumerContext},
+};
+use serde_json::json;
+use uuid::Uuid;
+use wiremock::{
+    matchers::{body_string_contains, header, method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+use crate::manager::{
+    assert_status, consumer_edr, create_prepare_message, proxy_config, setup, Setup,
+};
+
+fn create_edr(provider: &MockServer) -> DataAddress {
+    let property = |name: &str, value: String| {
+        EndpointProperty::builder()
+            .name(EDC_NAMESPACE.to_iri(name))
+            .value(value)
+            .build()
+    };
+
+    DataAddress::builder()
+        .endpoint_type(IDSA_NAMESPACE.to_iri("HTTP"))
+        .endpoint_properties(vec![
+            property("endpoint", format!("{}/public", provider.uri())),
+            property("access_token", "expired".to_string()),
+            property("refresh_token", "refresh".to_string()),
+            property("refresh_endpoint", format!("{}/token", provider.uri())),
+            property("expires_in", "600".to_string()),
+        ])
+        .build()
+}
+
+async fn start_consumer_transfer(setup: &Setup, provider: &MockServer) -> String {
+    let id = Uuid::new_v4().to_string();
+
+    setup
+        .service
+        .prepare(create_prepare_message(&id))
+        .await
+        .unwrap();
+
+    setup
+        .service
+        .started(id.clone(), Some(create_edr(provider)))
+        .await
+        .unwrap();
+
+    id
+}
+
+async fn start_consumer_proxy(setup: &Setup, port: u16) -> ServerHandle {
+    let cfg = proxy_config();
+    let consumer = cfg.consumer.clone().unwrap();
+    let edrs =
+        create_consumer_edr_manager(ConsumerEdrRepoRef::of(setup.consumer_edrs.clone()), &cfg)
+            .unwrap();
+
+    start_server(
+        IpAddr::V4(Ipv4Addr::LOCALHOST),
+        port,
+        consumer_app(consumer.api_key),
+        ConsumerContext::new(setup.service.clone(), edrs),
+        "Consumer proxy",
+    )
+    .await
+    .unwrap()
+}
+
+async fn get(url: String) -> reqwest::Response {
+    reqwest::Client::new()
+        .get(url)
+        .header("x-api-key", "consumer_key")
+        .send()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn started_stores_consumer_edr() {
+    let setup = setup().await;
+    let provider = MockServer::start().await;
+
+    let id = start_consumer_transfer(&setup, &provider).await;
+
+    assert_status(&setup, &id, TransferStatus::Started).await;
+
+    let edr = consumer_edr(&setup, &id).await.unwrap();
+    assert_eq!(edr.endpoint, format!("{}/public", provider.uri()));
+    assert_eq!(edr.access_token, "expired");
+    assert!(edr.is_refreshable());
+
+    setup
+        .service
+        .terminate(id.clone(), Some("terminate".to_string()))
+        .await
+        .unwrap();
+
+    assert!(consumer_edr(&setup, &id).await.is_none());
+}
+
+#[tokio::test]
+async fn consumer_proxy_refreshes_rejected_token() {
+    let setup = setup().await;
+    let provider = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/public/data"))
+        .and(query_param("page", "2"))
+        .and(header("authorization", "rotated"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("data"))
+        .with_priority(1)
+        .expect(1)
+        .mount(&provider)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/public/data"))
+        .respond_with(ResponseTemplate::new(401))
+        .expect(1)
+        .mount(&provider)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/token"))
+        .and(body_string_contains("refresh_token=refresh"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "access_token": "rotated",
+            "refresh_token": "rotated_refresh",
+            "expires_in": "600"
+        })))
+        .expect(1)
+        .mount(&provider)
+        .await;
+
+    let id = start_consumer_transfer(&setup, &provider).await;
+
+    let port = proxy_config().consumer.unwrap().port;
+    let _server = start_consumer_proxy(&setup, port).await;
+
+    let response = get(format!(
+        "http://localhost:{}/api/v1/transfers/{}/data?page=2",
+        port, id
+    ))
+    .await;
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.text().await.unwrap(), "data");
+
+    let edr = consumer_edr(&setup, &id).await.unwrap();
+    assert_eq!(edr.access_token, "rotated");
+    assert_eq!(edr.refresh_token.as_deref(), Some("rotated_refresh"));
+
+    let response = get(format!(
+        "http://localhost:{}/api/v1/transfers/{}/data",
+        port,
+        Uuid::new_v4()
+    ))
+    .await;
+
+    assert_eq!(response.status(), 403);
+
+    let response = reqwest::get(format!(
+        "http://localhost:{}/api/v1/transfers/{}/data",
+        port, id
+    ))
+    .await
+    .unwrap();
+
+    assert_eq!(response.status(), 401);
+
+    let response = get(format!(
+        "http://localhost:{}/api/v1/transfers/{}/%2e%2e%2fadmin",
+        port, id
+    ))
+    .await;
+
+    assert_eq!(response.status(), 400);
+
+    provider.verify().await;
+}
+
+#[tokio::test]
+async fn consumer_proxy_encodes_path_segments() {
+    let setup = setup().await;
+    let provider = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/public/a%3Fb"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("data"))
+        .expect(1)
+        .mount(&provider)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "access_token": "rotated",
+            "expires_in": "600"
+        })))
+        .mount(&provider)
+        .await;
+
+    let id = start_consumer_transfer(&setup, &provider).await;
+
+    let port = proxy_config().consumer.unwrap().port + 1;
+    let _server = start_consumer_proxy(&setup, port).await;
+
+    let response = get(format!(
+        "http://localhost:{}/api/v1/transfers/{}/a%3fb",
+        port, id
+    ))
+    .await;
+
+    assert_eq!(response.status(), 200);
+
+    provider.verify().await;
+}
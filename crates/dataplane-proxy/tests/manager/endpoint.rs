@@ -0,0 +1,123 @@
+use axum::http::Uri;
+use edc_dataplane_core::{
+    core::{
+        db::{sqlite::transfer::SqliteTransferRepo, transfer::TransferRepoRef},
+        model::{
+            namespace::EDC_NAMESPACE,
+            transfer::{
+                types::{DataAddressError, EndpointType},
+                TransferStatus,
+            },
+        },
+        service::{
+            registry::EndpointTypes,
+            transfer::{TransferManagerRef, TransferService},
+        },
+    },
+    signaling::{DataAddress, DataFlowStartMessage, EndpointProperty},
+};
+use edc_dataplane_proxy::{
+    db::{
+        consumer::ConsumerEdrRepoRef,
+        edr::{EdrRepo, EdrRepoRef},
+        sqlite::{consumer::SqliteConsumerEdrRepo, edr::SqliteEdrRepo},
+    },
+    extensions::manager::manager_from_config,
+};
+use uuid::Uuid;
+
+use crate::manager::{create_start_message, proxy_config};
+
+/// Object storage served over HTTP, defined outside of the core crate
+struct BucketType;
+
+impl EndpointType for BucketType {
+    fn name(&self) -> &str {
+        "Bucket"
+    }
+
+    fn validate(&self, address: &DataAddress) -> Vec<DataAddressError> {
+        self.pull_url(address).err().into_iter().collect()
+    }
+
+    fn pull_url(&self, address: &DataAddress) -> Result<Option<Uri>, DataAddressError> {
+        let bucket = address
+            .get_property("bucket")
+            .ok_or_else(|| DataAddressError::MissingProperty("bucket".to_string()))?;
+
+        format!("https://{}.storage.local", bucket)
+            .parse()
+            .map(Some)
+            .map_err(
+                |err: axum::http::uri::InvalidUri| DataAddressError::InvalidProperty {
+                    name: "bucket".to_string(),
+                    reason: err.to_string(),
+                },
+            )
+    }
+}
+
+async fn setup(endpoint_types: EndpointTypes) -> (TransferService, SqliteEdrRepo) {
+    let transfers = SqliteTransferRepo::connect("sqlite::memory:")
+        .await
+        .unwrap();
+    transfers.migrate().await.unwrap();
+
+    let edrs = SqliteEdrRepo::connect("sqlite::memory:").await.unwrap();
+    edrs.migrate().await.unwrap();
+
+    let manager = manager_from_config(
+        proxy_config(),
+        EdrRepoRef::of(edrs.clone()),
+        ConsumerEdrRepoRef::of(SqliteConsumerEdrRepo::new(edrs.pool().clone())),
+    )
+    .unwrap()
+    .with_endpoint_types(endpoint_types);
+
+    let service = TransferService::new(
+        TransferManagerRef::of(manager),
+        TransferRepoRef::of(transfers),
+    );
+
+    (service, edrs)
+}
+
+fn bucket_message(id: &str) -> DataFlowStartMessage {
+    let mut message = create_start_message(id);
+    message.source_data_address = DataAddress::builder()
+        .endpoint_type(EDC_NAMESPACE.to_iri("Bucket"))
+        .endpoint_properties(vec![EndpointProperty::builder()
+            .name("bucket")
+            .value("invoices")
+            .build()])
+        .build();
+    message
+}
+
+#[tokio::test]
+async fn start_with_registered_endpoint_type() {
+    let endpoint_types = EndpointTypes::default();
+    endpoint_types.register(BucketType).unwrap();
+
+    let (service, edrs) = setup(endpoint_types).await;
+    let id = Uuid::new_v4().to_string();
+
+    let response = service.start(bucket_message(&id)).await.unwrap();
+
+    assert!(response.data_address.is_some());
+    assert_eq!(
+        service.get(&id).await.unwrap().unwrap().status,
+        TransferStatus::Started
+    );
+    assert!(edrs.fetch_by_id(&id).await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn start_fails_with_unregistered_endpoint_type() {
+    let (service, edrs) = setup(EndpointTypes::default()).await;
+    let id = Uuid::new_v4().to_string();
+
+    assert!(service.start(bucket_message(&id)).await.is_err());
+    assert!(service.get(&id).await.unwrap().is_none());
+    assert!(edrs.fetch_by_id(&id).await.unwrap().is_none());
+}
@@ -0,0 +1,308 @@
+use std::collections::HashMap;
+
+use edc_dataplane_core::{
+    core::{
+        model::{namespace::EDC_NAMESPACE, transfer::TransferStatus},
+        service::transfer::TransferError,
+    },
+    signaling::DataAddress,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::manager::{
+    assert_status, create_agreement_start_message, create_start_message, create_start_message_with,
+    edr_entry, setup,
+};
+
+#[tokio::test]
+async fn suspend_revokes_tokens() {
+    let setup = setup().await;
+    let id = Uuid::new_v4().to_string();
+
+    setup
+        .service
+        .start(create_start_message(&id))
+        .await
+        .unwrap();
+
+    let issued = edr_entry(&setup, &id).await.unwrap();
+
+    setup
+        .service
+        .suspend(id.clone(), Some("suspend".to_string()))
+        .await
+        .unwrap();
+
+    assert_status(&setup, &id, TransferStatus::Suspended).await;
+
+    let revoked = edr_entry(&setup, &id).await.unwrap();
+
+    assert_ne!(revoked.token_id, issued.token_id);
+    assert_ne!(revoked.refresh_token_id, issued.refresh_token_id);
+}
+
+#[tokio::test]
+async fn terminate_deletes_edr() {
+    let setup = setup().await;
+    let id = Uuid::new_v4().to_string();
+
+    setup
+        .service
+        .start(create_start_message(&id))
+        .await
+        .unwrap();
+
+    assert!(edr_entry(&setup, &id).await.is_some());
+
+    setup
+        .service
+        .terminate(id.clone(), Some("terminate".to_string()))
+        .await
+        .unwrap();
+
+    assert_status(&setup, &id, TransferStatus::Terminated).await;
+    assert!(edr_entry(&setup, &id).await.is_none());
+}
+
+#[tokio::test]
+async fn resume_issues_new_tokens() {
+    let setup = setup().await;
+    let id = Uuid::new_v4().to_string();
+
+    let started = setup
+        .service
+        .start(create_start_message(&id))
+        .await
+        .unwrap();
+    let issued = edr_entry(&setup, &id).await.unwrap();
+
+    setup.service.suspend(id.clone(), None).await.unwrap();
+
+    let revoked = edr_entry(&setup, &id).await.unwrap();
+
+    let resumed = setup
+        .service
+        .start(create_start_message(&id))
+        .await
+        .unwrap();
+
+    assert_status(&setup, &id, TransferStatus::Started).await;
+
+    let reissued = edr_entry(&setup, &id).await.unwrap();
+
+    assert_ne!(reissued.token_id, issued.token_id);
+    assert_ne!(reissued.token_id, revoked.token_id);
+    assert_ne!(reissued.refresh_token_id, issued.refresh_token_id);
+    assert_ne!(reissued.refresh_token_id, revoked.refresh_token_id);
+
+    let access_token = |address: Option<DataAddress>| {
+        address
+            .and_then(|address| {
+                address
+                    .get_property(&EDC_NAMESPACE.to_iri("access_token"))
+                    .map(String::from)
+            })
+            .unwrap()
+    };
+
+    assert_ne!(
+        access_token(started.data_address),
+        access_token(resumed.data_address)
+    );
+}
+
+#[tokio::test]
+async fn resume_fails_when_terminated() {
+    let setup = setup().await;
+    let id = Uuid::new_v4().to_string();
+
+    setup
+        .service
+        .start(create_start_message(&id))
+        .await
+        .unwrap();
+    setup.service.terminate(id.clone(), None).await.unwrap();
+
+    let result = setup.service.start(create_start_message(&id)).await;
+
+    assert!(matches!(
+        result,
+        Err(TransferError::IllegalTransition {
+            from: TransferStatus::Terminated,
+            to: TransferStatus::Started,
+            ..
+        })
+    ));
+    assert!(edr_entry(&setup, &id).await.is_none());
+}
+
+#[tokio::test]
+async fn start_fails_with_unsupported_endpoint_type() {
+    let setup = setup().await;
+    let id = Uuid::new_v4().to_string();
+
+    let mut message = create_start_message(&id);
+    message.source_data_address.endpoint_type = "Kafka".to_string();
+
+    let result = setup.service.start(message).await;
+
+    assert!(matches!(
+        result,
+        Err(TransferError::UnsupportedEndpointType(kind)) if kind == "Kafka"
+    ));
+    assert!(setup.service.get(&id).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn repeated_start_keeps_issued_tokens() {
+    let setup = setup().await;
+    let id = Uuid::new_v4().to_string();
+
+    let started = setup
+        .service
+        .start(create_start_message(&id))
+        .await
+        .unwrap();
+    let issued = edr_entry(&setup, &id).await.unwrap();
+
+    let repeated = setup
+        .service
+        .start(create_start_message(&id))
+        .await
+        .unwrap();
+
+    assert_status(&setup, &id, TransferStatus::Started).await;
+    assert_eq!(edr_entry(&setup, &id).await.unwrap(), issued);
+
+    let endpoint = |address: &Option<DataAddress>| {
+        address
+            .as_ref()
+            .and_then(|address| address.get_property("endpoint").map(String::from))
+    };
+
+    assert!(repeated.data_address.is_some());
+    assert_eq!(
+        endpoint(&started.data_address),
+        endpoint(&repeated.data_address)
+    );
+}
+
+#[tokio::test]
+async fn repeated_start_fails_with_conflicting_payload() {
+    let setup = setup().await;
+    let id = Uuid::new_v4().to_string();
+
+    setup
+        .service
+        .start(create_start_message(&id))
+        .await
+        .unwrap();
+    let issued = edr_entry(&setup, &id).await.unwrap();
+
+    let mut message = create_start_message(&id);
+    message.participant_id = "other_participant".to_string();
+
+    let result = setup.service.start(message).await;
+
+    assert!(matches!(result, Err(TransferError::Conflict(conflict)) if conflict == id));
+    assert_eq!(edr_entry(&setup, &id).await.unwrap(), issued);
+}
+
+#[tokio::test]
+async fn start_redacts_secret_properties() {
+    let setup = setup().await;
+    let id = Uuid::new_v4().to_string();
+
+    let message = create_start_message_with(
+        &id,
+        HashMap::from([
+            ("authorization".to_string(), json!("Bearer token")),
+            (EDC_NAMESPACE.to_iri("apiKey"), json!("key")),
+            ("region".to_string(), json!("eu")),
+        ]),
+    );
+
+    setup.service.start(message.clone()).await.unwrap();
+    // A retry of the same message is not taken for a conflicting one
+    setup.service.start(message).await.unwrap();
+
+    let transfer = setup.service.get(&id).await.unwrap().unwrap();
+    let properties = transfer.properties.unwrap().0;
+
+    assert_eq!(properties["authorization"], json!("***"));
+    assert_eq!(properties[&EDC_NAMESPACE.to_iri("apiKey")], json!("***"));
+    assert_eq!(properties["region"], json!("eu"));
+}
+
+#[tokio::test]
+async fn terminate_agreement_terminates_its_live_transfers() {
+    let setup = setup().await;
+
+    let mut revoked = vec![];
+    for _ in 0..3 {
+        let id = Uuid::new_v4().to_string();
+        setup
+            .service
+            .start(create_agreement_start_message(
+                &id,
+                "revoked",
+                HashMap::new(),
+            ))
+            .await
+            .unwrap();
+        revoked.push(id);
+    }
+
+    setup
+        .service
+        .suspend(revoked[1].clone(), Some("suspend".to_string()))
+        .await
+        .unwrap();
+
+    let other = Uuid::new_v4().to_string();
+    setup
+        .service
+        .start(create_start_message(&other))
+        .await
+        .unwrap();
+
+    let terminated = setup
+        .service
+        .terminate_agreement("revoked", Some("Agreement revoked".to_string()))
+        .await
+        .unwrap();
+
+    assert_eq!(terminated, 3);
+
+    for id in &revoked {
+        let transfer = setup.service.get(id).await.unwrap().unwrap();
+        assert_eq!(transfer.status, TransferStatus::Terminated);
+        assert_eq!(
+            transfer.termination_reason.as_deref(),
+            Some("Agreement revoked")
+        );
+        assert!(edr_entry(&setup, id).await.is_none());
+    }
+    assert_status(&setup, &other, TransferStatus::Started).await;
+}
+
+#[tokio::test]
+async fn started_transfer_exposes_edr_without_tokens() {
+    let setup = setup().await;
+    let id = Uuid::new_v4().to_string();
+
+    setup
+        .service
+        .start(create_start_message(&id))
+        .await
+        .unwrap();
+
+    let issued = edr_entry(&setup, &id).await.unwrap();
+    let transfer = setup.service.get(&id).await.unwrap().unwrap();
+    let edr = setup.service.edr(&transfer).await.unwrap().unwrap();
+
+    assert_eq!(edr.token_id, Some(Uuid::from(issued.token_id).to_string()));
+    assert!(edr.expires_at.is_some());
+    assert!(edr.refreshable);
+}
@@ -0,0 +1,156 @@
+use ed25519_compact::{KeyPair, Seed};
+use edc_dataplane_core::{
+    core::{
+        db::{sqlite::transfer::SqliteTransferRepo, transfer::TransferRepoRef},
+        model::{namespace::EDC_NAMESPACE, transfer::TransferStatus},
+        service::transfer::{TransferManagerRef, TransferService},
+    },
+    signaling::{
+        DataAddress, DataFlowPrepareMessage, DataFlowStartMessage, EndpointProperty, FlowType,
+    },
+};
+use edc_dataplane_proxy::{
+    db::{
+        consumer::{ConsumerEdrRepo, ConsumerEdrRepoRef},
+        edr::{EdrRepo, EdrRepoRef},
+        sqlite::{consumer::SqliteConsumerEdrRepo, edr::SqliteEdrRepo},
+    },
+    extensions::{manager::manager_from_config, Proxy},
+    model::{consumer::ConsumerEdr, edr::EdrEntry},
+};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+mod atomic;
+mod consumer;
+mod endpoint;
+mod lifecycle;
+mod reaper;
+
+pub struct Setup {
+    pub service: TransferService,
+    pub transfers: SqliteTransferRepo,
+    pub edrs: SqliteEdrRepo,
+    pub consumer_edrs: SqliteConsumerEdrRepo,
+}
+
+pub async fn setup() -> Setup {
+    setup_with(TransferRepoRef::of, EdrRepoRef::of).await
+}
+
+/// Builds the setup with the repos wrapped, e.g. for injecting failures
+pub async fn setup_with(
+    wrap_transfers: impl FnOnce(SqliteTransferRepo) -> TransferRepoRef,
+    wrap_edrs: impl FnOnce(SqliteEdrRepo) -> EdrRepoRef,
+) -> Setup {
+    let transfers = SqliteTransferRepo::connect("sqlite::memory:")
+        .await
+        .unwrap();
+    transfers.migrate().await.unwrap();
+
+    let edrs = SqliteEdrRepo::connect("sqlite::memory:").await.unwrap();
+    edrs.migrate().await.unwrap();
+
+    let consumer_edrs = SqliteConsumerEdrRepo::new(edrs.pool().clone());
+
+    let manager = manager_from_config(
+        proxy_config(),
+        wrap_edrs(edrs.clone()),
+        ConsumerEdrRepoRef::of(consumer_edrs.clone()),
+    )
+    .unwrap();
+
+    let service = TransferService::new(
+        TransferManagerRef::of(manager),
+        wrap_transfers(transfers.clone()),
+    );
+
+    Setup {
+        service,
+        transfers,
+        edrs,
+        consumer_edrs,
+    }
+}
+
+pub fn proxy_config() -> Proxy {
+    let key_pair = KeyPair::from_seed(Seed::default());
+
+    serde_json::from_value(json!({
+        "issuer": "issuer",
+        "purge_grace": 0,
+        "keys": {
+            "kid": "kid",
+            "algorithm": "EdDSA",
+            "format": "Pem",
+            "private_key": key_pair.sk.to_pem(),
+            "public_key": key_pair.pk.to_pem(),
+        },
+        "consumer": {
+            "port": 18790,
+            "api_key": {
+                "key": "consumer_key"
+            }
+        }
+    }))
+    .unwrap()
+}
+
+pub fn create_start_message(id: &str) -> DataFlowStartMessage {
+    create_start_message_with(id, HashMap::new())
+}
+
+pub fn create_start_message_with(
+    id: &str,
+    properties: HashMap<String, Value>,
+) -> DataFlowStartMessage {
+    create_agreement_start_message(id, "agreement_id", properties)
+}
+
+pub fn create_agreement_start_message(
+    id: &str,
+    agreement_id: &str,
+    properties: HashMap<String, Value>,
+) -> DataFlowStartMessage {
+    DataFlowStartMessage::builder()
+        .participant_id("participant_id".to_string())
+        .process_id(id.to_string())
+        .source_data_address(
+            DataAddress::builder()
+                .endpoint_type("HttpData".to_string())
+                .endpoint_properties(vec![EndpointProperty::builder()
+                    .name(EDC_NAMESPACE.to_iri("baseUrl"))
+                    .value("http://localhost:8080")
+                    .build()])
+                .build(),
+        )
+        .properties(properties)
+        .flow_type(FlowType::Pull)
+        .dataset_id("dataset_id".to_string())
+        .agreement_id(agreement_id.to_string())
+        .build()
+}
+
+pub fn create_prepare_message(id: &str) -> DataFlowPrepareMessage {
+    DataFlowPrepareMessage::builder()
+        .participant_id("participant_id".to_string())
+        .process_id(id.to_string())
+        .properties(HashMap::new())
+        .flow_type(FlowType::Pull)
+        .dataset_id("dataset_id".to_string())
+        .agreement_id("agreement_id".to_string())
+        .build()
+}
+
+pub async fn assert_status(setup: &Setup, id: &str, status: TransferStatus) {
+    let transfer = setup.service.get(id).await.unwrap().unwrap();
+    assert_eq!(transfer.status, status);
+}
+
+pub async fn edr_entry(setup: &Setup, id: &str) -> Option<EdrEntry> {
+    setup.edrs.fetch_by_id(id).await.unwrap()
+}
+
+pub async fn consumer_edr(setup: &Setup, id: &str) -> Option<ConsumerEdr> {
+    setup.consumer_edrs.fetch_by_id(id).await.unwrap()
+}
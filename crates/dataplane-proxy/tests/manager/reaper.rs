@@ -0,0 +1,235 @@
+use std::collections::HashMap;
+
+use chrono::{Duration, Utc};
+use edc_dataplane_core::core::{
+    db::transfer::{TransferRepo, TransferRepoRef},
+    model::transfer::TransferStatus,
+    service::{
+        reaper::TransferReaper,
+        transfer::{TransferManagerRef, TransferService},
+    },
+};
+use edc_dataplane_proxy::{
+    db::{
+        consumer::ConsumerEdrRepoRef,
+        edr::{EdrRepo, EdrRepoRef},
+    },
+    extensions::manager::manager_from_config,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::manager::{
+    assert_status, create_start_message, create_start_message_with, edr_entry, proxy_config, setup,
+    Setup,
+};
+
+fn create_reaper(setup: &Setup, max_age: Option<Duration>) -> TransferReaper {
+    TransferReaper::builder()
+        .transfers(setup.service.clone())
+        .maybe_max_age(max_age)
+        .retention(Duration::days(1))
+        .build()
+}
+
+async fn start(setup: &Setup) -> String {
+    let id = Uuid::new_v4().to_string();
+    setup
+        .service
+        .start(create_start_message(&id))
+        .await
+        .unwrap();
+    id
+}
+
+/// Moves the timestamps of the transfer back in time
+async fn age(setup: &Setup, id: &str, by: Duration) {
+    let transfer = setup.transfers.fetch_by_id(id).await.unwrap().unwrap();
+
+    sqlx::query("UPDATE transfers SET created_at = $1, updated_at = $2 WHERE id = $3")
+        .bind(transfer.created_at - by)
+        .bind(transfer.updated_at - by)
+        .bind(id)
+        .execute(setup.transfers.pool())
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn reaper_terminates_transfers_past_max_age() {
+    let setup = setup().await;
+
+    let expired = start(&setup).await;
+    let recent = start(&setup).await;
+
+    age(&setup, &expired, Duration::hours(2)).await;
+
+    let report = create_reaper(&setup, Some(Duration::hours(1)))
+        .run()
+        .await
+        .unwrap();
+
+    assert_eq!(report.terminated, 1);
+    assert_status(&setup, &expired, TransferStatus::Terminated).await;
+    assert_status(&setup, &recent, TransferStatus::Started).await;
+    assert!(edr_entry(&setup, &expired).await.is_none());
+    assert!(edr_entry(&setup, &recent).await.is_some());
+}
+
+#[tokio::test]
+async fn reaper_terminates_transfers_past_agreement_end() {
+    let setup = setup().await;
+
+    let start_with_end = |end: chrono::DateTime<Utc>| {
+        let id = Uuid::new_v4().to_string();
+        let message = create_start_message_with(
+            &id,
+            HashMap::from([("agreementEndDate".to_string(), json!(end.timestamp()))]),
+        );
+        (id, message)
+    };
+
+    let (ended, message) = start_with_end(Utc::now() - Duration::minutes(1));
+    setup.service.start(message).await.unwrap();
+
+    let (running, message) = start_with_end(Utc::now() + Duration::days(1));
+    setup.service.start(message).await.unwrap();
+
+    let report = create_reaper(&setup, None).run().await.unwrap();
+
+    assert_eq!(report.terminated, 1);
+
+    let transfer = setup.service.get(&ended).await.unwrap().unwrap();
+    assert_eq!(transfer.status, TransferStatus::Terminated);
+    assert_eq!(
+        transfer.termination_reason.as_deref(),
+        Some("Contract agreement ended")
+    );
+    assert_status(&setup, &running, TransferStatus::Started).await;
+}
+
+#[tokio::test]
+async fn reaper_deletes_final_transfers_past_retention() {
+    let setup = setup().await;
+
+    let old = start(&setup).await;
+    let fresh = start(&setup).await;
+
+    for id in [&old, &fresh] {
+        setup
+            .service
+            .terminate(id.clone(), Some("terminate".to_string()))
+            .await
+            .unwrap();
+    }
+
+    age(&setup, &old, Duration::days(2)).await;
+
+    let report = create_reaper(&setup, None).run().await.unwrap();
+
+    assert_eq!(report.deleted, 1);
+    assert!(setup.service.get(&old).await.unwrap().is_none());
+    assert_status(&setup, &fresh, TransferStatus::Terminated).await;
+}
+
+#[tokio::test]
+async fn reaper_keeps_final_transfers_without_retention() {
+    let setup = setup().await;
+
+    let old = start(&setup).await;
+    setup
+        .service
+        .terminate(old.clone(), Some("terminate".to_string()))
+        .await
+        .unwrap();
+
+    age(&setup, &old, Duration::days(365)).await;
+
+    let report = TransferReaper::builder()
+        .transfers(setup.service.clone())
+        .build()
+        .run()
+        .await
+        .unwrap();
+
+    assert_eq!(report.deleted, 0);
+    assert_status(&setup, &old, TransferStatus::Terminated).await;
+}
+
+#[tokio::test]
+async fn reaper_purges_orphaned_edrs() {
+    let setup = setup().await;
+
+    let live = start(&setup).await;
+    let orphan = start(&setup).await;
+
+    setup.transfers.delete(&orphan).await.unwrap();
+
+    let mut terminated = edr_entry(&setup, &live).await.unwrap();
+    terminated.transfer_id = Uuid::new_v4().to_string();
+    setup.edrs.save(terminated).await.unwrap();
+
+    let report = create_reaper(&setup, None).run().await.unwrap();
+
+    assert_eq!(report.purged, 2);
+    assert_eq!(report.terminated, 0);
+    assert!(edr_entry(&setup, &orphan).await.is_none());
+    assert!(edr_entry(&setup, &live).await.is_some());
+}
+
+#[tokio::test]
+async fn purge_looks_up_transfers_by_page() {
+    let setup = setup().await;
+
+    let live = [
+        start(&setup).await,
+        start(&setup).await,
+        start(&setup).await,
+    ];
+    let orphan = start(&setup).await;
+    let terminated = start(&setup).await;
+
+    setup.transfers.delete(&orphan).await.unwrap();
+
+    let entry = edr_entry(&setup, &terminated).await.unwrap();
+    setup
+        .service
+        .terminate(terminated.clone(), Some("terminate".to_string()))
+        .await
+        .unwrap();
+    setup.edrs.save(entry).await.unwrap();
+
+    assert_eq!(setup.service.purge(2).await.unwrap(), 2);
+    assert!(edr_entry(&setup, &orphan).await.is_none());
+    assert!(edr_entry(&setup, &terminated).await.is_none());
+    for id in &live {
+        assert!(edr_entry(&setup, id).await.is_some());
+    }
+}
+
+#[tokio::test]
+async fn purge_keeps_recent_edrs() {
+    let setup = setup().await;
+
+    let mut cfg = proxy_config();
+    cfg.purge_grace = 3600;
+
+    let manager = manager_from_config(
+        cfg,
+        EdrRepoRef::of(setup.edrs.clone()),
+        ConsumerEdrRepoRef::of(setup.consumer_edrs.clone()),
+    )
+    .unwrap();
+
+    let service = TransferService::new(
+        TransferManagerRef::of(manager),
+        TransferRepoRef::of(setup.transfers.clone()),
+    );
+
+    // An entry whose transfer is not saved yet, as during a start
+    let orphan = start(&setup).await;
+    setup.transfers.delete(&orphan).await.unwrap();
+
+    assert_eq!(service.purge(50).await.unwrap(), 0);
+    assert!(edr_entry(&setup, &orphan).await.is_some());
+}
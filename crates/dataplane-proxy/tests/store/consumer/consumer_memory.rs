@@ -0,0 +1,19 @@
+use async_trait::async_trait;
+use edc_dataplane_proxy::db::memory::consumer::InMemoryConsumerEdrRepo;
+
+use crate::{generate_consumer_edr_store_tests, store::Tester};
+
+pub struct MemoryTester(InMemoryConsumerEdrRepo);
+
+#[async_trait]
+impl Tester<InMemoryConsumerEdrRepo> for MemoryTester {
+    async fn create() -> Self {
+        MemoryTester(InMemoryConsumerEdrRepo::new())
+    }
+
+    fn store(&self) -> &InMemoryConsumerEdrRepo {
+        &self.0
+    }
+}
+
+generate_consumer_edr_store_tests!(MemoryTester);
@@ -0,0 +1,27 @@
+use async_trait::async_trait;
+use edc_dataplane_proxy::db::postgres::{consumer::PgConsumerEdrRepo, edr::PgEdrRepo};
+
+use crate::{
+    generate_consumer_edr_store_tests,
+    store::{create_pg_database, Tester},
+};
+
+pub struct PgTester(PgConsumerEdrRepo);
+
+#[async_trait]
+impl Tester<PgConsumerEdrRepo> for PgTester {
+    async fn create() -> Self {
+        let store = PgEdrRepo::connect(&create_pg_database().await)
+            .await
+            .unwrap();
+
+        store.migrate().await.unwrap();
+        PgTester(PgConsumerEdrRepo::new(store.pool().clone()))
+    }
+
+    fn store(&self) -> &PgConsumerEdrRepo {
+        &self.0
+    }
+}
+
+generate_consumer_edr_store_tests!(PgTester);
@@ -0,0 +1,22 @@
+use async_trait::async_trait;
+use edc_dataplane_proxy::db::sqlite::{consumer::SqliteConsumerEdrRepo, edr::SqliteEdrRepo};
+
+use crate::{generate_consumer_edr_store_tests, store::Tester};
+
+pub struct SqliteTester(SqliteConsumerEdrRepo);
+
+#[async_trait]
+impl Tester<SqliteConsumerEdrRepo> for SqliteTester {
+    async fn create() -> Self {
+        let store = SqliteEdrRepo::connect("sqlite::memory:").await.unwrap();
+
+        store.migrate().await.unwrap();
+        SqliteTester(SqliteConsumerEdrRepo::new(store.pool().clone()))
+    }
+
+    fn store(&self) -> &SqliteConsumerEdrRepo {
+        &self.0
+    }
+}
+
+generate_consumer_edr_store_tests!(SqliteTester);
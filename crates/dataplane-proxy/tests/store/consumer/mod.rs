@@ -0,0 +1,129 @@
+use crate::store::Tester;
+use chrono::{Duration, SubsecRound, Utc};
+use edc_dataplane_proxy::{db::consumer::ConsumerEdrRepo, model::consumer::ConsumerEdr};
+use uuid::Uuid;
+
+mod consumer_memory;
+mod consumer_postgres;
+mod consumer_sqlite;
+
+pub fn create_edr(id: &str) -> ConsumerEdr {
+    // Postgres stores timestamps with microseconds precision
+    let now = Utc::now().trunc_subsecs(6);
+
+    ConsumerEdr::builder()
+        .transfer_id(id)
+        .endpoint("http://provider/public")
+        .access_token("access")
+        .refresh_token("refresh")
+        .refresh_endpoint("http://provider/token")
+        .expires_at(now + Duration::minutes(10))
+        .updated_at(now)
+        .build()
+}
+
+pub async fn save<T: ConsumerEdrRepo>(tester: impl Tester<T>) {
+    let store = tester.store();
+
+    let edr = create_edr(&Uuid::new_v4().to_string());
+
+    store.save(edr.clone()).await.unwrap();
+
+    let saved = store.fetch_by_id(&edr.transfer_id).await.unwrap().unwrap();
+
+    assert_eq!(saved, edr);
+}
+
+pub async fn save_without_refresh<T: ConsumerEdrRepo>(tester: impl Tester<T>) {
+    let store = tester.store();
+
+    let mut edr = create_edr(&Uuid::new_v4().to_string());
+    edr.refresh_token = None;
+    edr.refresh_endpoint = None;
+    edr.expires_at = None;
+
+    store.save(edr.clone()).await.unwrap();
+
+    let saved = store.fetch_by_id(&edr.transfer_id).await.unwrap().unwrap();
+
+    assert_eq!(saved, edr);
+}
+
+pub async fn update<T: ConsumerEdrRepo>(tester: impl Tester<T>) {
+    let store = tester.store();
+
+    let edr = create_edr(&Uuid::new_v4().to_string());
+    let mut rotated = edr.clone();
+
+    rotated.access_token = "rotated".to_string();
+    rotated.refresh_token = Some("rotated_refresh".to_string());
+    rotated.expires_at = rotated.expires_at.map(|at| at + Duration::minutes(10));
+    rotated.updated_at += Duration::seconds(1);
+
+    store.save(edr).await.unwrap();
+    store.save(rotated.clone()).await.unwrap();
+
+    let saved = store
+        .fetch_by_id(&rotated.transfer_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(saved, rotated);
+}
+
+pub async fn delete<T: ConsumerEdrRepo>(tester: impl Tester<T>) {
+    let store = tester.store();
+
+    let edr = create_edr(&Uuid::new_v4().to_string());
+    let edr_2 = create_edr(&Uuid::new_v4().to_string());
+
+    store.save(edr.clone()).await.unwrap();
+    store.save(edr_2.clone()).await.unwrap();
+
+    store.delete(&edr_2.transfer_id).await.unwrap();
+
+    assert!(store
+        .fetch_by_id(&edr_2.transfer_id)
+        .await
+        .unwrap()
+        .is_none());
+    assert!(store.fetch_by_id(&edr.transfer_id).await.unwrap().is_some());
+}
+
+pub async fn transfer_ids<T: ConsumerEdrRepo>(tester: impl Tester<T>) {
+    let store = tester.store();
+
+    store.save(create_edr("1")).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    let updated_before = Utc::now();
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    store.save(create_edr("2")).await.unwrap();
+
+    assert_eq!(store.transfer_ids(updated_before).await.unwrap(), vec!["1"]);
+
+    let mut ids = store.transfer_ids(Utc::now()).await.unwrap();
+    ids.sort();
+
+    assert_eq!(ids, vec!["1", "2"]);
+}
+
+#[macro_export]
+macro_rules! generate_consumer_edr_store_tests {
+    ($tester:ident) => {
+        macro_rules! test {
+            ($title: ident, $func: path) => {
+                $crate::declare_test_fn!($tester, $title, $func);
+            };
+        }
+
+        test!(save, $crate::store::consumer::save);
+        test!(
+            save_without_refresh,
+            $crate::store::consumer::save_without_refresh
+        );
+        test!(update, $crate::store::consumer::update);
+        test!(delete, $crate::store::consumer::delete);
+        test!(transfer_ids, $crate::store::consumer::transfer_ids);
+    };
+}
@@ -0,0 +1,19 @@
+use async_trait::async_trait;
+use edc_dataplane_proxy::db::memory::edr::InMemoryEdrRepo;
+
+use crate::{generate_token_store_tests, store::Tester};
+
+pub struct MemoryTester(InMemoryEdrRepo);
+
+#[async_trait]
+impl Tester<InMemoryEdrRepo> for MemoryTester {
+    async fn create() -> Self {
+        MemoryTester(InMemoryEdrRepo::new())
+    }
+
+    fn store(&self) -> &InMemoryEdrRepo {
+        &self.0
+    }
+}
+
+generate_token_store_tests!(MemoryTester);
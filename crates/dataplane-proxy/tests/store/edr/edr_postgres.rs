@@ -0,0 +1,27 @@
+use async_trait::async_trait;
+use edc_dataplane_proxy::db::postgres::edr::PgEdrRepo;
+
+use crate::{
+    generate_token_store_tests,
+    store::{create_pg_database, Tester},
+};
+
+pub struct PgTester(PgEdrRepo);
+
+#[async_trait]
+impl Tester<PgEdrRepo> for PgTester {
+    async fn create() -> Self {
+        let store = PgEdrRepo::connect(&create_pg_database().await)
+            .await
+            .unwrap();
+
+        store.migrate().await.unwrap();
+        PgTester(store)
+    }
+
+    fn store(&self) -> &PgEdrRepo {
+        &self.0
+    }
+}
+
+generate_token_store_tests!(PgTester);
@@ -1,8 +1,11 @@
 use crate::store::Tester;
+use chrono::Utc;
 use edc_dataplane_proxy::db::edr::EdrRepo;
 use edc_dataplane_proxy::model::edr::EdrEntry;
 use uuid::Uuid;
 
+mod edr_memory;
+mod edr_postgres;
 mod edr_sqlite;
 
 pub fn create_token(id: &str) -> EdrEntry {
@@ -60,17 +63,35 @@ pub async fn delete<T: EdrRepo>(tester: impl Tester<T>) {
     assert!(transfers.is_none());
 }
 
+pub async fn transfer_ids<T: EdrRepo>(tester: impl Tester<T>) {
+    let store = tester.store();
+
+    store.save(create_token("1")).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    let updated_before = Utc::now();
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    store.save(create_token("2")).await.unwrap();
+
+    assert_eq!(store.transfer_ids(updated_before).await.unwrap(), vec!["1"]);
+
+    let mut ids = store.transfer_ids(Utc::now()).await.unwrap();
+    ids.sort();
+
+    assert_eq!(ids, vec!["1", "2"]);
+}
+
 #[macro_export]
 macro_rules! generate_token_store_tests {
     ($tester:ident) => {
         macro_rules! test {
             ($title: ident, $func: path) => {
-                crate::declare_test_fn!($tester, $title, $func);
+                $crate::declare_test_fn!($tester, $title, $func);
             };
         }
 
-        test!(save, crate::store::edr::save);
-        test!(update, crate::store::edr::update);
-        test!(delete, crate::store::edr::delete);
+        test!(save, $crate::store::edr::save);
+        test!(update, $crate::store::edr::update);
+        test!(delete, $crate::store::edr::delete);
+        test!(transfer_ids, $crate::store::edr::transfer_ids);
     };
 }
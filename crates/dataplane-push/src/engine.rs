@@ -0,0 +1,198 @@
+use edc_dataplane_core::{
+    core::model::{
+        namespace::EDC_NAMESPACE,
+        transfer::types::{
+            DataAddressError, EndpointType, FileData, FileType, HttpData, HttpDataType,
+        },
+    },
+    signaling::DataAddress,
+};
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::Context;
+use futures::TryStreamExt;
+use reqwest::{header::CONTENT_TYPE, Body, Client, Method, Response};
+use tokio::{fs::File, io::AsyncWriteExt};
+use uuid::Uuid;
+
+pub enum Source {
+    Http(HttpData),
+}
+
+pub enum Sink {
+    Http(HttpSink),
+    File(FileData),
+}
+
+pub struct HttpSink {
+    pub url: String,
+    pub method: Method,
+}
+
+impl TryFrom<&DataAddress> for Source {
+    type Error = DataAddressError;
+
+    fn try_from(value: &DataAddress) -> Result<Self, Self::Error> {
+        if value.is_type(HttpDataType.name()) {
+            Ok(Source::Http(HttpData::try_from(value)?))
+        } else {
+            Err(DataAddressError::UnsupportedType(
+                value.endpoint_type.clone(),
+            ))
+        }
+    }
+}
+
+impl TryFrom<&DataAddress> for Sink {
+    type Error = DataAddressError;
+
+    fn try_from(value: &DataAddress) -> Result<Self, Self::Error> {
+        if value.is_type(HttpDataType.name()) {
+            let data = HttpData::try_from(value)?;
+            Ok(Sink::Http(HttpSink {
+                url: data.base_url.to_string(),
+                method: value
+                    .get_property(&EDC_NAMESPACE.to_iri("method"))
+                    .map(|method| method.parse::<Method>())
+                    .unwrap_or(Ok(Method::POST))
+                    .map_err(|err| DataAddressError::InvalidProperty {
+                        name: "method".to_string(),
+                        reason: err.to_string(),
+                    })?,
+            }))
+        } else if value.is_type(FileType.name()) {
+            Ok(Sink::File(FileData::try_from(value)?))
+        } else {
+            Err(DataAddressError::UnsupportedType(
+                value.endpoint_type.clone(),
+            ))
+        }
+    }
+}
+
+/// Streams the content of a source into a sink without buffering it in memory.
+#[derive(Clone, Default)]
+pub struct PushEngine {
+    client: Client,
+    file_root: Option<PathBuf>,
+}
+
+impl PushEngine {
+    pub fn new(client: Client) -> Self {
+        Self {
+            client,
+            file_root: None,
+        }
+    }
+
+    /// Confines the file sinks to `root`, they are rejected when no root is configured
+    pub fn with_file_root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.file_root = Some(root.into());
+        self
+    }
+
+    /// Resolves the path of a file sink, which must be relative to the file root
+    pub fn file_path(&self, path: &Path) -> Result<PathBuf, DataAddressError> {
+        let invalid = |reason: &str| DataAddressError::InvalidProperty {
+            name: "path".to_string(),
+            reason: reason.to_string(),
+        };
+
+        let root = self
+            .file_root
+            .as_ref()
+            .ok_or_else(|| invalid("file sinks are disabled, no file root is configured"))?;
+
+        if !path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+        {
+            return Err(invalid("must be relative and must not contain '..'"));
+        }
+
+        if path.file_name().is_none() {
+            return Err(invalid("must name a file"));
+        }
+
+        Ok(root.join(path))
+    }
+
+    pub async fn transfer(&self, source: &Source, sink: &Sink) -> anyhow::Result<()> {
+        let Source::Http(source) = source;
+
+        let response = self
+            .client
+            .get(source.base_url.to_string())
+            .send()
+            .await?
+            .error_for_status()?;
+
+        match sink {
+            Sink::Http(sink) => {
+                let mut request = self.client.request(sink.method.clone(), &sink.url);
+
+                if let Some(content_type) = response.headers().get(CONTENT_TYPE) {
+                    request = request.header(CONTENT_TYPE, content_type.clone());
+                }
+
+                request
+                    .body(Body::wrap_stream(response.bytes_stream()))
+                    .send()
+                    .await?
+                    .error_for_status()?;
+            }
+            Sink::File(sink) => self.write_file(&sink.path, response).await?,
+        }
+
+        Ok(())
+    }
+
+    /// Writes the response next to `path` and renames it once complete, so that a failed or
+    /// cancelled push never leaves a partial file behind
+    async fn write_file(&self, path: &Path, response: Response) -> anyhow::Result<()> {
+        let root = self
+            .file_root
+            .as_ref()
+            .context("No file root is configured")?;
+        let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+            anyhow::bail!("Invalid file path {}", path.display());
+        };
+
+        tokio::fs::create_dir_all(parent).await?;
+
+        // Symbolic links could still lead outside of the root
+        let root = tokio::fs::canonicalize(root).await?;
+        let parent = tokio::fs::canonicalize(parent).await?;
+        if !parent.starts_with(&root) {
+            anyhow::bail!("File path {} is outside of the file root", path.display());
+        }
+
+        let part = PartFile(parent.join(format!(
+            ".{}.{}.part",
+            name.to_string_lossy(),
+            Uuid::new_v4()
+        )));
+
+        let mut file = File::create(&part.0).await?;
+        let mut stream = response.bytes_stream();
+
+        while let Some(chunk) = stream.try_next().await? {
+            file.write_all(&chunk).await?;
+        }
+
+        file.sync_all().await?;
+        tokio::fs::rename(&part.0, parent.join(name)).await?;
+
+        Ok(())
+    }
+}
+
+/// Removes the temporary file of a push that did not complete, including an aborted one
+struct PartFile(PathBuf);
+
+impl Drop for PartFile {
+    fn drop(&mut self) {
+        // Already renamed when the push completed
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
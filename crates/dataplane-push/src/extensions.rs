@@ -0,0 +1,3 @@
+pub mod manager;
+
+pub use manager::transfer_push_extension;
@@ -0,0 +1,62 @@
+use std::path::PathBuf;
+
+use edc_dataplane_core::{
+    core::{
+        db::transfer::TransferRepoRef,
+        service::{
+            notification::NotificationService, registry::TransferManagers,
+            transfer::TransferManagerRef,
+        },
+    },
+    extensions::config::OptionalConfig,
+};
+use miwa::{
+    core::{Extension, MiwaContext, MiwaResult},
+    derive::{extension, ExtensionConfig},
+};
+use serde::Deserialize;
+
+use crate::{engine::PushEngine, manager::TransferPushManager};
+
+pub struct TransferPushExtension(TransferPushManager);
+
+#[async_trait::async_trait]
+impl Extension for TransferPushExtension {
+    async fn start(&self) -> MiwaResult<()> {
+        Ok(())
+    }
+
+    async fn shutdown(&self) -> MiwaResult<()> {
+        self.0.shutdown();
+        Ok(())
+    }
+}
+
+/// Transfer types handled by the push engine, named after their sink
+pub const TRANSFER_TYPES: [&str; 2] = ["HttpData-PUSH", "File-PUSH"];
+
+/// Push transfers. The section is optional, file sinks are disabled without a `file_root`
+#[derive(Deserialize, ExtensionConfig, Clone, Default)]
+#[config(prefix = "push")]
+pub struct PushConfig {
+    /// Directory the paths of the file sinks are relative to
+    pub file_root: Option<PathBuf>,
+}
+
+#[extension(name = "Transfer Push manager extension")]
+pub async fn transfer_push_extension(
+    _ctx: &MiwaContext,
+    transfers: TransferRepoRef,
+    managers: TransferManagers,
+    OptionalConfig(cfg): OptionalConfig<PushConfig>,
+) -> MiwaResult<TransferPushExtension> {
+    let mut engine = PushEngine::default();
+    if let Some(root) = cfg.unwrap_or_default().file_root {
+        engine = engine.with_file_root(root);
+    }
+
+    let manager =
+        TransferPushManager::new(engine, transfers).with_notifications(NotificationService::new());
+    managers.register(TRANSFER_TYPES, TransferManagerRef::of(manager.clone()))?;
+    Ok(TransferPushExtension(manager))
+}
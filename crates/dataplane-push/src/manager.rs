@@ -0,0 +1,192 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use dashmap::DashMap;
+use edc_dataplane_core::{
+    core::{
+        db::transfer::TransferRepoRef,
+        model::transfer::{types::DataAddressError, Transfer, TransferStatus},
+        service::{
+            notification::NotificationService,
+            transfer::{TransferManager, TransferResult},
+        },
+    },
+    signaling::{DataAddress, FlowType},
+};
+use tokio::{sync::oneshot, task::AbortHandle};
+
+use crate::engine::{PushEngine, Sink, Source};
+
+#[derive(Clone)]
+pub struct TransferPushManager {
+    engine: PushEngine,
+    transfers: TransferRepoRef,
+    notifications: Option<NotificationService>,
+    /// Pushes of the transfers being started, spawned once the start is persisted
+    pending: Arc<DashMap<String, (Source, Sink)>>,
+    jobs: Arc<DashMap<String, AbortHandle>>,
+}
+
+impl TransferPushManager {
+    pub fn new(engine: PushEngine, transfers: TransferRepoRef) -> Self {
+        Self {
+            engine,
+            transfers,
+            notifications: None,
+            pending: Arc::default(),
+            jobs: Arc::default(),
+        }
+    }
+
+    /// Notifies the control plane when a push completes or fails
+    pub fn with_notifications(mut self, notifications: NotificationService) -> Self {
+        self.notifications = Some(notifications);
+        self
+    }
+
+    /// Number of the push jobs running
+    pub fn running(&self) -> usize {
+        self.jobs.len()
+    }
+
+    /// Aborts all the running push jobs
+    pub fn shutdown(&self) {
+        self.jobs.retain(|_, job| {
+            job.abort();
+            false
+        });
+    }
+
+    fn cancel(&self, transfer_id: &str) {
+        if let Some((_, job)) = self.jobs.remove(transfer_id) {
+            tracing::debug!("Cancelling push for transfer {}", transfer_id);
+            job.abort();
+        }
+    }
+
+    fn parse(&self, transfer: &Transfer) -> TransferResult<(Source, Sink)> {
+        let destination = transfer
+            .destination
+            .as_ref()
+            .ok_or_else(|| DataAddressError::MissingProperty("destination".to_string()))?;
+
+        let sink = match Sink::try_from(&destination.0)? {
+            Sink::File(mut file) => {
+                file.path = self.engine.file_path(&file.path)?;
+                Sink::File(file)
+            }
+            sink => sink,
+        };
+
+        Ok((Source::try_from(transfer.source_address()?)?, sink))
+    }
+}
+
+#[async_trait::async_trait]
+impl TransferManager for TransferPushManager {
+    async fn can_handle(&self, transfer: &Transfer) -> TransferResult<bool> {
+        if transfer.flow_type != FlowType::Push {
+            return Ok(false);
+        }
+
+        self.parse(transfer)?;
+        Ok(true)
+    }
+
+    async fn handle_start(&self, transfer: &Transfer) -> TransferResult<Option<DataAddress>> {
+        let push = self.parse(transfer)?;
+        self.pending.insert(transfer.id.clone(), push);
+        Ok(None)
+    }
+
+    async fn rollback_start(&self, transfer: &Transfer) -> TransferResult<()> {
+        self.pending.remove(&transfer.id);
+        Ok(())
+    }
+
+    async fn commit_start(&self, transfer: &Transfer) {
+        let Some((transfer_id, (source, sink))) = self.pending.remove(&transfer.id) else {
+            return;
+        };
+
+        let engine = self.engine.clone();
+        let transfers = self.transfers.clone();
+        let notifications = self.notifications.clone();
+        let jobs = self.jobs.clone();
+        let id = transfer_id.clone();
+        // The job waits for its handle to be tracked, a push finishing right away would
+        // otherwise leave it behind
+        let (tracked, is_tracked) = oneshot::channel();
+
+        let job = tokio::spawn(async move {
+            if is_tracked.await.is_err() {
+                return;
+            }
+
+            let result = engine.transfer(&source, &sink).await;
+
+            // A restart of the transfer may have replaced the handle with the one of its job
+            jobs.remove_if(&id, |_, job| job.id() == tokio::task::id());
+
+            if let Err(err) = complete(&transfers, notifications, &id, result).await {
+                tracing::error!("Failed to update the status of transfer {}: {}", id, err);
+            }
+        });
+
+        if let Some(previous) = self.jobs.insert(transfer_id, job.abort_handle()) {
+            previous.abort();
+        }
+        let _ = tracked.send(());
+    }
+
+    async fn handle_suspend(&self, transfer_id: &str) -> TransferResult<()> {
+        self.cancel(transfer_id);
+        Ok(())
+    }
+
+    async fn handle_terminate(&self, transfer_id: &str) -> TransferResult<()> {
+        self.cancel(transfer_id);
+        Ok(())
+    }
+}
+
+async fn complete(
+    transfers: &TransferRepoRef,
+    notifications: Option<NotificationService>,
+    transfer_id: &str,
+    result: anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    let Some(mut transfer) = transfers.fetch_by_id(transfer_id).await? else {
+        return Ok(());
+    };
+
+    let (status, reason) = match result {
+        Ok(()) => (TransferStatus::Completed, None),
+        Err(err) => {
+            tracing::warn!("Push for transfer {} failed: {}", transfer_id, err);
+            (TransferStatus::Failed, Some(err.to_string()))
+        }
+    };
+
+    if !transfer.status.can_transition_to(&status) {
+        return Ok(());
+    }
+
+    let from = transfer.status.clone();
+    let now = Utc::now();
+    transfer.status = status;
+    transfer.termination_reason = reason;
+    transfer.terminated_at = Some(now);
+    transfer.updated_at = now;
+
+    let notification = notifications.and_then(|n| n.notification(&transfer));
+
+    if !transfers.transition(transfer, from, notification).await? {
+        tracing::debug!(
+            "Transfer {} changed while pushing, discarding the result",
+            transfer_id
+        );
+    }
+
+    Ok(())
+}
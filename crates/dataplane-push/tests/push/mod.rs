@@ -0,0 +1,93 @@
+use std::{collections::HashMap, path::PathBuf, time::Duration};
+
+use edc_dataplane_core::{
+    core::{
+        db::{sqlite::transfer::SqliteTransferRepo, transfer::TransferRepoRef},
+        model::{
+            namespace::EDC_NAMESPACE,
+            transfer::{Transfer, TransferStatus},
+        },
+        service::transfer::{TransferManagerRef, TransferService},
+    },
+    signaling::{DataAddress, DataFlowStartMessage, EndpointProperty, FlowType},
+};
+use edc_dataplane_push::{engine::PushEngine, manager::TransferPushManager};
+use uuid::Uuid;
+
+mod transfer;
+
+/// Directory the file sinks of the tests are confined to
+pub fn file_root() -> PathBuf {
+    std::env::temp_dir().join("push")
+}
+
+pub async fn setup() -> TransferService {
+    setup_with(PushEngine::default().with_file_root(file_root())).await
+}
+
+pub async fn setup_with(engine: PushEngine) -> TransferService {
+    setup_manager(engine).await.0
+}
+
+/// Builds the service along with its push manager
+pub async fn setup_manager(engine: PushEngine) -> (TransferService, TransferPushManager) {
+    let transfers = SqliteTransferRepo::connect("sqlite::memory:")
+        .await
+        .unwrap();
+    transfers.migrate().await.unwrap();
+
+    let transfers = TransferRepoRef::of(transfers);
+    let manager = TransferPushManager::new(engine, transfers.clone());
+
+    let service = TransferService::new(TransferManagerRef::of(manager.clone()), transfers);
+    (service, manager)
+}
+
+pub fn address(endpoint_type: &str, properties: &[(&str, &str)]) -> DataAddress {
+    DataAddress::builder()
+        .endpoint_type(endpoint_type.to_string())
+        .endpoint_properties(
+            properties
+                .iter()
+                .map(|(name, value)| {
+                    EndpointProperty::builder()
+                        .name(EDC_NAMESPACE.to_iri(name))
+                        .value(*value)
+                        .build()
+                })
+                .collect(),
+        )
+        .build()
+}
+
+pub fn create_start_message(
+    id: &str,
+    source: DataAddress,
+    destination: DataAddress,
+) -> DataFlowStartMessage {
+    DataFlowStartMessage::builder()
+        .participant_id("participant_id".to_string())
+        .process_id(id.to_string())
+        .source_data_address(source)
+        .destination_data_address(destination)
+        .properties(HashMap::new())
+        .flow_type(FlowType::Push)
+        .dataset_id(Uuid::new_v4().to_string())
+        .agreement_id(Uuid::new_v4().to_string())
+        .build()
+}
+
+pub async fn wait_for_status(
+    service: &TransferService,
+    id: &str,
+    status: TransferStatus,
+) -> Transfer {
+    for _ in 0..50 {
+        let transfer = service.get(id).await.unwrap().unwrap();
+        if transfer.status == status {
+            return transfer;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    panic!("Transfer {} did not reach status {:?}", id, status);
+}
@@ -0,0 +1,263 @@
+use std::time::Duration;
+
+use edc_dataplane_core::core::{model::transfer::TransferStatus, service::transfer::TransferError};
+use edc_dataplane_push::engine::PushEngine;
+use uuid::Uuid;
+use wiremock::{
+    matchers::{body_string, method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+use crate::push::{
+    address, create_start_message, file_root, setup, setup_manager, setup_with, wait_for_status,
+};
+
+const CONTENT: &str = "pushed content";
+
+async fn source_server(response: ResponseTemplate) -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/source"))
+        .respond_with(response)
+        .mount(&server)
+        .await;
+    server
+}
+
+#[tokio::test]
+async fn push_http_to_http() {
+    let service = setup().await;
+    let id = Uuid::new_v4().to_string();
+
+    let source = source_server(ResponseTemplate::new(200).set_body_string(CONTENT)).await;
+    let destination = MockServer::start().await;
+
+    Mock::given(method("PUT"))
+        .and(path("/sink"))
+        .and(body_string(CONTENT))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&destination)
+        .await;
+
+    let response = service
+        .start(create_start_message(
+            &id,
+            address(
+                "HttpData",
+                &[("baseUrl", &format!("{}/source", source.uri()))],
+            ),
+            address(
+                "HttpData",
+                &[
+                    ("baseUrl", &format!("{}/sink", destination.uri())),
+                    ("method", "PUT"),
+                ],
+            ),
+        ))
+        .await
+        .unwrap();
+
+    assert!(response.data_address.is_none());
+
+    wait_for_status(&service, &id, TransferStatus::Completed).await;
+
+    destination.verify().await;
+}
+
+#[tokio::test]
+async fn push_http_to_file() {
+    let service = setup().await;
+    let id = Uuid::new_v4().to_string();
+    let file = format!("{}/data.txt", id);
+
+    let source = source_server(ResponseTemplate::new(200).set_body_string(CONTENT)).await;
+
+    service
+        .start(create_start_message(
+            &id,
+            address(
+                "HttpData",
+                &[("baseUrl", &format!("{}/source", source.uri()))],
+            ),
+            address("File", &[("path", &file)]),
+        ))
+        .await
+        .unwrap();
+
+    wait_for_status(&service, &id, TransferStatus::Completed).await;
+
+    assert_eq!(
+        tokio::fs::read_to_string(file_root().join(&file))
+            .await
+            .unwrap(),
+        CONTENT
+    );
+}
+
+#[tokio::test]
+async fn push_fails_when_source_fails() {
+    let service = setup().await;
+    let id = Uuid::new_v4().to_string();
+    let file = format!("{}/data.txt", id);
+
+    let source = source_server(ResponseTemplate::new(500)).await;
+
+    service
+        .start(create_start_message(
+            &id,
+            address(
+                "HttpData",
+                &[("baseUrl", &format!("{}/source", source.uri()))],
+            ),
+            address("File", &[("path", &file)]),
+        ))
+        .await
+        .unwrap();
+
+    let transfer = wait_for_status(&service, &id, TransferStatus::Failed).await;
+
+    assert!(transfer.termination_reason.is_some());
+    assert!(transfer.terminated_at.is_some());
+    assert!(!file_root().join(&file).exists());
+}
+
+#[tokio::test]
+async fn terminate_cancels_push() {
+    let service = setup().await;
+    let id = Uuid::new_v4().to_string();
+    let file = format!("{}/data.txt", id);
+
+    let source = source_server(
+        ResponseTemplate::new(200)
+            .set_body_string(CONTENT)
+            .set_delay(Duration::from_secs(1)),
+    )
+    .await;
+
+    service
+        .start(create_start_message(
+            &id,
+            address(
+                "HttpData",
+                &[("baseUrl", &format!("{}/source", source.uri()))],
+            ),
+            address("File", &[("path", &file)]),
+        ))
+        .await
+        .unwrap();
+
+    service
+        .terminate(id.clone(), Some("terminate".to_string()))
+        .await
+        .unwrap();
+
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+
+    wait_for_status(&service, &id, TransferStatus::Terminated).await;
+    assert!(!file_root().join(&file).exists());
+}
+
+#[tokio::test]
+async fn push_to_file_outside_of_the_root_is_rejected() {
+    let service = setup().await;
+    let source = address("HttpData", &[("baseUrl", "http://localhost/source")]);
+
+    let outside = std::env::temp_dir().join("data.txt");
+    for path in [
+        outside.to_str().unwrap(),
+        "../data.txt",
+        "nested/../../data.txt",
+    ] {
+        let id = Uuid::new_v4().to_string();
+
+        let result = service
+            .start(create_start_message(
+                &id,
+                source.clone(),
+                address("File", &[("path", path)]),
+            ))
+            .await
+            .unwrap_err();
+
+        assert!(
+            matches!(result, TransferError::Validation(_)),
+            "{} was accepted",
+            path
+        );
+        assert!(service.get(&id).await.unwrap().is_none());
+    }
+}
+
+#[tokio::test]
+async fn push_to_file_is_rejected_without_root() {
+    let service = setup_with(PushEngine::default()).await;
+    let id = Uuid::new_v4().to_string();
+
+    let result = service
+        .start(create_start_message(
+            &id,
+            address("HttpData", &[("baseUrl", "http://localhost/source")]),
+            address("File", &[("path", "data.txt")]),
+        ))
+        .await
+        .unwrap_err();
+
+    assert!(matches!(result, TransferError::Validation(_)));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn push_through_link_outside_of_the_root_fails() {
+    let service = setup().await;
+    let id = Uuid::new_v4().to_string();
+    let outside = std::env::temp_dir().join(format!("push-outside-{}", id));
+
+    tokio::fs::create_dir_all(&outside).await.unwrap();
+    tokio::fs::create_dir_all(file_root()).await.unwrap();
+    tokio::fs::symlink(&outside, file_root().join(&id))
+        .await
+        .unwrap();
+
+    let source = source_server(ResponseTemplate::new(200).set_body_string(CONTENT)).await;
+
+    service
+        .start(create_start_message(
+            &id,
+            address(
+                "HttpData",
+                &[("baseUrl", &format!("{}/source", source.uri()))],
+            ),
+            address("File", &[("path", &format!("{}/data.txt", id))]),
+        ))
+        .await
+        .unwrap();
+
+    wait_for_status(&service, &id, TransferStatus::Failed).await;
+
+    assert!(!outside.join("data.txt").exists());
+}
+
+#[tokio::test]
+async fn finished_push_is_no_longer_tracked() {
+    let (service, manager) = setup_manager(PushEngine::default()).await;
+    let id = Uuid::new_v4().to_string();
+
+    let source = source_server(ResponseTemplate::new(500)).await;
+
+    service
+        .start(create_start_message(
+            &id,
+            address(
+                "HttpData",
+                &[("baseUrl", &format!("{}/source", source.uri()))],
+            ),
+            address("HttpData", &[("baseUrl", "http://localhost:1/sink")]),
+        ))
+        .await
+        .unwrap();
+
+    wait_for_status(&service, &id, TransferStatus::Failed).await;
+
+    assert_eq!(manager.running(), 0);
+}
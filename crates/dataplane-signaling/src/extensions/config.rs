@@ -0,0 +1,148 @@
+use std::{
+    collections::HashSet,
+    net::{IpAddr, Ipv4Addr},
+};
+
+use miwa::derive::ExtensionConfig;
+use serde::Deserialize;
+
+use crate::web::auth::AuthConfig;
+
+use super::registration::client::{ControlPlaneAuthConfig, TlsConfig};
+
+/// Configuration of the `signaling` section, shared by the signaling API and the
+/// registration with the control planes
+#[derive(Deserialize, ExtensionConfig, Clone)]
+#[config(prefix = "signaling")]
+pub struct SignalingConfig {
+    #[serde(default = "default_signaling_port")]
+    pub port: u16,
+    #[serde(default = "default_bind")]
+    pub bind: IpAddr,
+    pub auth: Option<AuthConfig>,
+    /// Schemes accepted for the `baseUrl` of data addresses
+    #[serde(default = "default_allowed_schemes")]
+    pub allowed_schemes: Vec<String>,
+    /// Single control plane registered as `default`, the `registration` section
+    /// holds its credentials
+    pub control_plane_url: Option<String>,
+    pub signaling_url: Option<String>,
+    #[serde(default)]
+    pub transfer_types: Vec<String>,
+    #[serde(default)]
+    pub source_types: Vec<String>,
+    #[serde(default)]
+    pub control_planes: Vec<ControlPlaneConfig>,
+    #[serde(default = "default_registration")]
+    pub registration: RegistrationConfig,
+}
+
+impl SignalingConfig {
+    pub fn control_planes(&self) -> anyhow::Result<Vec<ControlPlaneConfig>> {
+        let mut control_planes = vec![];
+
+        if let Some(url) = &self.control_plane_url {
+            let signaling_url = self
+                .signaling_url
+                .clone()
+                .ok_or_else(|| anyhow::anyhow!("Missing `signaling_url` for {}", url))?;
+
+            control_planes.push(ControlPlaneConfig {
+                id: DEFAULT_CONTROL_PLANE.to_string(),
+                url: url.clone(),
+                signaling_url,
+                transfer_types: self.transfer_types.clone(),
+                source_types: self.source_types.clone(),
+                auth: self.registration.auth.clone(),
+                tls: self.registration.tls.clone(),
+            });
+        }
+
+        control_planes.extend(self.control_planes.iter().cloned());
+
+        if control_planes.is_empty() {
+            anyhow::bail!("Either `control_plane_url` or `control_planes` is required");
+        }
+
+        let mut ids = HashSet::new();
+        if let Some(control_plane) = control_planes.iter().find(|cp| !ids.insert(&cp.id)) {
+            anyhow::bail!("Duplicate control plane id {}", control_plane.id);
+        }
+
+        Ok(control_planes)
+    }
+}
+
+const DEFAULT_CONTROL_PLANE: &str = "default";
+
+#[derive(Deserialize, Clone)]
+pub struct ControlPlaneConfig {
+    /// Identifies the control plane in the registration state
+    pub id: String,
+    pub url: String,
+    /// Signaling endpoint advertised to this control plane
+    pub signaling_url: String,
+    /// Types of the registered transfer managers when empty
+    #[serde(default)]
+    pub transfer_types: Vec<String>,
+    #[serde(default)]
+    pub source_types: Vec<String>,
+    pub auth: Option<ControlPlaneAuthConfig>,
+    pub tls: Option<TlsConfig>,
+}
+
+/// Shared by all the control planes, durations are in seconds
+#[derive(Deserialize, Clone)]
+pub struct RegistrationConfig {
+    /// Interval between two registrations once registered, so that a control
+    /// plane that lost the instance gets it back
+    #[serde(default = "default_heartbeat_interval")]
+    pub heartbeat_interval: u64,
+    #[serde(default = "default_initial_backoff")]
+    pub initial_backoff: u64,
+    #[serde(default = "default_max_backoff")]
+    pub max_backoff: u64,
+    #[serde(default = "default_unregister_on_shutdown")]
+    pub unregister_on_shutdown: bool,
+    pub auth: Option<ControlPlaneAuthConfig>,
+    pub tls: Option<TlsConfig>,
+}
+
+pub fn default_registration() -> RegistrationConfig {
+    RegistrationConfig {
+        heartbeat_interval: default_heartbeat_interval(),
+        initial_backoff: default_initial_backoff(),
+        max_backoff: default_max_backoff(),
+        unregister_on_shutdown: default_unregister_on_shutdown(),
+        auth: None,
+        tls: None,
+    }
+}
+
+pub fn default_heartbeat_interval() -> u64 {
+    60
+}
+
+pub fn default_initial_backoff() -> u64 {
+    2
+}
+
+pub fn default_max_backoff() -> u64 {
+    60 * 2
+}
+
+pub fn default_unregister_on_shutdown() -> bool {
+    true
+}
+
+pub fn default_signaling_port() -> u16 {
+    8787
+}
+
+pub fn default_bind() -> IpAddr {
+    IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0))
+}
+
+pub fn default_allowed_schemes() -> Vec<String> {
+    vec!["http".to_string(), "https".to_string()]
+}
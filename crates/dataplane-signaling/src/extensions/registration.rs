@@ -1,101 +1,624 @@
-use edc_dataplane_core::core::model::namespace::EDC_NAMESPACE;
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex, RwLock},
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use edc_dataplane_core::core::{
+    model::namespace::EDC_NAMESPACE, service::registry::TransferManagers,
+};
 use miwa::{
     core::{Extension, ExtensionConfig, MiwaContext, MiwaResult},
-    derive::{extension, ExtensionConfig},
+    derive::{extension, Injectable},
 };
 use reqwest::Response;
-use serde::Deserialize;
+use serde::Serialize;
 use serde_json::json;
+use tokio::task::JoinHandle;
 use tracing::{debug, error, info};
 
+use client::ControlPlaneClient;
+
+use super::config::{ControlPlaneConfig, RegistrationConfig, SignalingConfig};
+
+pub mod client;
+
+const PATH: &str = "/v1/dataplanes";
+
 pub struct RegistrationExtension {
-    component_id: String,
-    cfg: SignalingConfig,
+    registrars: Vec<Arc<Registrar>>,
+    unregister_on_shutdown: bool,
+    jobs: Mutex<Vec<JoinHandle<()>>>,
 }
 
 impl RegistrationExtension {
-    pub fn new(component_id: String, cfg: SignalingConfig) -> Self {
-        Self { component_id, cfg }
+    pub fn new(
+        component_id: String,
+        cfg: SignalingConfig,
+        status: RegistrationStatus,
+        managers: TransferManagers,
+    ) -> anyhow::Result<Self> {
+        let registrars = cfg
+            .control_planes()?
+            .into_iter()
+            .map(|control_plane| {
+                Registrar::new(
+                    component_id.clone(),
+                    control_plane,
+                    &cfg.registration,
+                    status.clone(),
+                    managers.clone(),
+                )
+                .map(Arc::new)
+            })
+            .collect::<anyhow::Result<_>>()?;
+
+        Ok(Self {
+            registrars,
+            unregister_on_shutdown: cfg.registration.unregister_on_shutdown,
+            jobs: Mutex::default(),
+        })
     }
 }
 
 #[async_trait::async_trait]
 impl Extension for RegistrationExtension {
     async fn start(&self) -> MiwaResult<()> {
-        let component_id = self.component_id.clone();
-        let cfg = self.cfg.clone();
-        tokio::task::spawn(async {
-            register_dataplane(component_id, cfg).await;
-        });
+        let mut jobs = self.jobs.lock().unwrap();
+        for registrar in &self.registrars {
+            let registrar = registrar.clone();
+            jobs.push(tokio::task::spawn(async move {
+                registrar.run().await;
+            }));
+        }
         Ok(())
     }
 
     async fn shutdown(&self) -> MiwaResult<()> {
+        for job in self.jobs.lock().unwrap().drain(..) {
+            job.abort();
+        }
+
+        if self.unregister_on_shutdown {
+            for registrar in &self.registrars {
+                if let Err(err) = registrar.unregister().await {
+                    error!(
+                        "Failed to unregister dataplane from {}: {}",
+                        registrar.control_plane.id, err
+                    );
+                }
+            }
+        }
         Ok(())
     }
 }
 
-#[derive(Deserialize, ExtensionConfig, Clone)]
-#[config(prefix = "signaling")]
-pub struct SignalingConfig {
-    control_plane_url: String,
-    signaling_url: String,
-    transfer_types: Vec<String>,
-    source_types: Vec<String>,
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "state", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RegistrationState {
+    Unregistered,
+    Registered {
+        /// Last successful registration
+        at: DateTime<Utc>,
+    },
+    Failed {
+        error: String,
+        /// Consecutive failed attempts
+        attempts: u32,
+    },
+}
+
+/// Shared view of the registrations by control plane id, used by health checks
+#[derive(Clone, Debug, Default, Injectable)]
+pub struct RegistrationStatus(Arc<RwLock<BTreeMap<String, RegistrationState>>>);
+
+impl RegistrationStatus {
+    pub fn get(&self, control_plane: &str) -> RegistrationState {
+        self.0
+            .read()
+            .unwrap()
+            .get(control_plane)
+            .cloned()
+            .unwrap_or(RegistrationState::Unregistered)
+    }
+
+    pub fn all(&self) -> BTreeMap<String, RegistrationState> {
+        self.0.read().unwrap().clone()
+    }
+
+    /// Whether the dataplane is registered with every control plane, never before the
+    /// control planes are known
+    pub fn is_registered(&self) -> bool {
+        let states = self.0.read().unwrap();
+
+        !states.is_empty()
+            && states
+                .values()
+                .all(|state| matches!(state, RegistrationState::Registered { .. }))
+    }
+
+    pub(crate) fn set(&self, control_plane: &str, state: RegistrationState) {
+        self.0
+            .write()
+            .unwrap()
+            .insert(control_plane.to_string(), state);
+    }
 }
 
-#[extension(name = "Data plane registration extension")]
+#[extension(
+    name = "Data plane registration extension",
+    provides(RegistrationStatus)
+)]
 pub async fn registration_extension(
     ctx: &MiwaContext,
     ExtensionConfig(cfg): ExtensionConfig<SignalingConfig>,
+    managers: TransferManagers,
 ) -> MiwaResult<RegistrationExtension> {
+    let status = RegistrationStatus::default();
+    ctx.register(status.clone());
+
     Ok(RegistrationExtension::new(
         ctx.component_id().to_string(),
         cfg,
-    ))
+        status,
+        managers,
+    )?)
 }
 
-pub async fn register_dataplane(component_id: String, cfg: SignalingConfig) {
-    loop {
+struct Registrar {
+    client: ControlPlaneClient,
+    component_id: String,
+    control_plane: ControlPlaneConfig,
+    status: RegistrationStatus,
+    managers: TransferManagers,
+    heartbeat_interval: Duration,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl Registrar {
+    fn new(
+        component_id: String,
+        control_plane: ControlPlaneConfig,
+        cfg: &RegistrationConfig,
+        status: RegistrationStatus,
+        managers: TransferManagers,
+    ) -> anyhow::Result<Self> {
+        status.set(&control_plane.id, RegistrationState::Unregistered);
+
+        Ok(Self {
+            client: ControlPlaneClient::new(control_plane.auth.clone(), control_plane.tls.clone())?,
+            component_id,
+            control_plane,
+            status,
+            managers,
+            heartbeat_interval: Duration::from_secs(cfg.heartbeat_interval),
+            initial_backoff: Duration::from_secs(cfg.initial_backoff),
+            max_backoff: Duration::from_secs(cfg.max_backoff),
+        })
+    }
+
+    /// Registers the dataplane and keeps re-registering it until aborted
+    async fn run(&self) {
+        let id = &self.control_plane.id;
+        let mut attempts = 0;
+
+        loop {
+            debug!(
+                "Registering dataplane with control plane {}: {}",
+                id, self.control_plane.url
+            );
+
+            let delay = match self.register().await {
+                Ok(()) => {
+                    if !matches!(self.status.get(id), RegistrationState::Registered { .. }) {
+                        info!(
+                            "Registered dataplane: {:?} at {} ({})",
+                            self.component_id, id, self.control_plane.url
+                        );
+                    }
+                    attempts = 0;
+                    self.status
+                        .set(id, RegistrationState::Registered { at: Utc::now() });
+                    self.heartbeat_interval
+                }
+                Err(err) => {
+                    attempts += 1;
+                    error!("Failed to register dataplane with {}: {}", id, err);
+                    self.status.set(
+                        id,
+                        RegistrationState::Failed {
+                            error: err.to_string(),
+                            attempts,
+                        },
+                    );
+                    self.backoff(attempts)
+                }
+            };
+
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    fn backoff(&self, attempts: u32) -> Duration {
+        let factor = 2_u32.saturating_pow(attempts.saturating_sub(1));
+        self.initial_backoff
+            .checked_mul(factor)
+            .unwrap_or(self.max_backoff)
+            .min(self.max_backoff)
+    }
+
+    async fn register(&self) -> anyhow::Result<()> {
+        let request = self
+            .client
+            .post(format!("{}{}", self.control_plane.url, PATH))
+            .json(&json!({
+                "@context" : {
+                    "@vocab": EDC_NAMESPACE.ns()
+                },
+                "@id": self.component_id,
+                "url": self.control_plane.signaling_url,
+                "allowedTransferTypes": self.transfer_types(),
+                "allowedSourceTypes": self.control_plane.source_types,
+            }));
+
+        check(self.client.send(request).await?).await
+    }
+
+    /// Without configured transfer types, advertises the types of the registered managers
+    fn transfer_types(&self) -> Vec<String> {
+        if self.control_plane.transfer_types.is_empty() {
+            self.managers.types()
+        } else {
+            self.control_plane.transfer_types.clone()
+        }
+    }
+
+    async fn unregister(&self) -> anyhow::Result<()> {
         debug!(
-            "Registering dataplane with control plane: {}",
-            cfg.control_plane_url
+            "Unregistering dataplane from control plane {}: {}",
+            self.control_plane.id, self.control_plane.url
         );
 
-        let response = send_registration(&component_id, &cfg).await;
+        let request = self.client.delete(format!(
+            "{}{}/{}",
+            self.control_plane.url, PATH, self.component_id
+        ));
 
-        let error = match response {
-            Ok(response) if response.status().is_success() => {
-                info!(
-                    "Registered dataplane: {:?} at {:?}",
-                    component_id, cfg.control_plane_url
-                );
-                break;
-            }
-            Ok(response) => response.text().await.ok().unwrap_or_default(),
-            Err(e) => e.to_string(),
-        };
+        check(self.client.send(request).await?).await?;
 
-        error!("Failed to register dataplane: {}", error);
+        info!(
+            "Unregistered dataplane: {:?} from {}",
+            self.component_id, self.control_plane.id
+        );
+        self.status
+            .set(&self.control_plane.id, RegistrationState::Unregistered);
+        Ok(())
+    }
+}
 
-        tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+async fn check(response: Response) -> anyhow::Result<()> {
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        anyhow::bail!("{}: {}", status, body)
     }
 }
 
-async fn send_registration(component_id: &str, cfg: &SignalingConfig) -> anyhow::Result<Response> {
-    const PATH: &str = "/v1/dataplanes";
-    reqwest::Client::new()
-        .post(format!("{}{}", cfg.control_plane_url, PATH))
-        .json(&json!({
-            "@context" : {
-                "@vocab": EDC_NAMESPACE.ns()
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use edc_dataplane_core::{
+        core::{
+            model::transfer::Transfer,
+            service::{
+                registry::TransferManagers,
+                transfer::{TransferManager, TransferManagerRef, TransferResult},
             },
-            "@id": component_id,
-            "url": cfg.signaling_url,
-            "allowedTransferTypes": cfg.transfer_types,
-            "allowedSourceTypes": cfg.source_types,
-        }))
-        .send()
-        .await
-        .map(Ok)?
+        },
+        signaling::DataAddress,
+    };
+    use miwa::core::Extension;
+    use serde_json::json;
+    use wiremock::{
+        matchers::{body_partial_json, method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    use crate::extensions::config::{
+        default_allowed_schemes, default_bind, default_registration, default_signaling_port,
+    };
+
+    use super::{
+        ControlPlaneConfig, Registrar, RegistrationExtension, RegistrationState,
+        RegistrationStatus, SignalingConfig,
+    };
+
+    fn control_plane(id: &str, url: String) -> ControlPlaneConfig {
+        ControlPlaneConfig {
+            id: id.to_string(),
+            url,
+            signaling_url: format!("http://{}.local:8787/api/v1/dataflows", id),
+            transfer_types: vec!["HttpData-PULL".to_string()],
+            source_types: vec!["HttpData".to_string()],
+            auth: None,
+            tls: None,
+        }
+    }
+
+    fn config(control_planes: Vec<ControlPlaneConfig>) -> SignalingConfig {
+        let mut registration = default_registration();
+        registration.initial_backoff = 0;
+        registration.heartbeat_interval = 1;
+
+        SignalingConfig {
+            port: default_signaling_port(),
+            bind: default_bind(),
+            auth: None,
+            allowed_schemes: default_allowed_schemes(),
+            control_plane_url: None,
+            signaling_url: None,
+            transfer_types: vec![],
+            source_types: vec![],
+            control_planes,
+            registration,
+        }
+    }
+
+    struct NoopManager;
+
+    #[async_trait::async_trait]
+    impl TransferManager for NoopManager {
+        async fn can_handle(&self, _transfer: &Transfer) -> TransferResult<bool> {
+            Ok(true)
+        }
+        async fn handle_start(&self, _transfer: &Transfer) -> TransferResult<Option<DataAddress>> {
+            Ok(None)
+        }
+        async fn handle_suspend(&self, _id: &str) -> TransferResult<()> {
+            Ok(())
+        }
+        async fn handle_terminate(&self, _id: &str) -> TransferResult<()> {
+            Ok(())
+        }
+    }
+
+    fn registrar(control_plane_url: String) -> Registrar {
+        registrar_with(
+            control_plane("cp", control_plane_url),
+            TransferManagers::default(),
+        )
+    }
+
+    fn registrar_with(control_plane: ControlPlaneConfig, managers: TransferManagers) -> Registrar {
+        let mut registrar = Registrar::new(
+            "dataplane".to_string(),
+            control_plane,
+            &default_registration(),
+            RegistrationStatus::default(),
+            managers,
+        )
+        .unwrap();
+        registrar.heartbeat_interval = Duration::from_millis(50);
+        registrar.initial_backoff = Duration::from_millis(10);
+        registrar.max_backoff = Duration::from_millis(40);
+        registrar
+    }
+
+    async fn wait_for(
+        status: &RegistrationStatus,
+        control_plane: &str,
+        check: impl Fn(&RegistrationState) -> bool,
+    ) {
+        for _ in 0..100 {
+            if check(&status.get(control_plane)) {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!(
+            "Unexpected registration state {:?}",
+            status.get(control_plane)
+        );
+    }
+
+    fn registered(state: &RegistrationState) -> bool {
+        matches!(state, RegistrationState::Registered { .. })
+    }
+
+    #[tokio::test]
+    async fn registers_periodically() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/dataplanes"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(2..)
+            .mount(&server)
+            .await;
+
+        let registrar = registrar(server.uri());
+        let status = registrar.status.clone();
+        let job = tokio::spawn(async move { registrar.run().await });
+
+        wait_for(&status, "cp", registered).await;
+        tokio::time::sleep(Duration::from_millis(120)).await;
+        job.abort();
+
+        server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn advertises_types_of_registered_managers() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/dataplanes"))
+            .and(body_partial_json(json!({
+                "allowedTransferTypes": ["HttpData-PULL", "HttpData-PUSH"]
+            })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1..)
+            .mount(&server)
+            .await;
+
+        let managers = TransferManagers::default();
+        managers
+            .register(["HttpData-PUSH"], TransferManagerRef::of(NoopManager))
+            .unwrap();
+        managers
+            .register(["HttpData-PULL"], TransferManagerRef::of(NoopManager))
+            .unwrap();
+
+        let mut control_plane = control_plane("cp", server.uri());
+        control_plane.transfer_types = vec![];
+
+        let registrar = registrar_with(control_plane, managers);
+        let status = registrar.status.clone();
+        let job = tokio::spawn(async move { registrar.run().await });
+
+        wait_for(&status, "cp", registered).await;
+        job.abort();
+
+        server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn retries_until_registered() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/dataplanes"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(3)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/dataplanes"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+
+        let registrar = registrar(server.uri());
+        let status = registrar.status.clone();
+        let job = tokio::spawn(async move { registrar.run().await });
+
+        wait_for(&status, "cp", |state| {
+            matches!(state, RegistrationState::Failed { attempts: 2.., .. })
+        })
+        .await;
+        wait_for(&status, "cp", registered).await;
+        job.abort();
+    }
+
+    #[tokio::test]
+    async fn unregisters() {
+        let server = MockServer::start().await;
+        Mock::given(method("DELETE"))
+            .and(path("/v1/dataplanes/dataplane"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let registrar = registrar(server.uri());
+        registrar.status.set(
+            "cp",
+            RegistrationState::Registered {
+                at: chrono::Utc::now(),
+            },
+        );
+
+        registrar.unregister().await.unwrap();
+
+        assert_eq!(registrar.status.get("cp"), RegistrationState::Unregistered);
+        server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn registers_with_each_control_plane() {
+        let tenant = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/dataplanes"))
+            .and(body_partial_json(json!({
+                "url": "http://tenant.local:8787/api/v1/dataflows"
+            })))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&tenant)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path("/v1/dataplanes/dataplane"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&tenant)
+            .await;
+
+        let staging = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/dataplanes"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&staging)
+            .await;
+
+        let status = RegistrationStatus::default();
+        let extension = RegistrationExtension::new(
+            "dataplane".to_string(),
+            config(vec![
+                control_plane("tenant", tenant.uri()),
+                control_plane("staging", staging.uri()),
+            ]),
+            status.clone(),
+            TransferManagers::default(),
+        )
+        .unwrap();
+
+        extension.start().await.unwrap();
+
+        wait_for(&status, "tenant", registered).await;
+        wait_for(&status, "staging", |state| {
+            matches!(state, RegistrationState::Failed { .. })
+        })
+        .await;
+        assert!(!status.is_registered());
+
+        extension.shutdown().await.unwrap();
+
+        assert_eq!(status.get("tenant"), RegistrationState::Unregistered);
+        tenant.verify().await;
+    }
+
+    #[test]
+    fn single_control_plane() {
+        let mut cfg = config(vec![]);
+        cfg.control_plane_url = Some("http://localhost/control".to_string());
+        cfg.signaling_url = Some("http://localhost:8787/api/v1/dataflows".to_string());
+        cfg.transfer_types = vec!["HttpData-PULL".to_string()];
+
+        let control_planes = cfg.control_planes().unwrap();
+
+        assert_eq!(control_planes.len(), 1);
+        assert_eq!(control_planes[0].id, "default");
+        assert_eq!(control_planes[0].transfer_types, cfg.transfer_types);
+    }
+
+    #[test]
+    fn invalid_control_planes() {
+        assert!(config(vec![]).control_planes().is_err());
+
+        let duplicates = config(vec![
+            control_plane("cp", "http://a".to_string()),
+            control_plane("cp", "http://b".to_string()),
+        ]);
+        assert!(duplicates.control_planes().is_err());
+    }
+
+    #[test]
+    fn backoff_is_capped() {
+        let registrar = registrar("http://localhost".to_string());
+
+        assert_eq!(registrar.backoff(1), Duration::from_millis(10));
+        assert_eq!(registrar.backoff(3), Duration::from_millis(40));
+        assert_eq!(registrar.backoff(50), Duration::from_millis(40));
+    }
 }
@@ -0,0 +1,368 @@
+use std::{
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use reqwest::{Certificate, Identity, RequestBuilder, Response, StatusCode};
+use secrecy::{ExposeSecret, SecretString};
+use serde::Deserialize;
+use tokio::sync::RwLock;
+
+/// Credentials presented to the control plane
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ControlPlaneAuthConfig {
+    ApiKey {
+        #[serde(default = "default_api_key_header")]
+        header: String,
+        key: SecretString,
+    },
+    Bearer {
+        token: SecretString,
+    },
+    /// OAuth2 client credentials grant
+    Oauth2 {
+        token_url: String,
+        client_id: String,
+        client_secret: SecretString,
+        scope: Option<String>,
+        /// Seconds before the expiration at which the token is renewed
+        #[serde(default = "default_token_leeway")]
+        leeway: u64,
+    },
+}
+
+/// PEM files used for the connections to the control plane
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct TlsConfig {
+    /// Additional root certificate
+    pub ca_cert: Option<PathBuf>,
+    pub client_cert: Option<PathBuf>,
+    /// PKCS#8 key of the client certificate
+    pub client_key: Option<PathBuf>,
+}
+
+#[derive(Clone)]
+pub struct ControlPlaneClient {
+    client: reqwest::Client,
+    auth: Option<Credentials>,
+}
+
+#[derive(Clone)]
+enum Credentials {
+    ApiKey { header: String, key: SecretString },
+    Bearer(SecretString),
+    Oauth2(Arc<TokenSource>),
+}
+
+impl ControlPlaneClient {
+    pub fn new(
+        auth: Option<ControlPlaneAuthConfig>,
+        tls: Option<TlsConfig>,
+    ) -> anyhow::Result<Self> {
+        let client = build_client(tls.unwrap_or_default())?;
+
+        let auth = auth.map(|auth| match auth {
+            ControlPlaneAuthConfig::ApiKey { header, key } => Credentials::ApiKey { header, key },
+            ControlPlaneAuthConfig::Bearer { token } => Credentials::Bearer(token),
+            ControlPlaneAuthConfig::Oauth2 {
+                token_url,
+                client_id,
+                client_secret,
+                scope,
+                leeway,
+            } => Credentials::Oauth2(Arc::new(TokenSource {
+                client: client.clone(),
+                token_url,
+                client_id,
+                client_secret,
+                scope,
+                leeway: Duration::from_secs(leeway),
+                token: RwLock::default(),
+            })),
+        });
+
+        Ok(Self { client, auth })
+    }
+
+    pub fn post(&self, url: String) -> RequestBuilder {
+        self.client.post(url)
+    }
+
+    pub fn delete(&self, url: String) -> RequestBuilder {
+        self.client.delete(url)
+    }
+
+    /// Sends the request with the configured credentials
+    pub async fn send(&self, request: RequestBuilder) -> anyhow::Result<Response> {
+        let request = match &self.auth {
+            None => request,
+            Some(Credentials::ApiKey { header, key }) => {
+                request.header(header, key.expose_secret())
+            }
+            Some(Credentials::Bearer(token)) => request.bearer_auth(token.expose_secret()),
+            Some(Credentials::Oauth2(source)) => request.bearer_auth(source.token().await?),
+        };
+
+        let response = request.send().await?;
+
+        if response.status() == StatusCode::UNAUTHORIZED {
+            if let Some(Credentials::Oauth2(source)) = &self.auth {
+                // The token may have been revoked, the next attempt fetches a new one
+                source.invalidate().await;
+            }
+        }
+
+        Ok(response)
+    }
+}
+
+fn build_client(tls: TlsConfig) -> anyhow::Result<reqwest::Client> {
+    let mut builder = reqwest::Client::builder();
+
+    if let Some(ca_cert) = &tls.ca_cert {
+        builder = builder.add_root_certificate(Certificate::from_pem(&std::fs::read(ca_cert)?)?);
+    }
+
+    match (&tls.client_cert, &tls.client_key) {
+        (Some(cert), Some(key)) => {
+            builder = builder.identity(Identity::from_pkcs8_pem(
+                &std::fs::read(cert)?,
+                &std::fs::read(key)?,
+            )?);
+        }
+        (None, None) => {}
+        _ => anyhow::bail!(
+            "Both `client_cert` and `client_key` are required for client authentication"
+        ),
+    }
+
+    Ok(builder.build()?)
+}
+
+struct TokenSource {
+    client: reqwest::Client,
+    token_url: String,
+    client_id: String,
+    client_secret: SecretString,
+    scope: Option<String>,
+    leeway: Duration,
+    token: RwLock<Option<CachedToken>>,
+}
+
+struct CachedToken {
+    access_token: String,
+    expires_at: Option<Instant>,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: Option<u64>,
+}
+
+impl TokenSource {
+    async fn token(&self) -> anyhow::Result<String> {
+        if let Some(token) = self.cached(&*self.token.read().await) {
+            return Ok(token);
+        }
+
+        let mut cached = self.token.write().await;
+        if let Some(token) = self.cached(&cached) {
+            return Ok(token);
+        }
+
+        let mut form = vec![
+            ("grant_type", "client_credentials"),
+            ("client_id", &self.client_id),
+            ("client_secret", self.client_secret.expose_secret()),
+        ];
+        if let Some(scope) = &self.scope {
+            form.push(("scope", scope));
+        }
+
+        let response = self
+            .client
+            .post(&self.token_url)
+            .form(&form)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<TokenResponse>()
+            .await?;
+
+        *cached = Some(CachedToken {
+            access_token: response.access_token.clone(),
+            expires_at: response
+                .expires_in
+                .map(|expires_in| Instant::now() + Duration::from_secs(expires_in)),
+        });
+
+        Ok(response.access_token)
+    }
+
+    fn cached(&self, token: &Option<CachedToken>) -> Option<String> {
+        token
+            .as_ref()
+            .filter(|token| {
+                token
+                    .expires_at
+                    .is_none_or(|expires_at| Instant::now() + self.leeway < expires_at)
+            })
+            .map(|token| token.access_token.clone())
+    }
+
+    async fn invalidate(&self) {
+        self.token.write().await.take();
+    }
+}
+
+fn default_api_key_header() -> String {
+    "x-api-key".to_string()
+}
+
+fn default_token_leeway() -> u64 {
+    30
+}
+
+#[cfg(test)]
+mod tests {
+    use secrecy::SecretString;
+    use wiremock::{
+        matchers::{body_string_contains, header, method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    use super::{ControlPlaneAuthConfig, ControlPlaneClient, TlsConfig};
+
+    async fn control_plane(matcher: impl wiremock::Match + 'static) -> MockServer {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/dataplanes"))
+            .and(matcher)
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+        server
+    }
+
+    async fn register(client: &ControlPlaneClient, server: &MockServer) -> u16 {
+        client
+            .send(client.post(format!("{}/v1/dataplanes", server.uri())))
+            .await
+            .unwrap()
+            .status()
+            .as_u16()
+    }
+
+    #[tokio::test]
+    async fn api_key() {
+        let server = control_plane(header("x-api-key", "secret")).await;
+        let client = ControlPlaneClient::new(
+            Some(ControlPlaneAuthConfig::ApiKey {
+                header: "x-api-key".to_string(),
+                key: SecretString::from("secret"),
+            }),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(register(&client, &server).await, 200);
+    }
+
+    #[tokio::test]
+    async fn bearer() {
+        let server = control_plane(header("authorization", "Bearer token")).await;
+        let client = ControlPlaneClient::new(
+            Some(ControlPlaneAuthConfig::Bearer {
+                token: SecretString::from("token"),
+            }),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(register(&client, &server).await, 200);
+    }
+
+    #[tokio::test]
+    async fn oauth2_token_is_cached() {
+        let server = control_plane(header("authorization", "Bearer access")).await;
+
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .and(body_string_contains("grant_type=client_credentials"))
+            .and(body_string_contains("client_id=dataplane"))
+            .and(body_string_contains("scope=management"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "access_token": "access",
+                "token_type": "Bearer",
+                "expires_in": 3600
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = ControlPlaneClient::new(
+            Some(ControlPlaneAuthConfig::Oauth2 {
+                token_url: format!("{}/token", server.uri()),
+                client_id: "dataplane".to_string(),
+                client_secret: SecretString::from("secret"),
+                scope: Some("management".to_string()),
+                leeway: 30,
+            }),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(register(&client, &server).await, 200);
+        assert_eq!(register(&client, &server).await, 200);
+
+        server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn oauth2_token_is_renewed_near_expiry() {
+        let server = control_plane(header("authorization", "Bearer access")).await;
+
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "access_token": "access",
+                "expires_in": 10
+            })))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let client = ControlPlaneClient::new(
+            Some(ControlPlaneAuthConfig::Oauth2 {
+                token_url: format!("{}/token", server.uri()),
+                client_id: "dataplane".to_string(),
+                client_secret: SecretString::from("secret"),
+                scope: None,
+                leeway: 30,
+            }),
+            None,
+        )
+        .unwrap();
+
+        register(&client, &server).await;
+        register(&client, &server).await;
+
+        server.verify().await;
+    }
+
+    #[test]
+    fn client_cert_requires_key() {
+        let result = ControlPlaneClient::new(
+            None,
+            Some(TlsConfig {
+                client_cert: Some("cert.pem".into()),
+                ..Default::default()
+            }),
+        );
+
+        assert!(result.is_err());
+    }
+}
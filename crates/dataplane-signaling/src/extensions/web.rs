@@ -1,33 +1,42 @@
-use std::{
-    net::{IpAddr, Ipv4Addr},
-    sync::Arc,
-};
+use std::sync::Arc;
 
-use edc_dataplane_core::core::service::transfer::TransferService;
+use edc_dataplane_core::core::service::{
+    registry::{EndpointTypes, TransferManagers},
+    transfer::TransferService,
+};
 
 use edc_dataplane_core::web::{self, ServerHandle};
 use miwa::{
     core::{Extension, ExtensionConfig, MiwaContext, MiwaResult},
-    derive::{extension, ExtensionConfig},
+    derive::extension,
 };
-use serde::Deserialize;
 use tokio::sync::Mutex;
 
-use crate::web::state::Context;
+use crate::{
+    extensions::{
+        config::{ControlPlaneConfig, SignalingConfig},
+        registration::RegistrationStatus,
+    },
+    web::{auth::Authenticator, state::Context, validation::StartMessageValidator},
+};
 
 pub struct SignalingApiExtension {
-    cfg: SignalingApiConfig,
+    cfg: SignalingConfig,
     ctx: Context,
+    auth: Option<Authenticator>,
     handle: Arc<Mutex<Option<ServerHandle>>>,
 }
 
 impl SignalingApiExtension {
-    pub fn new(cfg: SignalingApiConfig, ctx: Context) -> Self {
-        SignalingApiExtension {
+    pub fn new(cfg: SignalingConfig, ctx: Context) -> anyhow::Result<Self> {
+        let auth = cfg.auth.clone().map(Authenticator::new).transpose()?;
+
+        Ok(SignalingApiExtension {
             cfg,
             ctx,
+            auth,
             handle: Arc::default(),
-        }
+        })
     }
 }
 
@@ -37,7 +46,7 @@ impl Extension for SignalingApiExtension {
         let handle = web::start_server(
             self.cfg.bind,
             self.cfg.port,
-            crate::web::signaling_app(),
+            crate::web::signaling_app(self.auth.clone()),
             self.ctx.clone(),
             "Signaling API",
         )
@@ -51,31 +60,231 @@ impl Extension for SignalingApiExtension {
     }
 }
 
-#[derive(Deserialize, ExtensionConfig, Clone)]
-#[config(prefix = "signaling")]
-pub struct SignalingApiConfig {
-    #[serde(default = "default_signaling_port")]
-    pub port: u16,
-    #[serde(default = "default_bind")]
-    pub bind: IpAddr,
+/// Validator of the start messages accepting the types advertised to any of the control
+/// planes, the ones advertised the types of the registered managers accept those
+fn validator(
+    control_planes: &[ControlPlaneConfig],
+    allowed_schemes: Vec<String>,
+    managers: TransferManagers,
+) -> StartMessageValidator {
+    let mut transfer_types = control_planes
+        .iter()
+        .flat_map(|control_plane| control_plane.transfer_types.iter().cloned())
+        .collect::<Vec<_>>();
+    transfer_types.sort();
+    transfer_types.dedup();
+
+    // A control plane without source types accepts any source, so the API does too
+    let source_types = if control_planes
+        .iter()
+        .any(|control_plane| control_plane.source_types.is_empty())
+    {
+        vec![]
+    } else {
+        let mut source_types = control_planes
+            .iter()
+            .flat_map(|control_plane| control_plane.source_types.iter().cloned())
+            .collect::<Vec<_>>();
+        source_types.sort();
+        source_types.dedup();
+        source_types
+    };
+
+    let validator = StartMessageValidator::new(transfer_types, source_types, allowed_schemes);
+
+    if control_planes
+        .iter()
+        .any(|control_plane| control_plane.transfer_types.is_empty())
+    {
+        validator.with_managers(managers)
+    } else {
+        validator
+    }
 }
 
 #[extension(name = "DataPlane Signaling API extension")]
 pub async fn signaling_api_extension(
     _ctx: &MiwaContext,
-    ExtensionConfig(cfg): ExtensionConfig<SignalingApiConfig>,
+    ExtensionConfig(cfg): ExtensionConfig<SignalingConfig>,
     transfer_service: TransferService,
+    registration: RegistrationStatus,
+    managers: TransferManagers,
+    endpoint_types: EndpointTypes,
 ) -> MiwaResult<SignalingApiExtension> {
+    let validator = validator(
+        &cfg.control_planes()?,
+        cfg.allowed_schemes.clone(),
+        managers,
+    )
+    .with_endpoint_types(endpoint_types);
+
     Ok(SignalingApiExtension::new(
         cfg,
-        Context::new(transfer_service),
-    ))
+        Context::new(transfer_service, validator, registration),
+    )?)
 }
 
-pub fn default_signaling_port() -> u16 {
-    8787
-}
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use edc_dataplane_core::{
+        core::{
+            model::{namespace::EDC_NAMESPACE, transfer::Transfer},
+            service::{
+                registry::TransferManagers,
+                transfer::{TransferManager, TransferManagerRef, TransferResult},
+            },
+        },
+        signaling::{DataAddress, DataFlowStartMessage, EndpointProperty, FlowType},
+    };
+
+    use crate::{extensions::config::ControlPlaneConfig, web::validation::StartMessageValidator};
+
+    use super::validator;
+
+    struct NoopManager;
 
-pub fn default_bind() -> IpAddr {
-    IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0))
+    #[async_trait::async_trait]
+    impl TransferManager for NoopManager {
+        async fn can_handle(&self, _transfer: &Transfer) -> TransferResult<bool> {
+            Ok(true)
+        }
+        async fn handle_start(&self, _transfer: &Transfer) -> TransferResult<Option<DataAddress>> {
+            Ok(None)
+        }
+        async fn handle_suspend(&self, _id: &str) -> TransferResult<()> {
+            Ok(())
+        }
+        async fn handle_terminate(&self, _id: &str) -> TransferResult<()> {
+            Ok(())
+        }
+    }
+
+    fn control_plane(
+        id: &str,
+        transfer_types: &[&str],
+        source_types: &[&str],
+    ) -> ControlPlaneConfig {
+        ControlPlaneConfig {
+            id: id.to_string(),
+            url: format!("http://{}.local", id),
+            signaling_url: "http://dataplane.local:8787/api/v1/dataflows".to_string(),
+            transfer_types: transfer_types.iter().map(|ty| ty.to_string()).collect(),
+            source_types: source_types.iter().map(|ty| ty.to_string()).collect(),
+            auth: None,
+            tls: None,
+        }
+    }
+
+    fn address(endpoint_type: &str) -> DataAddress {
+        DataAddress::builder()
+            .endpoint_type(endpoint_type.to_string())
+            .endpoint_properties(vec![EndpointProperty::builder()
+                .name(EDC_NAMESPACE.to_iri("baseUrl"))
+                .value("https://example.com")
+                .build()])
+            .build()
+    }
+
+    fn message(
+        flow_type: FlowType,
+        destination_type: &str,
+        source_type: &str,
+    ) -> DataFlowStartMessage {
+        DataFlowStartMessage::builder()
+            .participant_id("participant_id".to_string())
+            .process_id("process_id".to_string())
+            .agreement_id("agreement_id".to_string())
+            .dataset_id("dataset_id".to_string())
+            .flow_type(flow_type)
+            .transfer_type_destination(destination_type.to_string())
+            .source_data_address(address(source_type))
+            .destination_data_address(address("HttpData"))
+            .properties(HashMap::new())
+            .build()
+    }
+
+    /// Fields of the errors about the types of the message, the addresses being left aside
+    fn rejected_types(validator: &StartMessageValidator, msg: DataFlowStartMessage) -> Vec<String> {
+        validator
+            .validate(&msg)
+            .err()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|error| error.field)
+            .filter(|field| field == "transferType" || field == "sourceDataAddress.endpointType")
+            .collect()
+    }
+
+    #[test]
+    fn accepts_types_advertised_to_any_control_plane() {
+        let managers = TransferManagers::default();
+        managers
+            .register(
+                ["HttpData-PULL", "HttpData-PUSH"],
+                TransferManagerRef::of(NoopManager),
+            )
+            .unwrap();
+
+        let validator = validator(
+            &[
+                control_plane("any", &[], &[]),
+                control_plane("pull", &["HttpData-PULL"], &["HttpData"]),
+            ],
+            vec![],
+            managers,
+        );
+
+        assert_eq!(
+            rejected_types(&validator, message(FlowType::Pull, "HttpData", "HttpData")),
+            Vec::<String>::new()
+        );
+        // Advertised to the control plane without configured types only
+        assert_eq!(
+            rejected_types(&validator, message(FlowType::Push, "HttpData", "HttpData")),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            rejected_types(&validator, message(FlowType::Pull, "HttpData", "File")),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            rejected_types(&validator, message(FlowType::Push, "Kafka", "HttpData")),
+            vec!["transferType"]
+        );
+    }
+
+    #[test]
+    fn accepts_only_configured_types() {
+        let managers = TransferManagers::default();
+        managers
+            .register(
+                ["HttpData-PULL", "HttpData-PUSH"],
+                TransferManagerRef::of(NoopManager),
+            )
+            .unwrap();
+
+        let validator = validator(
+            &[
+                control_plane("pull", &["HttpData-PULL"], &["HttpData"]),
+                control_plane("file", &["File-PUSH"], &["HttpData"]),
+            ],
+            vec![],
+            managers,
+        );
+
+        assert_eq!(
+            rejected_types(&validator, message(FlowType::Pull, "HttpData", "HttpData")),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            rejected_types(&validator, message(FlowType::Push, "HttpData", "HttpData")),
+            vec!["transferType"]
+        );
+        assert_eq!(
+            rejected_types(&validator, message(FlowType::Pull, "HttpData", "File")),
+            vec!["sourceDataAddress.endpointType"]
+        );
+    }
 }
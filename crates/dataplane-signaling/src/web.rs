@@ -1,6 +1,8 @@
 mod api;
+pub mod auth;
 mod context;
 mod error;
 mod router;
 pub mod state;
+pub mod validation;
 pub use router::signaling_app;
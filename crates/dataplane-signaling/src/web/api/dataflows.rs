@@ -1,35 +1,94 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     Json,
 };
+use axum_extra::extract::WithRejection;
+use chrono::{DateTime, Utc};
 use edc_dataplane_core::{
-    core::service::transfer::TransferService,
+    core::{
+        db::transfer::{SortOrder, TransferQuery, TransferSortField},
+        model::transfer::TransferStatus,
+        service::transfer::{TransferError, TransferService},
+    },
     signaling::{
-        DataFlowResponseMessage, DataFlowStartMessage, DataFlowSuspendMessage,
+        DataFlowPrepareMessage, DataFlowResponseMessage, DataFlowStartMessage,
+        DataFlowStartedNotificationMessage, DataFlowStatusMessage, DataFlowSuspendMessage,
         DataFlowTerminateMessage,
     },
 };
+use serde::Deserialize;
 use serde_json::{json, Value};
 
-use crate::web::{context::WithContext, error::SignalingResult};
+use crate::{
+    extensions::registration::RegistrationStatus,
+    web::{
+        context::{JsonLd, WithContext},
+        error::{SignalingError, SignalingResult},
+        validation::{FieldError, StartMessageValidator},
+    },
+};
+
+pub async fn health_check(
+    State(registration): State<RegistrationStatus>,
+) -> SignalingResult<Json<Value>> {
+    // Degraded until every configured control plane knows the dataplane
+    let status = if registration.is_registered() {
+        "ok"
+    } else {
+        "degraded"
+    };
 
-pub async fn health_check() -> SignalingResult<Json<Value>> {
-    Ok(Json(json!({"status": "ok"})))
+    Ok(Json(
+        json!({"status": status, "registration": registration.all()}),
+    ))
 }
 
 pub async fn init_flow(
     State(manager): State<TransferService>,
-    Json(flow): Json<DataFlowStartMessage>,
+    State(validator): State<StartMessageValidator>,
+    JsonLd(flow): JsonLd<DataFlowStartMessage>,
 ) -> SignalingResult<Json<WithContext<DataFlowResponseMessage>>> {
+    validator
+        .validate(&flow)
+        .map_err(SignalingError::Validation)?;
+
     let response = manager.start(flow).await?;
 
     Ok(Json(WithContext::builder(response).build()?))
 }
 
+pub async fn prepare_flow(
+    State(manager): State<TransferService>,
+    JsonLd(flow): JsonLd<DataFlowPrepareMessage>,
+) -> SignalingResult<Json<WithContext<DataFlowResponseMessage>>> {
+    let response = manager.prepare(flow).await?;
+
+    Ok(Json(WithContext::builder(response).build()?))
+}
+
+pub async fn started_flow(
+    State(manager): State<TransferService>,
+    Path(id): Path<String>,
+    JsonLd(msg): JsonLd<DataFlowStartedNotificationMessage>,
+) -> SignalingResult<()> {
+    manager.started(id, msg.data_address).await?;
+
+    Ok(())
+}
+
+pub async fn complete_flow(
+    State(manager): State<TransferService>,
+    Path(id): Path<String>,
+) -> SignalingResult<()> {
+    manager.complete(id).await?;
+
+    Ok(())
+}
+
 pub async fn terminate_flow(
     State(manager): State<TransferService>,
     Path(id): Path<String>,
-    Json(msg): Json<DataFlowTerminateMessage>,
+    JsonLd(msg): JsonLd<DataFlowTerminateMessage>,
 ) -> SignalingResult<()> {
     manager.terminate(id, msg.reason).await?;
 
@@ -39,9 +98,104 @@ pub async fn terminate_flow(
 pub async fn suspend_flow(
     State(manager): State<TransferService>,
     Path(id): Path<String>,
-    Json(_msg): Json<DataFlowSuspendMessage>,
+    JsonLd(msg): JsonLd<DataFlowSuspendMessage>,
 ) -> SignalingResult<()> {
-    manager.suspend(id).await?;
+    manager.suspend(id, msg.reason).await?;
 
     Ok(())
 }
+
+pub async fn get_flow(
+    State(manager): State<TransferService>,
+    Path(id): Path<String>,
+) -> SignalingResult<Json<WithContext<DataFlowStatusMessage>>> {
+    let transfer = manager.get(&id).await?.ok_or(TransferError::NotFound(id))?;
+
+    Ok(Json(
+        WithContext::builder(DataFlowStatusMessage::from(transfer)).build()?,
+    ))
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DataFlowQueryParams {
+    participant_id: Option<String>,
+    status: Option<TransferStatus>,
+    agreement_id: Option<String>,
+    dataset_id: Option<String>,
+    transfer_type: Option<String>,
+    created_after: Option<DateTime<Utc>>,
+    created_before: Option<DateTime<Utc>>,
+    updated_after: Option<DateTime<Utc>>,
+    updated_before: Option<DateTime<Utc>>,
+    sort_by: Option<TransferSortField>,
+    sort_order: Option<SortOrder>,
+    /// Page size, at most [`MAX_LIMIT`]
+    limit: Option<i32>,
+    offset: Option<i32>,
+}
+
+/// Largest page returned by the query endpoint
+pub const MAX_LIMIT: i32 = 1000;
+
+impl DataFlowQueryParams {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = vec![];
+
+        if self
+            .limit
+            .is_some_and(|limit| !(1..=MAX_LIMIT).contains(&limit))
+        {
+            errors.push(FieldError::new(
+                "limit",
+                format!("Must be between 1 and {}", MAX_LIMIT),
+            ));
+        }
+
+        if self.offset.is_some_and(|offset| offset < 0) {
+            errors.push(FieldError::new("offset", "Must not be negative"));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+impl From<DataFlowQueryParams> for TransferQuery {
+    fn from(params: DataFlowQueryParams) -> Self {
+        TransferQuery::builder()
+            .maybe_participant_id(params.participant_id)
+            .maybe_status(params.status)
+            .maybe_agreement_id(params.agreement_id)
+            .maybe_dataset_id(params.dataset_id)
+            .maybe_transfer_type(params.transfer_type)
+            .maybe_created_after(params.created_after)
+            .maybe_created_before(params.created_before)
+            .maybe_updated_after(params.updated_after)
+            .maybe_updated_before(params.updated_before)
+            .maybe_sort_by(params.sort_by)
+            .maybe_sort_order(params.sort_order)
+            .maybe_limit(params.limit)
+            .maybe_offset(params.offset)
+            .build()
+    }
+}
+
+pub async fn list_flows(
+    State(manager): State<TransferService>,
+    WithRejection(Query(params), _): WithRejection<Query<DataFlowQueryParams>, SignalingError>,
+) -> SignalingResult<Json<Vec<WithContext<DataFlowStatusMessage>>>> {
+    params.validate().map_err(SignalingError::Validation)?;
+
+    let flows = manager
+        .query(params.into())
+        .await?
+        .into_iter()
+        .map(|transfer| WithContext::builder(DataFlowStatusMessage::from(transfer)).build())
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    Ok(Json(flows))
+}
@@ -0,0 +1,397 @@
+use std::{
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use axum::{
+    extract::{Request, State},
+    http::{header::AUTHORIZATION, HeaderMap},
+    middleware::Next,
+    response::Response,
+};
+use jsonwebtoken::{
+    jwk::{Jwk, JwkSet},
+    Algorithm, DecodingKey, Validation,
+};
+use secrecy::{ExposeSecret, SecretString};
+use serde::Deserialize;
+use serde_json::Value;
+use tokio::sync::RwLock;
+use tracing::warn;
+
+use super::error::{SignalingError, SignalingResult};
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct AuthConfig {
+    /// Leaves the health check reachable without credentials
+    #[serde(default)]
+    pub open_health_check: bool,
+    pub api_key: Option<ApiKeyConfig>,
+    pub jwt: Option<JwtConfig>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct ApiKeyConfig {
+    #[serde(default = "default_api_key_header")]
+    pub header: String,
+    pub key: SecretString,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct JwtConfig {
+    /// JWKS of the control plane used to verify the bearer tokens
+    pub jwks_url: String,
+    pub issuer: Option<String>,
+    pub audience: Option<String>,
+    #[serde(default = "default_leeway")]
+    pub leeway: u64,
+    /// Minimum delay in seconds between two fetches of the JWKS
+    #[serde(default = "default_jwks_refresh_interval")]
+    pub jwks_refresh_interval: u64,
+}
+
+#[derive(Clone)]
+pub struct Authenticator {
+    api_key: Option<ApiKeyConfig>,
+    jwt: Option<JwtValidator>,
+    open_health_check: bool,
+}
+
+impl Authenticator {
+    pub fn new(cfg: AuthConfig) -> anyhow::Result<Self> {
+        if cfg.api_key.is_none() && cfg.jwt.is_none() {
+            anyhow::bail!("Signaling auth requires at least one of `api_key` or `jwt`");
+        }
+
+        Ok(Self {
+            api_key: cfg.api_key,
+            jwt: cfg.jwt.map(JwtValidator::new),
+            open_health_check: cfg.open_health_check,
+        })
+    }
+
+    pub fn open_health_check(&self) -> bool {
+        self.open_health_check
+    }
+
+    pub async fn authenticate(&self, headers: &HeaderMap) -> anyhow::Result<()> {
+        if let Some(api_key) = &self.api_key {
+            if let Some(value) = headers.get(&api_key.header) {
+                if constant_time_eq(value.as_bytes(), api_key.key.expose_secret().as_bytes()) {
+                    return Ok(());
+                }
+                anyhow::bail!("Invalid API key");
+            }
+        }
+
+        if let Some(jwt) = &self.jwt {
+            if let Some(token) = bearer_token(headers) {
+                return jwt.validate(token).await;
+            }
+        }
+
+        anyhow::bail!("Missing credentials")
+    }
+}
+
+pub async fn authenticate(
+    State(auth): State<Authenticator>,
+    request: Request,
+    next: Next,
+) -> SignalingResult<Response> {
+    if let Err(err) = auth.authenticate(request.headers()).await {
+        warn!(
+            "Rejected signaling request {} {}: {:#}",
+            request.method(),
+            request.uri().path(),
+            err
+        );
+        return Err(SignalingError::Unauthorized);
+    }
+
+    Ok(next.run(request).await)
+}
+
+#[derive(Clone)]
+struct JwtValidator {
+    cfg: JwtConfig,
+    client: reqwest::Client,
+    jwks: Arc<RwLock<CachedJwks>>,
+}
+
+#[derive(Default)]
+struct CachedJwks {
+    keys: Vec<Jwk>,
+    fetched_at: Option<Instant>,
+}
+
+impl JwtValidator {
+    fn new(cfg: JwtConfig) -> Self {
+        Self {
+            cfg,
+            client: reqwest::Client::new(),
+            jwks: Arc::default(),
+        }
+    }
+
+    async fn validate(&self, token: &str) -> anyhow::Result<()> {
+        let header = jsonwebtoken::decode_header(token)?;
+        let kid = header
+            .kid
+            .ok_or_else(|| anyhow::anyhow!("Missing kid in token header"))?;
+
+        let jwk = match self.find_key(&kid).await {
+            Some(jwk) => jwk,
+            None => {
+                // The control plane may have rotated its keys
+                self.refresh().await?;
+                self.find_key(&kid)
+                    .await
+                    .ok_or_else(|| anyhow::anyhow!("Unknown kid {}", kid))?
+            }
+        };
+
+        if matches!(
+            header.alg,
+            Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512
+        ) {
+            anyhow::bail!("Unsupported algorithm {:?}", header.alg);
+        }
+
+        if let Some(alg) = &jwk.common.key_algorithm {
+            if Algorithm::from_str(&alg.to_string())? != header.alg {
+                anyhow::bail!("Algorithm {:?} does not match the key", header.alg);
+            }
+        }
+
+        let mut validation = Validation::new(header.alg);
+        validation.leeway = self.cfg.leeway;
+
+        match &self.cfg.audience {
+            Some(audience) => validation.set_audience(&[audience]),
+            None => validation.validate_aud = false,
+        }
+
+        if let Some(issuer) = &self.cfg.issuer {
+            validation.set_issuer(&[issuer]);
+        }
+
+        jsonwebtoken::decode::<Value>(token, &DecodingKey::from_jwk(&jwk)?, &validation)?;
+
+        Ok(())
+    }
+
+    async fn find_key(&self, kid: &str) -> Option<Jwk> {
+        self.jwks
+            .read()
+            .await
+            .keys
+            .iter()
+            .find(|jwk| jwk.common.key_id.as_deref() == Some(kid))
+            .cloned()
+    }
+
+    async fn refresh(&self) -> anyhow::Result<()> {
+        let mut jwks = self.jwks.write().await;
+
+        let interval = Duration::from_secs(self.cfg.jwks_refresh_interval);
+        if jwks
+            .fetched_at
+            .is_some_and(|fetched_at| fetched_at.elapsed() < interval)
+        {
+            return Ok(());
+        }
+
+        let keys = self
+            .client
+            .get(&self.cfg.jwks_url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<JwkSet>()
+            .await?
+            .keys;
+
+        *jwks = CachedJwks {
+            keys,
+            fetched_at: Some(Instant::now()),
+        };
+
+        Ok(())
+    }
+}
+
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+}
+
+fn constant_time_eq(left: &[u8], right: &[u8]) -> bool {
+    left.len() == right.len()
+        && left
+            .iter()
+            .zip(right)
+            .fold(0, |acc, (left, right)| acc | (left ^ right))
+            == 0
+}
+
+fn default_api_key_header() -> String {
+    "x-api-key".to_string()
+}
+
+fn default_leeway() -> u64 {
+    60
+}
+
+fn default_jwks_refresh_interval() -> u64 {
+    30
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{header::AUTHORIZATION, HeaderMap, HeaderName, HeaderValue};
+    use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+    use ed25519_compact::{KeyPair, Seed};
+    use jsonwebtoken::{get_current_timestamp, Algorithm, EncodingKey, Header};
+    use serde_json::json;
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    use super::{ApiKeyConfig, AuthConfig, Authenticator, JwtConfig};
+
+    fn api_key_config() -> ApiKeyConfig {
+        ApiKeyConfig {
+            header: "x-api-key".to_string(),
+            key: "secret".into(),
+        }
+    }
+
+    fn jwt_config(jwks_url: String) -> JwtConfig {
+        JwtConfig {
+            jwks_url,
+            issuer: Some("control-plane".to_string()),
+            audience: None,
+            leeway: 0,
+            jwks_refresh_interval: 0,
+        }
+    }
+
+    fn headers(name: HeaderName, value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(name, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    async fn jwks_server(key_pair: &KeyPair) -> MockServer {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/jwks.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "keys": [{
+                    "kty": "OKP",
+                    "crv": "Ed25519",
+                    "x": URL_SAFE_NO_PAD.encode(key_pair.pk.as_ref()),
+                    "use": "sig",
+                    "alg": "EdDSA",
+                    "kid": "kid"
+                }]
+            })))
+            .mount(&server)
+            .await;
+
+        server
+    }
+
+    fn sign(key_pair: &KeyPair, issuer: &str) -> String {
+        let mut header = Header::new(Algorithm::EdDSA);
+        header.kid = Some("kid".to_string());
+
+        jsonwebtoken::encode(
+            &header,
+            &json!({ "iss": issuer, "exp": get_current_timestamp() + 60 }),
+            &EncodingKey::from_ed_pem(key_pair.sk.to_pem().as_bytes()).unwrap(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn auth_requires_a_method() {
+        let cfg = AuthConfig {
+            open_health_check: true,
+            api_key: None,
+            jwt: None,
+        };
+
+        assert!(Authenticator::new(cfg).is_err());
+    }
+
+    #[tokio::test]
+    async fn api_key() {
+        let auth = Authenticator::new(AuthConfig {
+            open_health_check: false,
+            api_key: Some(api_key_config()),
+            jwt: None,
+        })
+        .unwrap();
+
+        assert!(auth
+            .authenticate(&headers(HeaderName::from_static("x-api-key"), "secret"))
+            .await
+            .is_ok());
+        assert!(auth
+            .authenticate(&headers(HeaderName::from_static("x-api-key"), "wrong"))
+            .await
+            .is_err());
+        assert!(auth.authenticate(&HeaderMap::new()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn jwt() {
+        let key_pair = KeyPair::from_seed(Seed::default());
+        let server = jwks_server(&key_pair).await;
+
+        let auth = Authenticator::new(AuthConfig {
+            open_health_check: false,
+            api_key: None,
+            jwt: Some(jwt_config(format!("{}/jwks.json", server.uri()))),
+        })
+        .unwrap();
+
+        let token = sign(&key_pair, "control-plane");
+
+        assert!(auth
+            .authenticate(&headers(AUTHORIZATION, &format!("Bearer {token}")))
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn jwt_rejected() {
+        let key_pair = KeyPair::from_seed(Seed::default());
+        let server = jwks_server(&key_pair).await;
+
+        let auth = Authenticator::new(AuthConfig {
+            open_health_check: false,
+            api_key: None,
+            jwt: Some(jwt_config(format!("{}/jwks.json", server.uri()))),
+        })
+        .unwrap();
+
+        let wrong_issuer = sign(&key_pair, "other");
+        let wrong_key = sign(&KeyPair::generate(), "control-plane");
+
+        for token in [wrong_issuer, wrong_key] {
+            assert!(auth
+                .authenticate(&headers(AUTHORIZATION, &format!("Bearer {token}")))
+                .await
+                .is_err());
+        }
+    }
+}
@@ -1,10 +1,17 @@
+use axum::{
+    async_trait,
+    extract::{FromRequest, Request},
+    Json,
+};
 use edc_dataplane_core::{
     core::model::namespace::{DSPACE_NAMESPACE, EDC_NAMESPACE},
-    signaling::DataFlowResponseMessage,
+    signaling::{jsonld, DataFlowResponseMessage, DataFlowStatusMessage},
 };
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::{json, Value};
 
+use super::error::SignalingError;
+
 #[derive(Deserialize, Serialize, Debug)]
 pub struct WithContext<T> {
     #[allow(dead_code)]
@@ -28,7 +35,35 @@ impl<T: TypedObject> WithContext<T> {
 }
 
 pub fn default_context() -> Value {
-    json!({ "@vocab": EDC_NAMESPACE.ns(), "dspace": DSPACE_NAMESPACE.ns() })
+    json!({
+        "@vocab": EDC_NAMESPACE.ns(),
+        "edc": EDC_NAMESPACE.ns(),
+        "dspace": DSPACE_NAMESPACE.ns()
+    })
+}
+
+/// JSON-LD body, expanded and compacted against [`default_context`] before being deserialized
+pub struct JsonLd<T>(pub T);
+
+#[async_trait]
+impl<T: DeserializeOwned, S: Send + Sync> FromRequest<S> for JsonLd<T> {
+    type Rejection = SignalingError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(document) = Json::<Value>::from_request(req, state).await?;
+
+        let compacted = jsonld::normalize(&document, &default_context())
+            .map_err(|err| SignalingError::BadRequest(err.to_string()))?;
+
+        serde_json::from_value(compacted)
+            .map(JsonLd)
+            .map_err(|err| {
+                SignalingError::BadRequest(format!(
+                    "Failed to deserialize the JSON body into the target type: {}",
+                    err
+                ))
+            })
+    }
 }
 
 pub struct ContextBuilder<T> {
@@ -60,3 +95,9 @@ impl TypedObject for DataFlowResponseMessage {
         "DataFlowResponseMessage"
     }
 }
+
+impl TypedObject for DataFlowStatusMessage {
+    fn get_type() -> &'static str {
+        "DataFlowStatusMessage"
+    }
+}
@@ -1,29 +1,128 @@
 use axum::{
+    extract::rejection::{JsonRejection, QueryRejection},
     response::{IntoResponse, Response},
     Json,
 };
+use edc_dataplane_core::core::service::transfer::TransferError;
 use reqwest::StatusCode;
-use serde_json::json;
+use serde::Serialize;
 use tracing::error;
 
+use super::{
+    context::{TypedObject, WithContext},
+    validation::FieldError,
+};
+
 pub type SignalingResult<T> = Result<T, SignalingError>;
 
 pub enum SignalingError {
     Generic(anyhow::Error),
+    Transfer(TransferError),
+    Unauthorized,
+    BadRequest(String),
+    Validation(Vec<FieldError>),
 }
 
-impl IntoResponse for SignalingError {
-    fn into_response(self) -> Response {
-        let (status, error_message) = match self {
-            SignalingError::Generic(e) => {
+/// Error body returned to the control plane
+#[derive(Serialize, Debug)]
+pub struct SignalingErrorMessage {
+    pub code: &'static str,
+    pub message: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<FieldError>,
+}
+
+impl TypedObject for SignalingErrorMessage {
+    fn get_type() -> &'static str {
+        "SignalingError"
+    }
+}
+
+impl SignalingError {
+    fn status_and_message(self) -> (StatusCode, SignalingErrorMessage) {
+        let mut errors = vec![];
+        let (status, code, message) = match self {
+            SignalingError::Generic(e) | SignalingError::Transfer(TransferError::Generic(e)) => {
                 error!("Internal server error: {:#}", e);
-                (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "InternalError",
+                    "Internal server error".to_string(),
+                )
+            }
+            SignalingError::Transfer(TransferError::Storage(e)) => {
+                error!("Storage failure: {:#}", e);
+                (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    "StorageFailure",
+                    "Storage unavailable".to_string(),
+                )
+            }
+            SignalingError::Unauthorized => (
+                StatusCode::UNAUTHORIZED,
+                "Unauthorized",
+                "Unauthorized".to_string(),
+            ),
+            SignalingError::Validation(fields) => {
+                errors = fields;
+                (
+                    StatusCode::BAD_REQUEST,
+                    "ValidationFailure",
+                    "Invalid request".to_string(),
+                )
+            }
+            SignalingError::BadRequest(message) => {
+                (StatusCode::BAD_REQUEST, "ValidationFailure", message)
+            }
+            SignalingError::Transfer(e @ TransferError::NotFound(_)) => {
+                (StatusCode::NOT_FOUND, "UnknownFlow", e.to_string())
+            }
+            SignalingError::Transfer(e @ TransferError::IllegalTransition { .. }) => {
+                (StatusCode::CONFLICT, "IllegalTransition", e.to_string())
+            }
+            SignalingError::Transfer(e @ TransferError::Conflict(_)) => {
+                (StatusCode::CONFLICT, "FlowConflict", e.to_string())
+            }
+            SignalingError::Transfer(e @ TransferError::Modified(_)) => {
+                (StatusCode::CONFLICT, "FlowModified", e.to_string())
+            }
+            SignalingError::Transfer(e @ TransferError::NotConsumer(_)) => {
+                (StatusCode::CONFLICT, "NotConsumerFlow", e.to_string())
+            }
+            SignalingError::Transfer(e @ TransferError::Unsupported) => (
+                StatusCode::BAD_REQUEST,
+                "UnsupportedTransfer",
+                e.to_string(),
+            ),
+            SignalingError::Transfer(e @ TransferError::UnsupportedEndpointType(_)) => (
+                StatusCode::BAD_REQUEST,
+                "UnsupportedEndpointType",
+                e.to_string(),
+            ),
+            SignalingError::Transfer(e @ TransferError::Validation(_)) => {
+                (StatusCode::BAD_REQUEST, "ValidationFailure", e.to_string())
             }
         };
-        let body = Json(json!({
-            "error": error_message,
-        }));
-        (status, body).into_response()
+
+        (
+            status,
+            SignalingErrorMessage {
+                code,
+                message,
+                errors,
+            },
+        )
+    }
+}
+
+impl IntoResponse for SignalingError {
+    fn into_response(self) -> Response {
+        let (status, message) = self.status_and_message();
+
+        match WithContext::builder(message).build() {
+            Ok(body) => (status, Json(body)).into_response(),
+            Err(_) => status.into_response(),
+        }
     }
 }
 
@@ -32,3 +131,122 @@ impl From<anyhow::Error> for SignalingError {
         SignalingError::Generic(value)
     }
 }
+
+impl From<TransferError> for SignalingError {
+    fn from(value: TransferError) -> Self {
+        SignalingError::Transfer(value)
+    }
+}
+
+impl From<JsonRejection> for SignalingError {
+    fn from(value: JsonRejection) -> Self {
+        SignalingError::BadRequest(value.body_text())
+    }
+}
+
+impl From<QueryRejection> for SignalingError {
+    fn from(value: QueryRejection) -> Self {
+        SignalingError::BadRequest(value.body_text())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::to_bytes, response::IntoResponse};
+    use edc_dataplane_core::core::{
+        model::transfer::TransferStatus, service::transfer::TransferError,
+    };
+    use reqwest::StatusCode;
+    use serde_json::Value;
+
+    use super::SignalingError;
+    use crate::web::validation::FieldError;
+
+    async fn response(error: SignalingError) -> (StatusCode, Value) {
+        let response = error.into_response();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn maps_transfer_errors() {
+        let cases = [
+            (
+                TransferError::UnsupportedEndpointType("Kafka".to_string()),
+                StatusCode::BAD_REQUEST,
+                "UnsupportedEndpointType",
+            ),
+            (
+                TransferError::Validation("Missing property baseUrl".to_string()),
+                StatusCode::BAD_REQUEST,
+                "ValidationFailure",
+            ),
+            (
+                TransferError::NotFound("1".to_string()),
+                StatusCode::NOT_FOUND,
+                "UnknownFlow",
+            ),
+            (
+                TransferError::IllegalTransition {
+                    id: "1".to_string(),
+                    from: TransferStatus::Terminated,
+                    to: TransferStatus::Started,
+                },
+                StatusCode::CONFLICT,
+                "IllegalTransition",
+            ),
+            (
+                TransferError::Conflict("1".to_string()),
+                StatusCode::CONFLICT,
+                "FlowConflict",
+            ),
+            (
+                TransferError::Modified("1".to_string()),
+                StatusCode::CONFLICT,
+                "FlowModified",
+            ),
+            (
+                TransferError::NotConsumer("1".to_string()),
+                StatusCode::CONFLICT,
+                "NotConsumerFlow",
+            ),
+            (
+                TransferError::Storage(anyhow::anyhow!("connection refused")),
+                StatusCode::SERVICE_UNAVAILABLE,
+                "StorageFailure",
+            ),
+        ];
+
+        for (error, status, code) in cases {
+            let (actual, body) = response(error.into()).await;
+
+            assert_eq!(actual, status);
+            assert_eq!(body["@type"], "SignalingError");
+            assert_eq!(body["code"], code);
+            assert!(body["@context"].is_object());
+        }
+    }
+
+    #[tokio::test]
+    async fn lists_field_errors() {
+        let (status, body) = response(SignalingError::Validation(vec![FieldError {
+            field: "sourceDataAddress.baseUrl".to_string(),
+            message: "Missing property".to_string(),
+        }]))
+        .await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["code"], "ValidationFailure");
+        assert_eq!(body["errors"][0]["field"], "sourceDataAddress.baseUrl");
+    }
+
+    #[tokio::test]
+    async fn hides_internal_errors() {
+        let (status, body) =
+            response(TransferError::Storage(anyhow::anyhow!("password=secret")).into()).await;
+
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["message"], "Storage unavailable");
+    }
+}
@@ -1,17 +1,388 @@
 use axum::{
+    middleware,
     routing::{get, post},
     Router,
 };
 
 use super::{
-    api::dataflows::{health_check, init_flow, suspend_flow, terminate_flow},
+    api::dataflows::{
+        complete_flow, get_flow, health_check, init_flow, list_flows, prepare_flow, started_flow,
+        suspend_flow, terminate_flow,
+    },
+    auth::{authenticate, Authenticator},
     state::Context,
 };
 
-pub fn signaling_app() -> Router<Context> {
-    Router::new()
-        .route("/api/v1/dataflows/check", get(health_check))
-        .route("/api/v1/dataflows", post(init_flow))
+pub fn signaling_app(auth: Option<Authenticator>) -> Router<Context> {
+    let health = Router::new().route("/api/v1/dataflows/check", get(health_check));
+
+    let api = Router::new()
+        .route("/api/v1/dataflows", post(init_flow).get(list_flows))
+        .route("/api/v1/dataflows/:id", get(get_flow))
         .route("/api/v1/dataflows/:id/terminate", post(terminate_flow))
         .route("/api/v1/dataflows/:id/suspend", post(suspend_flow))
+        .route("/api/v1/dataflows/prepare", post(prepare_flow))
+        .route("/api/v1/dataflows/:id/started", post(started_flow))
+        .route("/api/v1/dataflows/:id/completed", post(complete_flow));
+
+    let Some(auth) = auth else {
+        return health.merge(api);
+    };
+
+    let layer = middleware::from_fn_with_state(auth.clone(), authenticate);
+
+    let health = if auth.open_health_check() {
+        health
+    } else {
+        health.route_layer(layer.clone())
+    };
+
+    health.merge(api.route_layer(layer))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use edc_dataplane_core::{
+        core::{
+            db::{memory::transfer::InMemoryTransferRepo, transfer::TransferRepoRef},
+            model::transfer::Transfer,
+            service::transfer::{
+                TransferManager, TransferManagerRef, TransferResult, TransferService,
+            },
+        },
+        signaling::{
+            DataAddress, DataFlowPrepareMessage, DataFlowStartMessage,
+            DataFlowStartedNotificationMessage, FlowType,
+        },
+    };
+    use reqwest::StatusCode;
+    use serde_json::{json, Value};
+    use tokio::net::TcpListener;
+
+    use super::signaling_app;
+    use crate::{
+        extensions::registration::{RegistrationState, RegistrationStatus},
+        web::{
+            auth::{ApiKeyConfig, AuthConfig, Authenticator},
+            context::default_context,
+            state::Context,
+            validation::StartMessageValidator,
+        },
+    };
+
+    struct NoopManager;
+
+    #[async_trait::async_trait]
+    impl TransferManager for NoopManager {
+        async fn can_handle(&self, _transfer: &Transfer) -> TransferResult<bool> {
+            Ok(true)
+        }
+        async fn handle_start(&self, _transfer: &Transfer) -> TransferResult<Option<DataAddress>> {
+            Ok(None)
+        }
+        async fn handle_suspend(&self, _id: &str) -> TransferResult<()> {
+            Ok(())
+        }
+        async fn handle_terminate(&self, _id: &str) -> TransferResult<()> {
+            Ok(())
+        }
+    }
+
+    /// Serves the signaling API and returns its base url
+    async fn serve(auth: Option<Authenticator>) -> (String, TransferService) {
+        serve_with(auth, RegistrationStatus::default()).await
+    }
+
+    async fn serve_with(
+        auth: Option<Authenticator>,
+        registration: RegistrationStatus,
+    ) -> (String, TransferService) {
+        let service = TransferService::new(
+            TransferManagerRef::of(NoopManager),
+            TransferRepoRef::of(InMemoryTransferRepo::new()),
+        );
+
+        let ctx = Context::new(
+            service.clone(),
+            StartMessageValidator::default(),
+            registration,
+        );
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/api/v1/dataflows", listener.local_addr().unwrap());
+
+        tokio::spawn(async move {
+            axum::serve(listener, signaling_app(auth).with_state(ctx))
+                .await
+                .unwrap();
+        });
+
+        (url, service)
+    }
+
+    async fn start(service: &TransferService, id: &str, participant_id: &str) {
+        service
+            .start(
+                DataFlowStartMessage::builder()
+                    .participant_id(participant_id.to_string())
+                    .process_id(id.to_string())
+                    .source_data_address(
+                        DataAddress::builder()
+                            .endpoint_type("HttpData".to_string())
+                            .endpoint_properties(vec![])
+                            .build(),
+                    )
+                    .properties(HashMap::new())
+                    .flow_type(FlowType::Pull)
+                    .dataset_id("dataset_id".to_string())
+                    .agreement_id(format!("agreement-{}", id))
+                    .build(),
+            )
+            .await
+            .unwrap();
+    }
+
+    async fn list(url: &str, query: &str) -> (StatusCode, Value) {
+        let response = reqwest::get(format!("{}?{}", url, query)).await.unwrap();
+        (response.status(), response.json().await.unwrap())
+    }
+
+    fn process_ids(flows: &Value) -> Vec<&str> {
+        flows
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|flow| flow["processId"].as_str().unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn list_flows_filters_and_paginates() {
+        let (url, service) = serve(None).await;
+
+        start(&service, "1", "participant").await;
+        start(&service, "2", "participant").await;
+        start(&service, "3", "other").await;
+
+        let (status, flows) = list(&url, "limit=2").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(process_ids(&flows), vec!["1", "2"]);
+
+        let (_, flows) = list(&url, "limit=2&offset=2").await;
+        assert_eq!(process_ids(&flows), vec!["3"]);
+
+        let (_, flows) = list(&url, "participantId=other").await;
+        assert_eq!(process_ids(&flows), vec!["3"]);
+
+        let (_, flows) = list(&url, "sortOrder=desc&limit=1").await;
+        assert_eq!(process_ids(&flows), vec!["3"]);
+    }
+
+    #[tokio::test]
+    async fn list_flows_rejects_invalid_pagination() {
+        let (url, _) = serve(None).await;
+
+        for (query, field) in [
+            ("limit=0", "limit"),
+            ("limit=1001", "limit"),
+            ("offset=-1", "offset"),
+        ] {
+            let (status, body) = list(&url, query).await;
+
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{}", query);
+            assert_eq!(body["code"], "ValidationFailure");
+            assert_eq!(body["errors"][0]["field"], field);
+        }
+
+        let (status, _) = list(&url, "limit=many").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    fn api_key_auth(open_health_check: bool) -> Authenticator {
+        Authenticator::new(AuthConfig {
+            open_health_check,
+            api_key: Some(ApiKeyConfig {
+                header: "x-api-key".to_string(),
+                key: "secret".into(),
+            }),
+            jwt: None,
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn api_requires_credentials() {
+        let (url, _) = serve(Some(api_key_auth(true))).await;
+        let client = reqwest::Client::new();
+
+        let response = client.get(&url).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = client
+            .post(format!("{}/1/terminate", url))
+            .json(&serde_json::json!({}))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = client
+            .get(&url)
+            .header("x-api-key", "secret")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = client.get(format!("{}/check", url)).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn health_check_requires_credentials_unless_open() {
+        let (url, _) = serve(Some(api_key_auth(false))).await;
+        let client = reqwest::Client::new();
+
+        let response = client.get(format!("{}/check", url)).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = client
+            .get(format!("{}/check", url))
+            .header("x-api-key", "secret")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn health_check_is_degraded_until_registered() {
+        let registration = RegistrationStatus::default();
+        let (url, _) = serve_with(None, registration.clone()).await;
+
+        let health = || async {
+            reqwest::get(format!("{}/check", url))
+                .await
+                .unwrap()
+                .json::<Value>()
+                .await
+                .unwrap()
+        };
+
+        assert_eq!(health().await["status"], "degraded");
+
+        registration.set("first", RegistrationState::Unregistered);
+        registration.set(
+            "second",
+            RegistrationState::Registered {
+                at: chrono::Utc::now(),
+            },
+        );
+        assert_eq!(health().await["status"], "degraded");
+
+        registration.set(
+            "first",
+            RegistrationState::Failed {
+                error: "Connection refused".to_string(),
+                attempts: 1,
+            },
+        );
+        let body = health().await;
+        assert_eq!(body["status"], "degraded");
+        assert_eq!(body["registration"]["first"]["state"], "FAILED");
+
+        registration.set(
+            "first",
+            RegistrationState::Registered {
+                at: chrono::Utc::now(),
+            },
+        );
+        assert_eq!(health().await["status"], "ok");
+    }
+
+    async fn post<T: serde::Serialize>(url: String, msg: T) -> (StatusCode, Value) {
+        let mut body = serde_json::to_value(msg).unwrap();
+        body["@context"] = default_context();
+
+        let response = reqwest::Client::new()
+            .post(url)
+            .json(&body)
+            .send()
+            .await
+            .unwrap();
+
+        let status = response.status();
+        let body = response.text().await.unwrap();
+        (status, serde_json::from_str(&body).unwrap_or(Value::Null))
+    }
+
+    async fn state(url: &str, id: &str) -> Value {
+        reqwest::get(format!("{}/{}", url, id))
+            .await
+            .unwrap()
+            .json::<Value>()
+            .await
+            .unwrap()["state"]
+            .clone()
+    }
+
+    #[tokio::test]
+    async fn consumer_flow_is_prepared_started_and_completed() {
+        let (url, _) = serve(None).await;
+
+        let (status, body) = post(
+            format!("{}/prepare", url),
+            DataFlowPrepareMessage::builder()
+                .agreement_id("agreement_id".to_string())
+                .dataset_id("dataset_id".to_string())
+                .participant_id("participant".to_string())
+                .process_id("1".to_string())
+                .flow_type(FlowType::Pull)
+                .properties(HashMap::new())
+                .build(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["state"], "PREPARED");
+        assert_eq!(state(&url, "1").await, "PREPARED");
+
+        let (status, _) = post(
+            format!("{}/1/started", url),
+            DataFlowStartedNotificationMessage::default(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(state(&url, "1").await, "STARTED");
+
+        let (status, _) = post(format!("{}/1/completed", url), json!({})).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(state(&url, "1").await, "COMPLETED");
+    }
+
+    #[tokio::test]
+    async fn provider_flow_cannot_be_started_or_completed() {
+        let (url, service) = serve(None).await;
+
+        start(&service, "1", "participant").await;
+
+        let (status, body) = post(
+            format!("{}/1/started", url),
+            DataFlowStartedNotificationMessage::default(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body["code"], "NotConsumerFlow");
+
+        let (status, body) = post(format!("{}/1/completed", url), json!({})).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body["code"], "NotConsumerFlow");
+
+        assert_eq!(state(&url, "1").await, "STARTED");
+
+        let (status, body) = post(format!("{}/2/completed", url), json!({})).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["code"], "UnknownFlow");
+    }
 }
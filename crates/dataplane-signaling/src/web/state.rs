@@ -1,14 +1,27 @@
 use axum::extract::FromRef;
 use edc_dataplane_core::core::service::transfer::TransferService;
 
+use super::validation::StartMessageValidator;
+use crate::extensions::registration::RegistrationStatus;
+
 #[derive(Clone)]
 pub struct Context {
     transfer_manager: TransferService,
+    validator: StartMessageValidator,
+    registration: RegistrationStatus,
 }
 
 impl Context {
-    pub fn new(transfer_manager: TransferService) -> Self {
-        Self { transfer_manager }
+    pub fn new(
+        transfer_manager: TransferService,
+        validator: StartMessageValidator,
+        registration: RegistrationStatus,
+    ) -> Self {
+        Self {
+            transfer_manager,
+            validator,
+            registration,
+        }
     }
 
     pub fn transfer_manager(&self) -> &TransferService {
@@ -21,3 +34,15 @@ impl FromRef<Context> for TransferService {
         ctx.transfer_manager.clone()
     }
 }
+
+impl FromRef<Context> for StartMessageValidator {
+    fn from_ref(ctx: &Context) -> StartMessageValidator {
+        ctx.validator.clone()
+    }
+}
+
+impl FromRef<Context> for RegistrationStatus {
+    fn from_ref(ctx: &Context) -> RegistrationStatus {
+        ctx.registration.clone()
+    }
+}
@@ -0,0 +1,324 @@
+use edc_dataplane_core::{
+    core::{
+        model::{
+            namespace::{compact_iri, EDC_NAMESPACE},
+            transfer::types::DataAddressError,
+        },
+        service::registry::{EndpointTypes, TransferManagers},
+    },
+    signaling::{DataAddress, DataFlowStartMessage, FlowType},
+};
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub(crate) fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Checks incoming start messages against the types advertised to the control plane
+#[derive(Clone, Default)]
+pub struct StartMessageValidator {
+    transfer_types: Vec<String>,
+    source_types: Vec<String>,
+    allowed_schemes: Vec<String>,
+    managers: Option<TransferManagers>,
+    endpoint_types: EndpointTypes,
+}
+
+impl StartMessageValidator {
+    /// Empty lists disable the corresponding check
+    pub fn new(
+        transfer_types: Vec<String>,
+        source_types: Vec<String>,
+        allowed_schemes: Vec<String>,
+    ) -> Self {
+        Self {
+            transfer_types,
+            source_types,
+            allowed_schemes,
+            managers: None,
+            endpoint_types: EndpointTypes::default(),
+        }
+    }
+
+    /// Validates the data addresses with the given types instead of the default ones
+    pub fn with_endpoint_types(mut self, endpoint_types: EndpointTypes) -> Self {
+        self.endpoint_types = endpoint_types;
+        self
+    }
+
+    /// Also accepts the types of the registered managers, as advertised to the control planes
+    /// without configured transfer types
+    pub fn with_managers(mut self, managers: TransferManagers) -> Self {
+        self.managers = Some(managers);
+        self
+    }
+
+    pub fn validate(&self, msg: &DataFlowStartMessage) -> Result<(), Vec<FieldError>> {
+        let mut errors = vec![];
+
+        self.validate_transfer_type(msg, &mut errors);
+
+        let source_type = compact_iri(&msg.source_data_address.endpoint_type);
+        if !self.source_types.is_empty() && !self.source_types.iter().any(|t| t == source_type) {
+            errors.push(FieldError::new(
+                "sourceDataAddress.endpointType",
+                format!("Unsupported source type {}", source_type),
+            ));
+        } else {
+            self.validate_address("sourceDataAddress", &msg.source_data_address, &mut errors);
+        }
+
+        match (&msg.flow_type, &msg.destination_data_address) {
+            (FlowType::Push, None) => errors.push(FieldError::new(
+                "destinationDataAddress",
+                "Required for PUSH transfers",
+            )),
+            (FlowType::Push, Some(destination)) => {
+                self.validate_address("destinationDataAddress", destination, &mut errors)
+            }
+            (FlowType::Pull, _) => {}
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn validate_transfer_type(&self, msg: &DataFlowStartMessage, errors: &mut Vec<FieldError>) {
+        let mut transfer_types = self.transfer_types.clone();
+        if let Some(managers) = &self.managers {
+            transfer_types.extend(managers.types());
+        }
+
+        if transfer_types.is_empty() {
+            return;
+        }
+
+        let Some(transfer_type) = msg.transfer_type() else {
+            errors.push(FieldError::new(
+                "transferTypeDestination",
+                "Missing transfer type destination",
+            ));
+            return;
+        };
+
+        if !transfer_types.contains(&transfer_type) {
+            errors.push(FieldError::new(
+                "transferType",
+                format!("Unsupported transfer type {}", transfer_type),
+            ));
+        }
+    }
+
+    fn validate_address(&self, field: &str, address: &DataAddress, errors: &mut Vec<FieldError>) {
+        for error in self.endpoint_types.validate(address) {
+            errors.push(match error {
+                DataAddressError::UnsupportedType(kind) => FieldError::new(
+                    format!("{}.endpointType", field),
+                    format!("Unsupported endpoint type {}", kind),
+                ),
+                DataAddressError::MissingProperty(name) => {
+                    FieldError::new(format!("{}.{}", field, name), "Missing property")
+                }
+                DataAddressError::InvalidProperty { name, reason } => {
+                    FieldError::new(format!("{}.{}", field, name), reason)
+                }
+            });
+        }
+
+        let base_url = address.get_property(&EDC_NAMESPACE.to_iri("baseUrl"));
+        if let Some(scheme) = base_url.and_then(|url| url.split_once("://").map(|(s, _)| s)) {
+            if !self.allowed_schemes.is_empty()
+                && !self.allowed_schemes.iter().any(|allowed| allowed == scheme)
+            {
+                errors.push(FieldError::new(
+                    format!("{}.baseUrl", field),
+                    format!("Scheme {} is not allowed", scheme),
+                ));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use edc_dataplane_core::{
+        core::{
+            model::{namespace::EDC_NAMESPACE, transfer::Transfer},
+            service::{
+                registry::TransferManagers,
+                transfer::{TransferManager, TransferManagerRef, TransferResult},
+            },
+        },
+        signaling::{DataAddress, DataFlowStartMessage, EndpointProperty, FlowType},
+    };
+
+    use super::{FieldError, StartMessageValidator};
+
+    struct NoopManager;
+
+    #[async_trait::async_trait]
+    impl TransferManager for NoopManager {
+        async fn can_handle(&self, _transfer: &Transfer) -> TransferResult<bool> {
+            Ok(true)
+        }
+        async fn handle_start(&self, _transfer: &Transfer) -> TransferResult<Option<DataAddress>> {
+            Ok(None)
+        }
+        async fn handle_suspend(&self, _id: &str) -> TransferResult<()> {
+            Ok(())
+        }
+        async fn handle_terminate(&self, _id: &str) -> TransferResult<()> {
+            Ok(())
+        }
+    }
+
+    fn validator() -> StartMessageValidator {
+        StartMessageValidator::new(
+            vec!["HttpData-PULL".to_string(), "HttpData-PUSH".to_string()],
+            vec!["HttpData".to_string()],
+            vec!["https".to_string()],
+        )
+    }
+
+    fn address(endpoint_type: &str, properties: &[(&str, &str)]) -> DataAddress {
+        DataAddress::builder()
+            .endpoint_type(endpoint_type.to_string())
+            .endpoint_properties(
+                properties
+                    .iter()
+                    .map(|(name, value)| {
+                        EndpointProperty::builder()
+                            .name(EDC_NAMESPACE.to_iri(name))
+                            .value(*value)
+                            .build()
+                    })
+                    .collect(),
+            )
+            .build()
+    }
+
+    fn message(flow_type: FlowType, source: DataAddress) -> DataFlowStartMessage {
+        DataFlowStartMessage::builder()
+            .participant_id("participant_id".to_string())
+            .process_id("process_id".to_string())
+            .agreement_id("agreement_id".to_string())
+            .dataset_id("dataset_id".to_string())
+            .flow_type(flow_type)
+            .transfer_type_destination("HttpData".to_string())
+            .source_data_address(source)
+            .properties(HashMap::new())
+            .build()
+    }
+
+    fn fields(errors: Vec<FieldError>) -> Vec<String> {
+        errors.into_iter().map(|error| error.field).collect()
+    }
+
+    #[test]
+    fn valid_message() {
+        let msg = message(
+            FlowType::Pull,
+            address(
+                &EDC_NAMESPACE.to_iri("HttpData"),
+                &[("baseUrl", "https://example.com"), ("proxyPath", "true")],
+            ),
+        );
+
+        assert_eq!(validator().validate(&msg), Ok(()));
+    }
+
+    #[test]
+    fn unsupported_types() {
+        let mut msg = message(FlowType::Pull, address("AmazonS3", &[]));
+        msg.transfer_type_destination = Some("Kafka".to_string());
+
+        let errors = validator().validate(&msg).unwrap_err();
+
+        assert_eq!(
+            fields(errors),
+            vec!["transferType", "sourceDataAddress.endpointType"]
+        );
+    }
+
+    #[test]
+    fn invalid_properties() {
+        let msg = message(
+            FlowType::Push,
+            address(
+                "HttpData",
+                &[("baseUrl", "http://example.com"), ("proxyMethod", "yes")],
+            ),
+        );
+
+        let errors = validator().validate(&msg).unwrap_err();
+
+        assert_eq!(
+            fields(errors),
+            vec![
+                "sourceDataAddress.proxyMethod",
+                "sourceDataAddress.baseUrl",
+                "destinationDataAddress"
+            ]
+        );
+    }
+
+    #[test]
+    fn relative_base_url() {
+        let msg = message(
+            FlowType::Pull,
+            address("HttpData", &[("baseUrl", "/relative")]),
+        );
+
+        let errors = validator().validate(&msg).unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "sourceDataAddress.baseUrl");
+        assert_eq!(errors[0].message, "must be an absolute URL");
+    }
+
+    #[test]
+    fn transfer_types_of_registered_managers() {
+        let managers = TransferManagers::default();
+        let validator =
+            StartMessageValidator::new(vec![], vec![], vec![]).with_managers(managers.clone());
+
+        let mut msg = message(
+            FlowType::Pull,
+            address("HttpData", &[("baseUrl", "https://example.com")]),
+        );
+        msg.transfer_type_destination = None;
+        assert_eq!(validator.validate(&msg), Ok(()));
+
+        managers
+            .register(["HttpData-PULL"], TransferManagerRef::of(NoopManager))
+            .unwrap();
+
+        let errors = validator.validate(&msg).unwrap_err();
+        assert_eq!(fields(errors), vec!["transferTypeDestination"]);
+
+        msg.transfer_type_destination = Some(EDC_NAMESPACE.to_iri("HttpData"));
+        assert_eq!(validator.validate(&msg), Ok(()));
+
+        msg.flow_type = FlowType::Push;
+        msg.destination_data_address =
+            Some(address("HttpData", &[("baseUrl", "https://example.com")]));
+        let errors = validator.validate(&msg).unwrap_err();
+        assert_eq!(fields(errors), vec!["transferType"]);
+    }
+}
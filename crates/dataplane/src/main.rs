@@ -2,10 +2,15 @@ use miwa::core::Miwa;
 use tracing::info;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
-use edc_dataplane_core::extensions::{sql_repo_extension, transfer_service_extension};
+use edc_dataplane_core::extensions::{
+    endpoint_types_extension, notification_extension, reaper_extension, sql_repo_extension,
+    transfer_events_extension, transfer_managers_extension, transfer_service_extension,
+    webhook_extension,
+};
 use edc_dataplane_proxy::extensions::{
     proxy_api_extension, proxy_sql_repo_extension, transfer_proxy_extension,
 };
+use edc_dataplane_push::extensions::transfer_push_extension;
 use edc_dataplane_signaling::extensions::{registration_extension, signaling_api_extension};
 
 #[tokio::main]
@@ -21,9 +26,16 @@ async fn main() -> anyhow::Result<()> {
         .with_file(config_file)
         .build()?
         .add_extension(sql_repo_extension)
+        .add_extension(transfer_events_extension)
+        .add_extension(transfer_managers_extension)
+        .add_extension(endpoint_types_extension)
         .add_extension(proxy_sql_repo_extension)
         .add_extension(transfer_service_extension)
+        .add_extension(notification_extension)
+        .add_extension(webhook_extension)
+        .add_extension(reaper_extension)
         .add_extension(transfer_proxy_extension)
+        .add_extension(transfer_push_extension)
         .add_extension(registration_extension)
         .add_extension(signaling_api_extension)
         .add_extension(proxy_api_extension)